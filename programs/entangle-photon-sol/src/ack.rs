@@ -0,0 +1,62 @@
+//! The `ack` module reports the outcome of the executed operations back to their source chain.
+//!
+//! ## Overview
+//! The source chain requests an acknowledgement by setting the `Meta::FLAG_FAILURE_CALLBACK` flag of the
+//! operation and carrying the address of the protocol on the source chain in its `reserved` field. Once the
+//! operation is executed, or reported as failed by an executor, a `ProposeEvent` is emitted to the source
+//! chain on behalf of the protocol, so the acknowledgement is routed like any other proposal. It calls
+//! `handleOperationAck(bytes)` with the ABI encoded `(bytes32 op_hash, bool success, bytes return_data)`.
+//! The acknowledgements are sequenced by the proposal nonce of the protocol to the source chain, passed by
+//! the executor as the `ack_nonce` account.
+//!
+//! ## Failures
+//! A failed CPI reverts the whole transaction, so `execute_operation` can only acknowledge the successful
+//! executions. The failures are acknowledged by `report_operation_failure`, sent by the executor once it
+//! gives up on the operation. The executor only reports the operations whose execution is rejected by the
+//! program, not the ones failing for transient reasons, and the program only accepts the report once the
+//! operation has stayed signed for `FAILURE_REPORT_DELAY`.
+
+use anchor_lang::prelude::*;
+use ethabi::Token;
+
+use crate::{
+    error::CustomError,
+    protocol_data::{Meta, OperationData},
+    ProposeEvent, ProposeNonce,
+};
+
+const HANDLE_OPERATION_ACK_SELECTOR: &[u8] = &[0xd8, 0x7a, 0xec, 0xbf];
+
+/// Builds the acknowledgement of the operation outcome if the source chain requested it, the caller
+/// emits it. The acknowledgement is sequenced by the proposal nonce of the protocol to the source chain
+pub(super) fn acknowledgement(
+    op_hash: &[u8],
+    op_data: &OperationData,
+    ack_nonce: Option<&mut ProposeNonce>,
+    success: bool,
+    return_data: Vec<u8>,
+) -> Result<Option<ProposeEvent>> {
+    if !op_data.meta()?.has_flag(Meta::FLAG_FAILURE_CALLBACK) {
+        return Ok(None);
+    }
+    let ack_nonce = ack_nonce.ok_or(CustomError::AckNonceNotProvided)?;
+    let mut function_selector = vec![0_u8, 32];
+    function_selector.extend_from_slice(&ethabi::encode(&[Token::FixedBytes(
+        HANDLE_OPERATION_ACK_SELECTOR.to_vec(),
+    )]));
+    let params = ethabi::encode(&[Token::Tuple(vec![
+        Token::FixedBytes(op_hash.to_vec()),
+        Token::Bool(success),
+        Token::Bytes(return_data),
+    ])]);
+    Ok(Some(ProposeEvent {
+        protocol_id: op_data.protocol_id.clone(),
+        nonce: ack_nonce.next(),
+        dst_chain_id: op_data.src_chain_id,
+        protocol_address: op_data.reserved.clone(),
+        function_selector,
+        params,
+        fee: 0,
+        fee_mint: None,
+    }))
+}
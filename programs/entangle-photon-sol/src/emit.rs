@@ -0,0 +1,128 @@
+//! The `emit` module delivers the proposals and the operation lifecycle events.
+//!
+//! ## Overview
+//! By default the events are written into the program logs with `emit!`. RPC nodes may truncate or
+//! drop the logs, so with the `event-cpi` feature the events are emitted as self CPIs with Anchor's
+//! `emit_cpi!` instead. The event data then stays in the inner instructions of the transaction,
+//! from where the listener decodes it regardless of the logs.
+//!
+//! ## Accounts
+//! The self CPI is signed by the event authority PDA, so with the `event-cpi` feature the contexts
+//! emitting the events are extended by `#[event_cpi]` with the `event_authority` and `program`
+//! accounts. The governance proposals to the EOB are emitted into the logs in either mode.
+
+/// Emits the event in the mode selected by the `event-cpi` feature
+macro_rules! emit_event {
+    ($ctx:ident, $event:expr) => {{
+        #[cfg(feature = "event-cpi")]
+        {
+            // `emit_cpi!` expects the context to be named `ctx`
+            let ctx = &$ctx;
+            anchor_lang::prelude::emit_cpi!($event);
+        }
+        #[cfg(not(feature = "event-cpi"))]
+        anchor_lang::prelude::emit!($event);
+    }};
+}
+
+pub(crate) use emit_event;
+
+/// Seed of the PDA signing the event self CPIs, as set by `#[event_cpi]`
+#[cfg(feature = "event-cpi")]
+pub const EVENT_AUTHORITY_SEED: &[u8] = b"__event_authority";
+
+#[cfg(all(test, feature = "event-cpi"))]
+mod tests {
+    use super::*;
+    use crate::ProposalExecuted;
+    use anchor_lang::{
+        event::EVENT_IX_TAG_LE,
+        prelude::*,
+        solana_program::{entrypoint::ProgramResult, instruction::Instruction, program_stubs},
+        Event,
+    };
+    use std::sync::Mutex;
+
+    /// The self CPIs with their signer seeds captured by the syscall stubs
+    static INVOKED: Mutex<Vec<(Instruction, Vec<Vec<u8>>)>> = Mutex::new(vec![]);
+
+    struct CaptureStubs;
+
+    impl program_stubs::SyscallStubs for CaptureStubs {
+        fn sol_invoke_signed(
+            &self,
+            instruction: &Instruction,
+            _account_infos: &[AccountInfo],
+            signers_seeds: &[&[&[u8]]],
+        ) -> ProgramResult {
+            let seeds = signers_seeds[0].iter().map(|seed| seed.to_vec()).collect();
+            INVOKED.lock().unwrap().push((instruction.clone(), seeds));
+            Ok(())
+        }
+    }
+
+    #[event_cpi]
+    #[derive(Accounts)]
+    pub struct EmitEvent {}
+
+    fn emit(ctx: Context<EmitEvent>, event: ProposalExecuted) -> Result<()> {
+        emit_event!(ctx, event);
+        Ok(())
+    }
+
+    #[test]
+    fn test_emit_event_cpi() {
+        program_stubs::set_syscall_stubs(Box::new(CaptureStubs));
+        let (authority, bump) = Pubkey::find_program_address(&[EVENT_AUTHORITY_SEED], &crate::ID);
+        let (mut authority_lamports, mut program_lamports) = (0, 0);
+        let (mut authority_data, mut program_data) = (vec![], vec![]);
+        let owner = Pubkey::default();
+        let mut accounts = EmitEvent {
+            event_authority: AccountInfo::new(
+                &authority,
+                false,
+                false,
+                &mut authority_lamports,
+                &mut authority_data,
+                &owner,
+                false,
+                0,
+            ),
+            program: AccountInfo::new(
+                &crate::ID,
+                false,
+                false,
+                &mut program_lamports,
+                &mut program_data,
+                &owner,
+                true,
+                0,
+            ),
+        };
+        let event = ProposalExecuted {
+            op_hash: vec![1; 32],
+            executor: Pubkey::new_unique(),
+            return_data: vec![2, 3],
+        };
+        let event_data = event.data();
+        let ctx = Context::new(
+            &crate::ID,
+            &mut accounts,
+            &[],
+            EmitEventBumps {
+                event_authority: bump,
+            },
+        );
+        emit(ctx, event).unwrap();
+
+        let invoked = INVOKED.lock().unwrap();
+        let [(ix, seeds)] = &invoked[..] else {
+            panic!("Expected exactly one self CPI, got {}", invoked.len());
+        };
+        assert_eq!(ix.program_id, crate::ID);
+        assert_eq!(ix.accounts, vec![AccountMeta::new_readonly(authority, true)]);
+        assert_eq!(ix.data, [&EVENT_IX_TAG_LE[..], &event_data].concat());
+        let seeds: Vec<&[u8]> = seeds.iter().map(Vec::as_slice).collect();
+        assert_eq!(Pubkey::create_program_address(&seeds, &crate::ID).unwrap(), authority);
+    }
+}
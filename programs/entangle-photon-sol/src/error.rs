@@ -98,4 +98,66 @@ pub enum CustomError {
     ConsensusTargetRateTooHigh,
     #[msg("SelectorTooBig")]
     SelectorTooBig,
+    #[msg("TransmitterIsNotAllowed")]
+    TransmitterIsNotAllowed,
+    #[msg("InvalidTransmitterWeight")]
+    InvalidTransmitterWeight,
+    #[msg("ProtocolIsPaused")]
+    ProtocolIsPaused,
+    #[msg("IsNotGuardian")]
+    IsNotGuardian,
+    #[msg("GovCannotBePaused")]
+    GovCannotBePaused,
+    #[msg("GovChangeAlreadyQueued")]
+    GovChangeAlreadyQueued,
+    #[msg("GovChangeNotFound")]
+    GovChangeNotFound,
+    #[msg("GovChangeNotActive")]
+    GovChangeNotActive,
+    #[msg("GovCannotBeRemoved")]
+    GovCannotBeRemoved,
+    #[msg("RentRecipientNotProvided")]
+    RentRecipientNotProvided,
+    #[msg("DstChainIsNotAllowed")]
+    DstChainIsNotAllowed,
+    #[msg("InvalidProtocolAddressLength")]
+    InvalidProtocolAddressLength,
+    #[msg("ParamsTooBig")]
+    ParamsTooBig,
+    #[msg("ProposeRateLimitExceeded")]
+    ProposeRateLimitExceeded,
+    #[msg("MaxDstChainsExceeded")]
+    MaxDstChainsExceeded,
+    #[msg("FeeAccountsNotProvided")]
+    FeeAccountsNotProvided,
+    #[msg("InvalidFeeVaultTokenAccount")]
+    InvalidFeeVaultTokenAccount,
+    #[msg("InsufficientFees")]
+    InsufficientFees,
+    #[msg("OpAlreadyProcessed")]
+    OpAlreadyProcessed,
+    #[msg("InvalidProtocolId")]
+    InvalidProtocolId,
+    #[msg("UnsupportedMetaVersion")]
+    UnsupportedMetaVersion,
+    #[msg("InvalidSelectorType")]
+    InvalidSelectorType,
+    #[msg("InvalidSelectorLength")]
+    InvalidSelectorLength,
+    #[msg("InvalidSelectorName")]
+    InvalidSelectorName,
+    #[msg("OpParamsTooBig")]
+    OpParamsTooBig,
+    #[msg("AckNotRequested")]
+    AckNotRequested,
+    #[msg("AccountNotInLegacyLayout")]
+    AccountNotInLegacyLayout,
+    #[msg("ProposeWindowNotProvided")]
+    ProposeWindowNotProvided,
+    #[msg("AckNonceNotProvided")]
+    AckNonceNotProvided,
+    #[msg("FailureReportTooEarly")]
+    FailureReportTooEarly,
+    #[msg("ProtocolAlreadyInit")]
+    ProtocolAlreadyInit,
 }
@@ -0,0 +1,117 @@
+//! The `fee` module charges the optional proposal fees and lets the admin withdraw them.
+//!
+//! ## Overview
+//! Gov sets a per-protocol proposal fee with the `SetProposeFee` operation, charged either in lamports or
+//! in an SPL token. The fees are paid by `propose` into the protocol fee vault, a system account PDA derived
+//! from `[ROOT, b"FEE_VAULT", protocol_id]`. The lamport fees are kept in the vault itself, while the SPL token
+//! fees are kept in token accounts owned by the vault.
+//!
+//! ## Rent
+//! The vault must stay rent exempt, so the first lamport fee paid into it also covers its rent exemption and
+//! withdrawals always leave the rent exempt minimum in it.
+
+use anchor_lang::{prelude::*, system_program};
+use anchor_spl::token;
+
+use crate::{error::CustomError, photon::ROOT, FeesWithdrawn, Propose, WithdrawFees};
+
+/// Charges the protocol proposal fee if it is set, returns the charged fee and its mint
+pub(super) fn charge_propose_fee(accounts: &Propose) -> Result<(u64, Option<Pubkey>)> {
+    let (fee, fee_mint) = accounts.protocol_info.propose_fee();
+    if fee == 0 {
+        return Ok((0, None));
+    }
+    let (Some(fee_payer), Some(fee_vault)) = (&accounts.fee_payer, &accounts.fee_vault) else {
+        return Err(CustomError::FeeAccountsNotProvided.into());
+    };
+    match fee_mint {
+        None => {
+            let rent_exempt = Rent::get()?.minimum_balance(0);
+            let lamports = fee.saturating_add(rent_exempt.saturating_sub(fee_vault.lamports()));
+            let cpi_accounts = system_program::Transfer {
+                from: fee_payer.to_account_info(),
+                to: fee_vault.to_account_info(),
+            };
+            system_program::transfer(
+                CpiContext::new(accounts.system_program.to_account_info(), cpi_accounts),
+                lamports,
+            )?;
+        }
+        Some(mint) => {
+            let (Some(from), Some(to), Some(token_program)) = (
+                &accounts.fee_payer_token_account,
+                &accounts.fee_vault_token_account,
+                &accounts.token_program,
+            ) else {
+                return Err(CustomError::FeeAccountsNotProvided.into());
+            };
+            require!(
+                to.mint == mint && to.owner == fee_vault.key(),
+                CustomError::InvalidFeeVaultTokenAccount
+            );
+            let cpi_accounts = token::Transfer {
+                from: from.to_account_info(),
+                to: to.to_account_info(),
+                authority: fee_payer.to_account_info(),
+            };
+            token::transfer(CpiContext::new(token_program.to_account_info(), cpi_accounts), fee)?;
+        }
+    }
+    Ok((fee, fee_mint))
+}
+
+/// Withdraws the collected fees from the protocol fee vault to the recipient
+pub(super) fn withdraw_fees(
+    accounts: &WithdrawFees,
+    protocol_id: &[u8],
+    fee_vault_bump: u8,
+    amount: u64,
+) -> Result<()> {
+    let bump = [fee_vault_bump];
+    let vault_seeds = [ROOT, b"FEE_VAULT", protocol_id, &bump[..]];
+    let signer = &[&vault_seeds[..]][..];
+    let mint = match &accounts.fee_vault_token_account {
+        None => {
+            let rent_exempt = Rent::get()?.minimum_balance(0);
+            require!(
+                amount <= accounts.fee_vault.lamports().saturating_sub(rent_exempt),
+                CustomError::InsufficientFees
+            );
+            let cpi_accounts = system_program::Transfer {
+                from: accounts.fee_vault.to_account_info(),
+                to: accounts.recipient.to_account_info(),
+            };
+            system_program::transfer(
+                CpiContext::new_with_signer(
+                    accounts.system_program.to_account_info(),
+                    cpi_accounts,
+                    signer,
+                ),
+                amount,
+            )?;
+            None
+        }
+        Some(vault_token_account) => {
+            let token_program =
+                accounts.token_program.as_ref().ok_or(CustomError::FeeAccountsNotProvided)?;
+            require!(amount <= vault_token_account.amount, CustomError::InsufficientFees);
+            let cpi_accounts = token::Transfer {
+                from: vault_token_account.to_account_info(),
+                to: accounts.recipient.to_account_info(),
+                authority: accounts.fee_vault.to_account_info(),
+            };
+            token::transfer(
+                CpiContext::new_with_signer(token_program.to_account_info(), cpi_accounts, signer),
+                amount,
+            )?;
+            Some(vault_token_account.mint)
+        }
+    };
+    emit!(FeesWithdrawn {
+        protocol_id: protocol_id.to_vec(),
+        recipient: accounts.recipient.key(),
+        amount,
+        mint
+    });
+    Ok(())
+}
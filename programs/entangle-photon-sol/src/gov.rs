@@ -41,14 +41,18 @@
 use anchor_lang::prelude::*;
 use ethabi::{ethereum_types::U256, ParamType, Token};
 use num_enum::TryFromPrimitive;
+use sha3::{Digest, Keccak256};
 
 use crate::{
     error::CustomError,
     protocol_data::{FunctionSelector, OperationData, GOV_PROTOCOL_ID},
     require_ok,
-    util::EthAddress,
-    Config, ProposeEvent, ProtocolInfo, MAX_EXECUTORS, MAX_PROPOSERS, MAX_TRANSMITTERS,
-    RATE_DECIMALS, SOLANA_CHAIN_ID,
+    util::{close_account, realloc_account, EthAddress},
+    AllowedDstChainsUpdated, Config, ConsensusRateChanged, ExecutorAdded, ExecutorRemoved,
+    GovTimelockChanged, GuardianChanged, ProposeEvent, ProposeFeeChanged, ProposeRateLimitChanged,
+    ProposerAdded, ProposerRemoved, ProtocolAddressChanged, ProtocolInfo, ProtocolRemoved,
+    TransmitterWeightsUpdated, TransmittersUpdated, MAX_DST_CHAINS, MAX_EXECUTORS,
+    MAX_GOV_TIMELOCK, MAX_PROPOSERS, MAX_TRANSMITTERS, RATE_DECIMALS, SOLANA_CHAIN_ID,
 };
 
 /// Enumerates government operations with their corresponding unique operation codes,
@@ -56,7 +60,7 @@ use crate::{
 ///
 /// This approach enables clearer and more maintainable code by replacing arbitrary numerical codes
 /// with descriptive enum variants, each associated with a specific governance action.
-#[derive(TryFromPrimitive)]
+#[derive(TryFromPrimitive, Clone, Copy, PartialEq, Eq)]
 #[repr(u32)]
 pub enum GovOperation {
     AddAllowedProtocol = 0x45a004b9,
@@ -70,6 +74,42 @@ pub enum GovOperation {
     RemoveTransmitters = 0x5206da70,
     UpdateTransmitters = 0x654b46e1,
     SetConsensusTargetRate = 0x970b6109,
+    SetTransmitterWeights = 0x2b28eb2a,
+    SetProtocolPaused = 0x6fa92b39,
+    SetGlobalPaused = 0x5a01ef28,
+    SetGuardian = 0x062a8a55,
+    SetGovTimelock = 0x1e9e1ec7,
+    CancelGovChange = 0xdf107031,
+    RemoveAllowedProtocol = 0xe6acf3e8,
+    AddAllowedDstChains = 0xe390ac88,
+    RemoveAllowedDstChains = 0xf59219a7,
+    SetProposeRateLimit = 0x84579a53,
+    SetProposeFee = 0x5688e481,
+}
+
+impl GovOperation {
+    /// Sensitive operations are queued as pending changes while the gov timelock is enabled.
+    ///
+    /// The rest are applied at once: `AddAllowedProtocol` only sets up a protocol that is not
+    /// initialized yet, executors only relay operations the transmitters have signed, the
+    /// removals and pauses are to be taken in emergencies, and the propose fee, rate limit and
+    /// destination chains only bound what the allowed proposers can do.
+    pub fn is_timelocked(&self) -> bool {
+        matches!(
+            self,
+            GovOperation::AddAllowedProtocolAddress
+                | GovOperation::RemoveAllowedProtocolAddress
+                | GovOperation::AddAllowedProposerAddress
+                | GovOperation::AddTransmitters
+                | GovOperation::RemoveTransmitters
+                | GovOperation::UpdateTransmitters
+                | GovOperation::SetConsensusTargetRate
+                | GovOperation::SetTransmitterWeights
+                | GovOperation::SetGuardian
+                | GovOperation::SetGovTimelock
+                | GovOperation::RemoveAllowedProtocol
+        )
+    }
 }
 
 const U32_SIZE: usize = 4;
@@ -77,21 +117,26 @@ const HANDLE_ADD_ALLOWED_PROTOCOL_SELECTOR: &[u8] = &[
     0xba, 0x96, 0x6e, 0x5f, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
     0, 0, 0, 0,
 ];
+const HANDLE_REMOVE_ALLOWED_PROTOCOL_SELECTOR: &[u8] = &[
+    0xa4, 0x1e, 0xfb, 0x1a, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+    0, 0, 0, 0,
+];
 
-pub(super) fn handle_gov_operation(
-    config: &mut Config,
-    target_protocol_info: &mut ProtocolInfo,
-    code: Vec<u8>,
-    op_data: &OperationData,
-) -> Result<()> {
+pub(super) fn parse_gov_operation(code: &[u8]) -> Result<GovOperation> {
     if code.len() < U32_SIZE {
         return Err(CustomError::InvalidMethodSelector.into());
     }
     let selector_u32 = u32::from_be_bytes(code[..U32_SIZE].try_into().expect("Checked above"));
 
-    let gov_operation =
-        require_ok!(GovOperation::try_from(selector_u32), CustomError::InvalidMethodSelector);
+    Ok(require_ok!(GovOperation::try_from(selector_u32), CustomError::InvalidMethodSelector))
+}
 
+pub(super) fn handle_gov_operation(
+    config: &mut Config,
+    target_protocol_info: &mut ProtocolInfo,
+    gov_operation: GovOperation,
+    op_data: &OperationData,
+) -> Result<()> {
     let calldata = &op_data.params;
     match gov_operation {
         GovOperation::AddAllowedProtocol => {
@@ -101,7 +146,7 @@ pub(super) fn handle_gov_operation(
             add_allowed_protocol_address(calldata, target_protocol_info)?
         }
         GovOperation::RemoveAllowedProtocolAddress => {
-            remove_allowed_protocol_address(target_protocol_info)
+            remove_allowed_protocol_address(calldata, target_protocol_info)?
         }
         GovOperation::AddAllowedProposerAddress => {
             add_allowed_proposer_address(calldata, target_protocol_info)?
@@ -119,10 +164,54 @@ pub(super) fn handle_gov_operation(
         GovOperation::SetConsensusTargetRate => {
             set_consensus_target_rate(calldata, target_protocol_info)?
         }
+        GovOperation::SetTransmitterWeights => {
+            set_transmitter_weights(calldata, target_protocol_info)?
+        }
+        GovOperation::SetProtocolPaused => set_protocol_paused(calldata, target_protocol_info)?,
+        GovOperation::SetGlobalPaused => set_global_paused(calldata, config)?,
+        GovOperation::SetGuardian => set_guardian(calldata, config)?,
+        GovOperation::SetGovTimelock => set_gov_timelock(calldata, config)?,
+        GovOperation::RemoveAllowedProtocol => {
+            remove_allowed_protocol(calldata, target_protocol_info, config)?
+        }
+        GovOperation::AddAllowedDstChains => {
+            add_allowed_dst_chains(calldata, target_protocol_info)?
+        }
+        GovOperation::RemoveAllowedDstChains => {
+            remove_allowed_dst_chains(calldata, target_protocol_info)?
+        }
+        GovOperation::SetProposeRateLimit => {
+            set_propose_rate_limit(calldata, target_protocol_info)?
+        }
+        GovOperation::SetProposeFee => set_propose_fee(calldata, target_protocol_info)?,
+        // Cancelling needs the pending change account, so it is handled by `receive_photon_msg`
+        GovOperation::CancelGovChange => return Err(CustomError::InvalidMethodSelector.into()),
     }
     Ok(())
 }
 
+/// Resizes the target protocol account to fit the applied change, or closes it if the protocol was
+/// removed, sending its rent to the recipient passed within the remaining accounts
+pub(super) fn finalize_target_protocol<'info>(
+    gov_operation: GovOperation,
+    op_data: &OperationData,
+    target_protocol_info: &Account<'info, ProtocolInfo>,
+    payer: &AccountInfo<'info>,
+    system_program: &AccountInfo<'info>,
+    remaining_accounts: &[AccountInfo<'info>],
+) -> Result<()> {
+    let target = target_protocol_info.to_account_info();
+    if gov_operation == GovOperation::RemoveAllowedProtocol {
+        let recipient_key = rent_recipient(&op_data.params)?;
+        let recipient = remaining_accounts
+            .iter()
+            .find(|x| x.key() == recipient_key && x.is_writable && x.key() != target.key())
+            .ok_or(CustomError::RentRecipientNotProvided)?;
+        return close_account(&target, recipient);
+    }
+    realloc_account(&target, payer, system_program, target_protocol_info.space())
+}
+
 pub(super) fn add_allowed_protocol(
     calldata: &[u8],
     target_protocol_info: &mut ProtocolInfo,
@@ -149,23 +238,44 @@ pub(super) fn add_allowed_protocol(
         .into_iter()
         .map(|x| x.into_address().expect("always address"))
         .collect();
+    if transmitters.len() > MAX_TRANSMITTERS {
+        return Err(CustomError::MaxTransmittersExceeded.into());
+    }
+    // The transmitters and the rate of an existing protocol are changed by the timelocked operations
+    require!(!target_protocol_info.is_init, CustomError::ProtocolAlreadyInit);
+    let protocol_id = protocol_id_param(&params)?;
+    let old_rate = target_protocol_info.consensus_target_rate;
+    let old_transmitters = target_protocol_info.transmitters.clone();
+    target_protocol_info.version = ProtocolInfo::VERSION;
     target_protocol_info.is_init = true;
     target_protocol_info.consensus_target_rate = consensus_target_rate;
-    for (i, k) in transmitters.into_iter().enumerate() {
-        target_protocol_info.transmitters[i] = k.into();
-    }
+    target_protocol_info.set_transmitters(transmitters.into_iter().map(|k| k.into()).collect());
+    emit!(ConsensusRateChanged {
+        protocol_id: protocol_id.clone(),
+        old_rate,
+        new_rate: consensus_target_rate
+    });
+    emit!(TransmittersUpdated {
+        protocol_id,
+        old_transmitters,
+        new_transmitters: target_protocol_info.transmitters.clone()
+    });
     propose_handle_add_allowed_protocol(params, config)?;
     Ok(())
 }
 
 fn propose_handle_add_allowed_protocol(params: Vec<Token>, config: &mut Config) -> Result<()> {
+    let protocol_id = params[0].clone().into_fixed_bytes().ok_or(CustomError::InvalidGovMsg)?;
+    propose_to_eob(HANDLE_ADD_ALLOWED_PROTOCOL_SELECTOR, protocol_id, config);
+    Ok(())
+}
+
+/// Notifies the EOB master smart contract about a protocol registry change on Solana
+fn propose_to_eob(selector: &[u8], protocol_id: Vec<u8>, config: &mut Config) {
     let nonce = config.nonce;
     config.nonce += 1;
     let mut function_selector = vec![0_u8, 32];
-    function_selector.extend_from_slice(&ethabi::encode(&[Token::FixedBytes(
-        HANDLE_ADD_ALLOWED_PROTOCOL_SELECTOR.to_vec(),
-    )]));
-    let protocol_id = params[0].clone().into_fixed_bytes().ok_or(CustomError::InvalidGovMsg)?;
+    function_selector.extend_from_slice(&ethabi::encode(&[Token::FixedBytes(selector.to_vec())]));
     let params = ethabi::encode(&[Token::Tuple(vec![
         Token::FixedBytes(protocol_id),
         Token::Uint(U256::from(SOLANA_CHAIN_ID)),
@@ -176,11 +286,46 @@ fn propose_handle_add_allowed_protocol(params: Vec<Token>, config: &mut Config)
         dst_chain_id: config.eob_chain_id as u128,
         protocol_address: config.eob_master_smart_contract.to_vec(),
         function_selector,
-        params
+        params,
+        fee: 0,
+        fee_mint: None
+    });
+}
+
+/// Deregisters the protocol, the emptied account is closed by the caller
+fn remove_allowed_protocol(
+    calldata: &[u8],
+    target_protocol_info: &mut ProtocolInfo,
+    config: &mut Config,
+) -> Result<()> {
+    let params =
+        decode_abi_params(calldata, abi_decode_scheme(GovOperation::RemoveAllowedProtocol))?;
+    let protocol_id = protocol_id_param(&params)?;
+    require!(protocol_id != GOV_PROTOCOL_ID, CustomError::GovCannotBeRemoved);
+    require!(target_protocol_info.is_init, CustomError::ProtocolNotInit);
+    *target_protocol_info = ProtocolInfo::default();
+    emit!(ProtocolRemoved {
+        protocol_id: protocol_id.clone(),
+        rent_recipient: rent_recipient(calldata)?
     });
+    propose_to_eob(HANDLE_REMOVE_ALLOWED_PROTOCOL_SELECTOR, protocol_id, config);
     Ok(())
 }
 
+/// Decodes the account to send the rent of a removed protocol to
+fn rent_recipient(calldata: &[u8]) -> Result<Pubkey> {
+    let params =
+        decode_abi_params(calldata, abi_decode_scheme(GovOperation::RemoveAllowedProtocol))?;
+    Ok(Pubkey::new_from_array(
+        params[1]
+            .clone()
+            .into_bytes()
+            .ok_or(CustomError::InvalidGovMsg)?
+            .try_into()
+            .map_err(|_| CustomError::InvalidGovMsg)?,
+    ))
+}
+
 fn add_allowed_protocol_address(calldata: &[u8], protocol_info: &mut ProtocolInfo) -> Result<()> {
     let params = decode_abi_params(
         calldata,
@@ -190,14 +335,31 @@ fn add_allowed_protocol_address(calldata: &[u8], protocol_info: &mut ProtocolInf
         ]),
     )?;
     let protocol_address = params[1].clone().into_bytes().ok_or(CustomError::InvalidGovMsg)?;
-    protocol_info.protocol_address = Pubkey::new_from_array(
+    let new_address = Pubkey::new_from_array(
         protocol_address.try_into().map_err(|_| CustomError::InvalidGovMsg)?,
     );
+    emit!(ProtocolAddressChanged {
+        protocol_id: protocol_id_param(&params)?,
+        old_address: protocol_info.protocol_address,
+        new_address
+    });
+    protocol_info.protocol_address = new_address;
     Ok(())
 }
 
-fn remove_allowed_protocol_address(target_protocol_info: &mut ProtocolInfo) {
+fn remove_allowed_protocol_address(
+    calldata: &[u8],
+    target_protocol_info: &mut ProtocolInfo,
+) -> Result<()> {
+    let params =
+        decode_abi_params(calldata, abi_decode_scheme(GovOperation::RemoveAllowedProtocolAddress))?;
+    emit!(ProtocolAddressChanged {
+        protocol_id: protocol_id_param(&params)?,
+        old_address: target_protocol_info.protocol_address,
+        new_address: Pubkey::default()
+    });
     target_protocol_info.protocol_address = Pubkey::default();
+    Ok(())
 }
 
 fn add_allowed_proposer_address(
@@ -220,26 +382,22 @@ fn add_allowed_proposer_address(
             .try_into()
             .map_err(|_| CustomError::InvalidGovMsg)?,
     );
-    let mut proposers: Vec<_> = target_protocol_info.proposers();
-
     if proposer == Pubkey::default() {
         return Err(CustomError::InvalidProposerAddress.into());
     }
 
-    if proposers.contains(&proposer) {
-        return Err(CustomError::ProposerIsAlreadyAllowed.into());
-    }
-
-    if proposers.len() >= MAX_PROPOSERS {
+    if target_protocol_info.proposers.len() >= MAX_PROPOSERS {
         return Err(CustomError::MaxProposersExceeded.into());
     }
 
-    proposers.push(proposer);
-    target_protocol_info.proposers = Default::default();
-    for (i, k) in proposers.into_iter().enumerate() {
-        target_protocol_info.proposers[i] = k;
+    if !insert_sorted(&mut target_protocol_info.proposers, proposer) {
+        return Err(CustomError::ProposerIsAlreadyAllowed.into());
     }
 
+    emit!(ProposerAdded {
+        protocol_id: protocol_id_param(&params)?,
+        proposer
+    });
     Ok(())
 }
 
@@ -263,11 +421,12 @@ fn remove_allowed_proposer_address(
             .try_into()
             .map_err(|_| CustomError::InvalidGovMsg)?,
     );
-    let proposers: Vec<_> =
-        target_protocol_info.proposers().into_iter().filter(|x| x != &proposer).collect();
-    target_protocol_info.proposers = Default::default();
-    for (i, k) in proposers.into_iter().enumerate() {
-        target_protocol_info.proposers[i] = k;
+    if let Ok(pos) = target_protocol_info.proposers.binary_search(&proposer) {
+        target_protocol_info.proposers.remove(pos);
+        emit!(ProposerRemoved {
+            protocol_id: protocol_id_param(&params)?,
+            proposer
+        });
     }
     Ok(())
 }
@@ -294,19 +453,16 @@ fn add_executor(calldata: &[u8], target_protocol_info: &mut ProtocolInfo) -> Res
         return Err(CustomError::InvalidExecutorAddress.into());
     }
 
-    let mut executors: Vec<_> = target_protocol_info.executors();
-    if executors.contains(&executor) {
-        return Err(CustomError::ExecutorIsAlreadyAllowed.into());
-    }
-    if executors.len() >= MAX_EXECUTORS {
+    if target_protocol_info.executors.len() >= MAX_EXECUTORS {
         return Err(CustomError::MaxExecutorsExceeded.into());
     }
-
-    executors.push(executor);
-    target_protocol_info.executors = Default::default();
-    for (i, k) in executors.into_iter().enumerate() {
-        target_protocol_info.executors[i] = k;
+    if !insert_sorted(&mut target_protocol_info.executors, executor) {
+        return Err(CustomError::ExecutorIsAlreadyAllowed.into());
     }
+    emit!(ExecutorAdded {
+        protocol_id: protocol_id_param(&params)?,
+        executor
+    });
     Ok(())
 }
 
@@ -331,16 +487,16 @@ fn remove_executor(
             .try_into()
             .map_err(|_| CustomError::InvalidGovMsg)?,
     );
-    let executors: Vec<_> =
-        target_protocol_info.executors().into_iter().filter(|x| x != &executor).collect();
-
-    if executors.is_empty() && protocol_id == GOV_PROTOCOL_ID {
-        return Err(CustomError::TryingToRemoveLastGovExecutor.into());
+    if let Ok(pos) = target_protocol_info.executors.binary_search(&executor) {
+        target_protocol_info.executors.remove(pos);
+        emit!(ExecutorRemoved {
+            protocol_id: protocol_id_param(&params)?,
+            executor
+        });
     }
 
-    target_protocol_info.executors = Default::default();
-    for (i, k) in executors.into_iter().enumerate() {
-        target_protocol_info.executors[i] = k;
+    if target_protocol_info.executors.is_empty() && protocol_id == GOV_PROTOCOL_ID {
+        return Err(CustomError::TryingToRemoveLastGovExecutor.into());
     }
     Ok(())
 }
@@ -349,7 +505,10 @@ fn add_transmitters(calldata: &[u8], target_protocol_info: &mut ProtocolInfo) ->
     let params = decode_abi_params(calldata, abi_decode_scheme(GovOperation::AddTransmitters))?;
     let transmitters = get_transmitters_to_add(&params[1])?;
     require!(!transmitters.is_empty(), CustomError::NoTransmittersAllowed);
-    add_transmitters_impl(transmitters, target_protocol_info)
+    let old_transmitters = target_protocol_info.transmitters.clone();
+    add_transmitters_impl(transmitters, target_protocol_info)?;
+    emit_transmitters_updated(protocol_id_param(&params)?, old_transmitters, target_protocol_info);
+    Ok(())
 }
 
 fn get_transmitters_to_add(params: &Token) -> Result<Vec<EthAddress>> {
@@ -367,27 +526,25 @@ fn add_transmitters_impl(
     to_add: Vec<EthAddress>,
     target_protocol_info: &mut ProtocolInfo,
 ) -> Result<()> {
-    let mut total_transmitters = target_protocol_info.transmitters();
-    total_transmitters.extend_from_slice(&to_add);
-    total_transmitters.dedup();
-    if total_transmitters.len() >= MAX_TRANSMITTERS {
-        return Err(CustomError::MaxTransmittersExceeded.into());
+    for transmitter in to_add {
+        target_protocol_info.add_transmitter(transmitter);
     }
-    target_protocol_info.transmitters = Default::default();
-    for (i, k) in total_transmitters.into_iter().enumerate() {
-        target_protocol_info.transmitters[i] = k;
+    if target_protocol_info.transmitters.len() > MAX_TRANSMITTERS {
+        return Err(CustomError::MaxTransmittersExceeded.into());
     }
     Ok(())
 }
 
 fn remove_transmitters(calldata: &[u8], target_protocol_info: &mut ProtocolInfo) -> Result<()> {
     let params = decode_abi_params(calldata, abi_decode_scheme(GovOperation::RemoveTransmitters))?;
-    let to_remove = get_transmitters_to_remove(&params[1])?;
+    let to_remove = decode_eth_addresses(&params[1])?;
+    let old_transmitters = target_protocol_info.transmitters.clone();
     remove_transmitters_impl(to_remove, target_protocol_info);
+    emit_transmitters_updated(protocol_id_param(&params)?, old_transmitters, target_protocol_info);
     Ok(())
 }
 
-fn get_transmitters_to_remove(params: &Token) -> std::result::Result<Vec<EthAddress>, CustomError> {
+fn decode_eth_addresses(params: &Token) -> std::result::Result<Vec<EthAddress>, CustomError> {
     params
         .clone()
         .into_array()
@@ -398,21 +555,27 @@ fn get_transmitters_to_remove(params: &Token) -> std::result::Result<Vec<EthAddr
 }
 
 fn remove_transmitters_impl(to_remove: Vec<EthAddress>, target_protocol_info: &mut ProtocolInfo) {
-    let total_transmitters: Vec<_> = target_protocol_info
-        .transmitters()
-        .into_iter()
-        .filter(|x| !to_remove.contains(x))
-        .collect();
-    target_protocol_info.transmitters = Default::default();
-    for (i, k) in total_transmitters.into_iter().enumerate() {
-        target_protocol_info.transmitters[i] = k;
+    for transmitter in &to_remove {
+        target_protocol_info.remove_transmitter(transmitter);
+    }
+}
+
+/// Inserts an entry into a sorted registry, returns false if it is already present
+fn insert_sorted<T: Ord>(registry: &mut Vec<T>, entry: T) -> bool {
+    match registry.binary_search(&entry) {
+        Ok(_) => false,
+        Err(pos) => {
+            registry.insert(pos, entry);
+            true
+        }
     }
 }
 
 fn update_transmitters(calldata: &[u8], target_protocol_info: &mut ProtocolInfo) -> Result<()> {
     let params = decode_abi_params(calldata, abi_decode_scheme(GovOperation::UpdateTransmitters))?;
+    let old_transmitters = target_protocol_info.transmitters.clone();
 
-    let to_remove = get_transmitters_to_remove(&params[2])?;
+    let to_remove = decode_eth_addresses(&params[2])?;
     if !to_remove.is_empty() {
         remove_transmitters_impl(to_remove, target_protocol_info);
     }
@@ -422,9 +585,22 @@ fn update_transmitters(calldata: &[u8], target_protocol_info: &mut ProtocolInfo)
         add_transmitters_impl(to_add, target_protocol_info)?;
     }
 
+    emit_transmitters_updated(protocol_id_param(&params)?, old_transmitters, target_protocol_info);
     Ok(())
 }
 
+fn emit_transmitters_updated(
+    protocol_id: Vec<u8>,
+    old_transmitters: Vec<EthAddress>,
+    target_protocol_info: &ProtocolInfo,
+) {
+    emit!(TransmittersUpdated {
+        protocol_id,
+        old_transmitters,
+        new_transmitters: target_protocol_info.transmitters.clone()
+    });
+}
+
 fn set_consensus_target_rate(
     calldata: &[u8],
     target_protocol_info: &mut ProtocolInfo,
@@ -440,10 +616,208 @@ fn set_consensus_target_rate(
     let consensus_target_rate =
         params[1].clone().into_uint().ok_or(CustomError::InvalidGovMsg)?.as_u64();
     check_consensus_target_rate(consensus_target_rate)?;
+    emit!(ConsensusRateChanged {
+        protocol_id: protocol_id_param(&params)?,
+        old_rate: target_protocol_info.consensus_target_rate,
+        new_rate: consensus_target_rate
+    });
     target_protocol_info.consensus_target_rate = consensus_target_rate;
     Ok(())
 }
 
+fn set_transmitter_weights(calldata: &[u8], target_protocol_info: &mut ProtocolInfo) -> Result<()> {
+    let params =
+        decode_abi_params(calldata, abi_decode_scheme(GovOperation::SetTransmitterWeights))?;
+    let transmitters = decode_eth_addresses(&params[1])?;
+    let weights: Vec<U256> = params[2]
+        .clone()
+        .into_array()
+        .ok_or(CustomError::InvalidGovMsg)?
+        .into_iter()
+        .map(|x| x.into_uint().ok_or(CustomError::InvalidGovMsg))
+        .collect::<std::result::Result<_, _>>()?;
+    require_eq!(transmitters.len(), weights.len(), CustomError::InvalidGovMsg);
+
+    let mut old_weights = Vec::with_capacity(transmitters.len());
+    let mut new_weights = Vec::with_capacity(transmitters.len());
+    for (transmitter, weight) in transmitters.iter().zip(weights) {
+        if weight.is_zero() || weight > U256::from(u64::MAX) {
+            return Err(CustomError::InvalidTransmitterWeight.into());
+        }
+        old_weights.push(target_protocol_info.transmitter_weight(transmitter));
+        target_protocol_info.set_transmitter_weight(transmitter, weight.as_u64())?;
+        new_weights.push(weight.as_u64());
+    }
+    emit!(TransmitterWeightsUpdated {
+        protocol_id: protocol_id_param(&params)?,
+        transmitters,
+        old_weights,
+        new_weights
+    });
+    Ok(())
+}
+
+fn set_protocol_paused(calldata: &[u8], target_protocol_info: &mut ProtocolInfo) -> Result<()> {
+    let params = decode_abi_params(calldata, abi_decode_scheme(GovOperation::SetProtocolPaused))?;
+    let protocol_id = params[0].clone().into_fixed_bytes().ok_or(CustomError::InvalidGovMsg)?;
+    let paused = params[1].clone().into_bool().ok_or(CustomError::InvalidGovMsg)?;
+    target_protocol_info.set_paused(&protocol_id, paused)
+}
+
+fn set_global_paused(calldata: &[u8], config: &mut Config) -> Result<()> {
+    let params = decode_abi_params(calldata, abi_decode_scheme(GovOperation::SetGlobalPaused))?;
+    let protocol_id = params[0].clone().into_fixed_bytes().ok_or(CustomError::InvalidGovMsg)?;
+    require!(protocol_id == GOV_PROTOCOL_ID, CustomError::InvalidGovMsg);
+    let paused = params[1].clone().into_bool().ok_or(CustomError::InvalidGovMsg)?;
+    config.set_paused(paused);
+    Ok(())
+}
+
+fn set_guardian(calldata: &[u8], config: &mut Config) -> Result<()> {
+    let params = decode_abi_params(calldata, abi_decode_scheme(GovOperation::SetGuardian))?;
+    let protocol_id = params[0].clone().into_fixed_bytes().ok_or(CustomError::InvalidGovMsg)?;
+    require!(protocol_id == GOV_PROTOCOL_ID, CustomError::InvalidGovMsg);
+    let new_guardian = Pubkey::new_from_array(
+        params[1]
+            .clone()
+            .into_bytes()
+            .ok_or(CustomError::InvalidGovMsg)?
+            .try_into()
+            .map_err(|_| CustomError::InvalidGovMsg)?,
+    );
+    emit!(GuardianChanged {
+        old_guardian: config.guardian,
+        new_guardian
+    });
+    config.guardian = new_guardian;
+    Ok(())
+}
+
+fn set_gov_timelock(calldata: &[u8], config: &mut Config) -> Result<()> {
+    let params = decode_abi_params(calldata, abi_decode_scheme(GovOperation::SetGovTimelock))?;
+    let protocol_id = params[0].clone().into_fixed_bytes().ok_or(CustomError::InvalidGovMsg)?;
+    require!(protocol_id == GOV_PROTOCOL_ID, CustomError::InvalidGovMsg);
+    let gov_timelock = params[1].clone().into_uint().ok_or(CustomError::InvalidGovMsg)?;
+    require!(gov_timelock <= U256::from(MAX_GOV_TIMELOCK), CustomError::InvalidGovMsg);
+    emit!(GovTimelockChanged {
+        old_timelock: config.gov_timelock,
+        new_timelock: gov_timelock.as_u64()
+    });
+    config.gov_timelock = gov_timelock.as_u64();
+    Ok(())
+}
+
+fn add_allowed_dst_chains(calldata: &[u8], target_protocol_info: &mut ProtocolInfo) -> Result<()> {
+    let params = decode_abi_params(calldata, abi_decode_scheme(GovOperation::AddAllowedDstChains))?;
+    let old_dst_chains = target_protocol_info.allowed_dst_chains().to_vec();
+    for dst_chain_id in get_dst_chains(&params[1])? {
+        target_protocol_info.add_allowed_dst_chain(dst_chain_id);
+    }
+    if target_protocol_info.allowed_dst_chains().len() > MAX_DST_CHAINS {
+        return Err(CustomError::MaxDstChainsExceeded.into());
+    }
+    emit!(AllowedDstChainsUpdated {
+        protocol_id: protocol_id_param(&params)?,
+        old_dst_chains,
+        new_dst_chains: target_protocol_info.allowed_dst_chains().to_vec()
+    });
+    Ok(())
+}
+
+fn remove_allowed_dst_chains(
+    calldata: &[u8],
+    target_protocol_info: &mut ProtocolInfo,
+) -> Result<()> {
+    let params =
+        decode_abi_params(calldata, abi_decode_scheme(GovOperation::RemoveAllowedDstChains))?;
+    let old_dst_chains = target_protocol_info.allowed_dst_chains().to_vec();
+    for dst_chain_id in get_dst_chains(&params[1])? {
+        target_protocol_info.remove_allowed_dst_chain(dst_chain_id);
+    }
+    emit!(AllowedDstChainsUpdated {
+        protocol_id: protocol_id_param(&params)?,
+        old_dst_chains,
+        new_dst_chains: target_protocol_info.allowed_dst_chains().to_vec()
+    });
+    Ok(())
+}
+
+fn get_dst_chains(params: &Token) -> Result<Vec<u128>> {
+    params
+        .clone()
+        .into_array()
+        .ok_or(CustomError::InvalidGovMsg)?
+        .into_iter()
+        .map(|x| -> Result<u128> {
+            let dst_chain_id = x.into_uint().ok_or(CustomError::InvalidGovMsg)?;
+            require!(dst_chain_id <= U256::from(u128::MAX), CustomError::InvalidGovMsg);
+            Ok(dst_chain_id.as_u128())
+        })
+        .collect()
+}
+
+fn set_propose_rate_limit(calldata: &[u8], target_protocol_info: &mut ProtocolInfo) -> Result<()> {
+    let params = decode_abi_params(calldata, abi_decode_scheme(GovOperation::SetProposeRateLimit))?;
+    let max_proposals = params[1].clone().into_uint().ok_or(CustomError::InvalidGovMsg)?;
+    let window = params[2].clone().into_uint().ok_or(CustomError::InvalidGovMsg)?;
+    require!(
+        max_proposals <= U256::from(u64::MAX) && window <= U256::from(u64::MAX),
+        CustomError::InvalidGovMsg
+    );
+    let (max_proposals, window) = (max_proposals.as_u64(), window.as_u64());
+    // A limit needs a window to be counted within
+    require!(max_proposals == 0 || window > 0, CustomError::InvalidGovMsg);
+    let (old_max_proposals, old_window) = target_protocol_info.propose_rate_limit();
+    target_protocol_info.set_propose_rate_limit(max_proposals, window);
+    emit!(ProposeRateLimitChanged {
+        protocol_id: protocol_id_param(&params)?,
+        old_max_proposals,
+        old_window,
+        new_max_proposals: max_proposals,
+        new_window: window
+    });
+    Ok(())
+}
+
+fn set_propose_fee(calldata: &[u8], target_protocol_info: &mut ProtocolInfo) -> Result<()> {
+    let params = decode_abi_params(calldata, abi_decode_scheme(GovOperation::SetProposeFee))?;
+    let fee = params[1].clone().into_uint().ok_or(CustomError::InvalidGovMsg)?;
+    require!(fee <= U256::from(u64::MAX), CustomError::InvalidGovMsg);
+    // An empty mint charges the fee in lamports
+    let mint = params[2].clone().into_bytes().ok_or(CustomError::InvalidGovMsg)?;
+    let mint = if mint.is_empty() {
+        None
+    } else {
+        Some(Pubkey::new_from_array(mint.try_into().map_err(|_| CustomError::InvalidGovMsg)?))
+    };
+    let (old_fee, old_mint) = target_protocol_info.propose_fee();
+    target_protocol_info.set_propose_fee(fee.as_u64(), mint);
+    emit!(ProposeFeeChanged {
+        protocol_id: protocol_id_param(&params)?,
+        old_fee,
+        old_mint,
+        new_fee: fee.as_u64(),
+        new_mint: mint
+    });
+    Ok(())
+}
+
+/// Decodes the identifier of the change to cancel from the `CancelGovChange` calldata
+fn cancelled_change_id(params: &[u8]) -> Result<Vec<u8>> {
+    let params = decode_abi_params(params, abi_decode_scheme(GovOperation::CancelGovChange))?;
+    let protocol_id = params[0].clone().into_fixed_bytes().ok_or(CustomError::InvalidGovMsg)?;
+    require!(protocol_id == GOV_PROTOCOL_ID, CustomError::InvalidGovMsg);
+    Ok(params[1].clone().into_fixed_bytes().ok_or(CustomError::InvalidGovMsg)?)
+}
+
+/// Every gov calldata starts with the identifier of the protocol it targets
+fn protocol_id_param(params: &[Token]) -> Result<Vec<u8>> {
+    Ok(params
+        .first()
+        .and_then(|x| x.clone().into_fixed_bytes())
+        .ok_or(CustomError::InvalidGovMsg)?)
+}
+
 fn check_consensus_target_rate(consensus_target_rate: u64) -> Result<()> {
     if consensus_target_rate == 0 {
         return Err(CustomError::ConsensusTargetRateTooLow.into());
@@ -487,6 +861,36 @@ pub(super) fn target_protocol_by_code(
         .ok_or_else(|| "Failed to convert first decoded abi param as fixed_bytes".to_string())
 }
 
+pub(super) fn pending_change_id(function_selector: &FunctionSelector, params: &[u8]) -> Vec<u8> {
+    let FunctionSelector::ByCode(code) = function_selector else {
+        panic!("Unexpected function selector");
+    };
+
+    let Ok(change_id) = pending_change_id_by_code(code, params) else {
+        panic!("Failed to get pending change id");
+    };
+    change_id
+}
+
+/// Identifier of the pending change PDA a gov operation is queued into, or the one it cancels.
+/// Commonly used in the `gov-extension` to derive the pending change account.
+pub fn pending_change_id_by_code(
+    code: &[u8],
+    params: &[u8],
+) -> std::result::Result<Vec<u8>, String> {
+    if code.len() < U32_SIZE {
+        return Err("Selector to short".to_string());
+    }
+    let selector_u32 =
+        u32::from_be_bytes(code[..U32_SIZE].try_into().map_err(|_| "Checked above".to_string())?);
+    let gov_operation = GovOperation::try_from(selector_u32)
+        .map_err(|_| "Failed to get gov_operation from selector".to_string())?;
+    if gov_operation == GovOperation::CancelGovChange {
+        return cancelled_change_id(params).map_err(|_| "Failed to decode abi params".to_string());
+    }
+    Ok(Keccak256::digest([&selector_u32.to_be_bytes()[..], params].concat()).to_vec())
+}
+
 /// Commonly used in the `gov-extension` to extract accounts from encoded `calldata` based on the `param_type`.
 pub fn decode_abi_params(calldata: &[u8], param_type: ParamType) -> Result<Vec<Token>> {
     let decoded =
@@ -551,5 +955,53 @@ pub fn abi_decode_scheme(gov_operation: GovOperation) -> ParamType {
             ParamType::FixedBytes(32), // protocolId
             ParamType::Uint(256),      // target rate
         ]),
+        GovOperation::SetTransmitterWeights => ParamType::Tuple(vec![
+            ParamType::FixedBytes(32),                        // protocolId
+            ParamType::Array(Box::new(ParamType::Address)),   // transmitters
+            ParamType::Array(Box::new(ParamType::Uint(256))), // weights
+        ]),
+        GovOperation::SetProtocolPaused => ParamType::Tuple(vec![
+            ParamType::FixedBytes(32), // protocolId
+            ParamType::Bool,           // paused
+        ]),
+        // The protocolId of the global operations must be the gov one, it only addresses the target account
+        GovOperation::SetGlobalPaused => ParamType::Tuple(vec![
+            ParamType::FixedBytes(32), // protocolId
+            ParamType::Bool,           // paused
+        ]),
+        GovOperation::SetGuardian => ParamType::Tuple(vec![
+            ParamType::FixedBytes(32), // protocolId
+            ParamType::Bytes,          // guardian
+        ]),
+        GovOperation::SetGovTimelock => ParamType::Tuple(vec![
+            ParamType::FixedBytes(32), // protocolId
+            ParamType::Uint(256),      // timelock in seconds
+        ]),
+        GovOperation::CancelGovChange => ParamType::Tuple(vec![
+            ParamType::FixedBytes(32), // protocolId
+            ParamType::FixedBytes(32), // changeId
+        ]),
+        GovOperation::RemoveAllowedProtocol => ParamType::Tuple(vec![
+            ParamType::FixedBytes(32), // protocolId
+            ParamType::Bytes,          // rentRecipient
+        ]),
+        GovOperation::AddAllowedDstChains => ParamType::Tuple(vec![
+            ParamType::FixedBytes(32),                        // protocolId
+            ParamType::Array(Box::new(ParamType::Uint(256))), // dst chain ids
+        ]),
+        GovOperation::RemoveAllowedDstChains => ParamType::Tuple(vec![
+            ParamType::FixedBytes(32),                        // protocolId
+            ParamType::Array(Box::new(ParamType::Uint(256))), // dst chain ids
+        ]),
+        GovOperation::SetProposeRateLimit => ParamType::Tuple(vec![
+            ParamType::FixedBytes(32), // protocolId
+            ParamType::Uint(256),      // max proposals per window, zero to disable
+            ParamType::Uint(256),      // window in slots
+        ]),
+        GovOperation::SetProposeFee => ParamType::Tuple(vec![
+            ParamType::FixedBytes(32), // protocolId
+            ParamType::Uint(256),      // fee per proposal, zero to disable
+            ParamType::Bytes,          // fee mint, empty for lamports
+        ]),
     }
 }
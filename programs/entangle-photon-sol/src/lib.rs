@@ -165,6 +165,17 @@
 //!    proposer: ctx.accounts.proposer.to_account_info(),
 //!    config: ctx.accounts.config.to_account_info(),
 //!    protocol_info: ctx.accounts.protocol_info.to_account_info(),
+//!    payer: ctx.accounts.owner.to_account_info(),
+//!    propose_nonce: ctx.accounts.propose_nonce.to_account_info(),
+//!    // The rate limit window is only needed if gov has set a proposal rate limit for the protocol
+//!    propose_window: ctx.accounts.propose_window.as_ref().map(|x| x.to_account_info()),
+//!    // The fee accounts are only needed if gov has set a proposal fee for the protocol
+//!    fee_payer: None,
+//!    fee_vault: None,
+//!    fee_payer_token_account: None,
+//!    fee_vault_token_account: None,
+//!    token_program: None,
+//!    system_program: ctx.accounts.system_program.to_account_info(),
 //!};
 //!let bump = [ctx.bumps.proposer];
 //!let proposer_seeds = [ROOT, b"PROPOSER", &bump[..]];
@@ -182,16 +193,22 @@
 //! ```
 //!
 
+mod ack;
+pub mod emit;
 pub mod error;
+mod fee;
 pub mod gov;
 mod interface;
+pub mod migrate;
 pub mod protocol_data;
 pub mod util;
 
 use anchor_lang::prelude::*;
+use anchor_spl::token::{Token, TokenAccount};
 use error::CustomError;
+use migrate::{LegacyConfig, LegacyOpInfo, LegacyProtocolInfo};
 use protocol_data::{
-    gov_protocol_id, FunctionSelector, OpStatus, OperationData, TransmitterSignature,
+    gov_protocol_id, FunctionSelector, Meta, OpStatus, OperationData, TransmitterSignature,
 };
 use util::EthAddress;
 
@@ -217,8 +234,8 @@ pub const DEPLOYER: &str = "7M681DVV9eg5jihLwbL4yvcwtPJTpih99f94c9K8Sipx";
 /// - `SOLANA_CHAIN_ID`: Unique identifier for the Solana chain, used for validation.
 /// - `RATE_DECIMALS`: Used for calculations involving rate percentages in consensus processes.
 /// - `ROOT`: A byte string used as a base for seed generation in account addresses.
-/// - `MAX_TRANSMITTERS`, `MAX_EXECUTORS`, `MAX_PROPOSERS`: Define the maximum allowable numbers of transmitters,
-///   executors, and proposers respectively to ensure the system's scalability and manageability.
+/// - `MAX_TRANSMITTERS`, `MAX_EXECUTORS`, `MAX_PROPOSERS`: Bound the growth of the resizable transmitter,
+///   executor, and proposer registries of a protocol.
 ///
 /// ## Key Operations
 /// - **Initialize**: Sets up the initial configuration for protocols, defining administrators, chain IDs,
@@ -229,7 +246,9 @@ pub const DEPLOYER: &str = "7M681DVV9eg5jihLwbL4yvcwtPJTpih99f94c9K8Sipx";
 ///   the operation status upon achieving the required threshold.
 /// - **Execute Operation**: The final step where the operation is executed based on the received and
 ///   validated instructions, with potential cross-program invocations if the operation involves governance
-///   protocols.
+///   protocols. The outcome is acknowledged to the source chain if it was requested in the operation meta.
+/// - **Report Operation Failure**: Lets the executor acknowledge to the source chain an operation it could not
+///   execute.
 /// - **Propose**: Allows registered proposers to submit operations intended to be executed on other chains,
 ///   managing these proposals through events that ensure transparency and traceability.
 /// - **Receive Photon Message**: Specialized in handling operations directed at the governance protocol,
@@ -274,27 +293,68 @@ pub mod photon {
     /// This root seed acts as a foundational element for generating deterministic account addresses.
     pub const ROOT: &[u8] = b"r0";
 
-    /// The maximum number of transmitters that can be registered in the system.
+    /// The maximum number of transmitters that can be registered for a protocol.
     /// Transmitters are critical for the dissemination and signing of cross-chain messages.
-    pub const MAX_TRANSMITTERS: usize = 20;
+    /// Protocol accounts are resized on demand, so this only bounds account growth.
+    pub const MAX_TRANSMITTERS: usize = 256;
 
-    /// The maximum number of executors allowed within the system.
+    /// The maximum number of executors that can be registered for a protocol.
     /// Executors are responsible for carrying out operations and managing transaction state transitions.
-    pub const MAX_EXECUTORS: usize = 20;
+    pub const MAX_EXECUTORS: usize = 256;
 
-    /// The maximum number of proposers that can be registered in the system.
+    /// The maximum number of proposers that can be registered for a protocol.
     /// Proposers are authorized to initiate new operations that may affect multiple chains.
-    pub const MAX_PROPOSERS: usize = 20;
+    pub const MAX_PROPOSERS: usize = 256;
+
+    /// The longest delay governance can put on its sensitive operations, in seconds.
+    pub const MAX_GOV_TIMELOCK: u64 = 30 * 24 * 60 * 60;
+
+    /// The voting weight assigned to a transmitter when it is registered.
+    /// Governance can change it afterwards to model stake-weighted transmitter sets.
+    pub const DEFAULT_TRANSMITTER_WEIGHT: u64 = 1;
+
+    /// The maximum number of destination chains a protocol can be allowed to propose to.
+    pub const MAX_DST_CHAINS: usize = 256;
+
+    /// The maximum length of the destination protocol address of a proposal.
+    /// It fits the address formats of the supported chains, like 20 bytes EVM and 32 bytes Solana ones.
+    pub const MAX_PROTOCOL_ADDRESS_LEN: usize = 64;
+
+    /// The maximum size of the params of a proposal, it keeps the emitted event within the log limits.
+    pub const MAX_PROPOSE_PARAMS_LEN: usize = 4096;
+
+    /// The maximum size of the params of an operation loaded to Solana.
+    /// The whole operation is passed within a single transaction, so it can not grow much larger anyway.
+    pub const MAX_OP_PARAMS_LEN: usize = 1024;
+
+    /// The maximum size of the execution return data carried by the events, the hash stored in the
+    /// operation info covers the whole return data.
+    pub const MAX_RETURN_DATA_LEN: usize = 512;
+
+    /// The number of consecutive operation nonces tracked by a single processed nonces account.
+    pub const NONCES_PER_BITMAP: u64 = 4096;
+
+    /// The time an operation has to stay signed before an executor can report it as failed, in seconds.
+    /// It keeps a single executor from failing the operations the other executors can still execute.
+    #[cfg(feature = "localnet")]
+    pub const FAILURE_REPORT_DELAY: i64 = 2;
+    #[cfg(not(feature = "localnet"))]
+    pub const FAILURE_REPORT_DELAY: i64 = 60 * 60;
 
     use self::{
-        gov::handle_gov_operation,
+        emit::emit_event,
+        gov::{finalize_target_protocol, handle_gov_operation, parse_gov_operation, GovOperation},
         interface::{PhotonMsg, PhotonMsgWithSelector},
         protocol_data::ecrecover,
-        util::sighash,
+        util::{close_account, create_pda_account, realloc_account, sighash},
     };
     use super::*;
 
-    use anchor_lang::solana_program::{instruction::Instruction, program::invoke_signed};
+    use anchor_lang::solana_program::{
+        instruction::Instruction,
+        program::{get_return_data, invoke_signed},
+    };
+    use sha3::{Digest, Keccak256};
 
     /// Initializes the Solana program with the provided configuration and protocol information.
     ///
@@ -324,25 +384,33 @@ pub mod photon {
         ctx.accounts.config.admin = ctx.accounts.admin.key();
         ctx.accounts.config.eob_chain_id = eob_chain_id;
         require_eq!(eob_master_smart_contract.len(), 32);
+        require_gte!(MAX_TRANSMITTERS, gov_transmitters.len(), CustomError::MaxTransmittersExceeded);
+        require_gte!(MAX_EXECUTORS, gov_executors.len(), CustomError::MaxExecutorsExceeded);
         ctx.accounts.config.eob_master_smart_contract.copy_from_slice(&eob_master_smart_contract);
+        ctx.accounts.protocol_info.version = ProtocolInfo::VERSION;
         ctx.accounts.protocol_info.is_init = true;
         ctx.accounts.protocol_info.protocol_address = photon::ID;
         ctx.accounts.protocol_info.consensus_target_rate = consensus_target_rate;
-        ctx.accounts.protocol_info.transmitters = Default::default();
-        for (i, k) in gov_transmitters.into_iter().enumerate() {
-            ctx.accounts.protocol_info.transmitters[i] = k;
-        }
-        ctx.accounts.protocol_info.executors = Default::default();
-        for (i, e) in gov_executors.into_iter().enumerate() {
-            ctx.accounts.protocol_info.executors[i] = e;
-        }
-        Ok(())
+        ctx.accounts.protocol_info.set_transmitters(gov_transmitters);
+        ctx.accounts.protocol_info.set_executors(gov_executors);
+        let space = ctx.accounts.protocol_info.space();
+        realloc_account(
+            &ctx.accounts.protocol_info.to_account_info(),
+            &ctx.accounts.admin.to_account_info(),
+            &ctx.accounts.system_program.to_account_info(),
+            space,
+        )
     }
 
     /// Loads an operation in the Photon cross-chain messaging layer.
     ///
     /// This method serves as the first step in executing an operation. It verifies the provided operation data
-    /// and sets the initial status of the operation.
+    /// and sets the initial status of the operation. Malformed operation data is rejected with a specific error,
+    /// see `OperationData::validate`.
+    ///
+    /// The nonce of the operation is marked as processed for its protocol and source chain, so the same
+    /// source operation can not be loaded twice under a different hash, e.g. with altered `meta` or
+    /// `reserved` bytes.
     ///
     /// # Arguments
     ///
@@ -358,6 +426,7 @@ pub mod photon {
         op_data: OperationData,
         op_hash_cached: Vec<u8>,
     ) -> Result<()> {
+        op_data.validate()?;
         let op_hash = op_data.op_hash_with_message();
         require!(op_hash == op_hash_cached, CustomError::CachedOpHashMismatch);
         require_eq!(op_data.dest_chain_id, SOLANA_CHAIN_ID, CustomError::OpIsNotForThisChain);
@@ -366,16 +435,19 @@ pub mod photon {
             op_data.protocol_addr,
             CustomError::ProtocolAddressMismatch
         );
-        require!(
-            op_data.protocol_id != [0; 32] && op_data.protocol_id.len() == 32,
-            CustomError::InvalidOpData
-        );
+        ctx.accounts
+            .config
+            .require_not_paused(&op_data.protocol_id, &ctx.accounts.protocol_info)?;
+        ctx.accounts.processed_nonces.mark_processed(op_data.nonce)?;
         ctx.accounts.op_info.op_data = op_data;
         ctx.accounts.op_info.status = OpStatus::Init;
-        emit!(ProposalLoaded {
-            op_hash,
-            executor: ctx.accounts.executor.key()
-        });
+        emit_event!(
+            ctx,
+            ProposalLoaded {
+                op_hash,
+                executor: ctx.accounts.executor.key()
+            }
+        );
         Ok(())
     }
 
@@ -398,43 +470,49 @@ pub mod photon {
         op_hash: Vec<u8>,
         signatures: Vec<TransmitterSignature>,
     ) -> Result<bool> {
-        let allowed_transmitters = &ctx.accounts.protocol_info.transmitters();
-        require_gt!(allowed_transmitters.len(), 0, CustomError::NoTransmittersAllowed);
-        let mut unique_signers: Vec<EthAddress> = ctx
-            .accounts
-            .op_info
-            .unique_signers
-            .into_iter()
-            .filter(|x| x != &EthAddress::default())
-            .collect();
-        let consensus =
-            ((unique_signers.len() as u64) * RATE_DECIMALS) / (allowed_transmitters.len() as u64);
-        let mut consensus_reached = consensus >= ctx.accounts.protocol_info.consensus_target_rate;
+        let protocol_info = &ctx.accounts.protocol_info;
+        require_gt!(
+            protocol_info.total_transmitter_weight(),
+            0,
+            CustomError::NoTransmittersAllowed
+        );
+        let mut unique_signers: Vec<EthAddress> = ctx.accounts.op_info.unique_signers.clone();
+        let mut consensus_reached =
+            protocol_info.consensus_rate(&unique_signers) >= protocol_info.consensus_target_rate;
         if consensus_reached {
             return Ok(true);
         }
         for sig in signatures {
             let transmitter = ecrecover(&op_hash, &sig)?;
-            if allowed_transmitters.contains(&transmitter) && !unique_signers.contains(&transmitter)
+            if protocol_info.is_transmitter(&transmitter) && !unique_signers.contains(&transmitter)
             {
                 unique_signers.push(transmitter);
-                let consensus_rate = ((unique_signers.len() as u64) * RATE_DECIMALS)
-                    / (allowed_transmitters.len() as u64);
-                if consensus_rate >= ctx.accounts.protocol_info.consensus_target_rate {
+                if protocol_info.consensus_rate(&unique_signers)
+                    >= protocol_info.consensus_target_rate
+                {
                     consensus_reached = true;
                     ctx.accounts.op_info.status = OpStatus::Signed;
-                    emit!(ProposalApproved {
-                        op_hash,
-                        executor: ctx.accounts.executor.key()
-                    });
+                    ctx.accounts.op_info.signed_at = Clock::get()?.unix_timestamp;
+                    emit_event!(
+                        ctx,
+                        ProposalApproved {
+                            op_hash,
+                            executor: ctx.accounts.executor.key()
+                        }
+                    );
                     break;
                 }
             }
         }
-        ctx.accounts.op_info.unique_signers = Default::default();
-        for (i, s) in unique_signers.into_iter().enumerate() {
-            ctx.accounts.op_info.unique_signers[i] = s;
-        }
+        // The transmitter set may have grown since the operation was loaded
+        let required_len = OpInfo::len(&ctx.accounts.op_info.op_data, unique_signers.len());
+        ctx.accounts.op_info.unique_signers = unique_signers;
+        realloc_account(
+            &ctx.accounts.op_info.to_account_info(),
+            &ctx.accounts.executor.to_account_info(),
+            &ctx.accounts.system_program.to_account_info(),
+            required_len,
+        )?;
         Ok(consensus_reached)
     }
 
@@ -444,6 +522,9 @@ pub mod photon {
     ) -> Result<()> {
         let op_data = &ctx.accounts.op_info.op_data;
         msg!("op_hash: {}", hex::encode(&op_hash));
+        ctx.accounts
+            .config
+            .require_not_paused(&op_data.protocol_id, &ctx.accounts.protocol_info)?;
         // The first account in remaining_accounts should be protocol address, which is added first in account list
         let mut accounts: Vec<_> = ctx.remaining_accounts.first().into_iter().cloned().collect();
         require!(
@@ -486,7 +567,7 @@ pub mod photon {
                 };
                 (name.clone(), payload.try_to_vec().expect("fixed struct serialization"))
             }
-            FunctionSelector::Dummy => panic!("Uninitialized function_selector"),
+            FunctionSelector::Dummy => return Err(CustomError::InvalidSelectorType.into()),
         };
 
         let data = [&sighash("global", &method)[..], &payload[..]].concat();
@@ -502,12 +583,90 @@ pub mod photon {
             ]],
         )?;
 
+        // Only the return data set by the protocol program itself is captured
+        let mut return_data = get_return_data()
+            .filter(|(program_id, _)| *program_id == op_data.protocol_addr)
+            .map(|(_, data)| data)
+            .unwrap_or_default();
+        let return_data_hash =
+            (!return_data.is_empty()).then(|| Keccak256::digest(&return_data).into());
+        return_data.truncate(MAX_RETURN_DATA_LEN);
+
+        // Report the execution back to the source chain if it asked for it
+        if let Some(ack) = ack::acknowledgement(
+            &op_hash,
+            op_data,
+            ctx.accounts.ack_nonce.as_deref_mut().map(|x| &mut **x),
+            true,
+            return_data.clone(),
+        )? {
+            emit_event!(ctx, ack);
+        }
+
         ctx.accounts.op_info.status = OpStatus::Executed;
+        ctx.accounts.op_info.return_data_hash = return_data_hash;
+
+        emit_event!(
+            ctx,
+            ProposalExecuted {
+                op_hash,
+                executor: ctx.accounts.executor.key(),
+                return_data
+            }
+        );
+        Ok(())
+    }
 
-        emit!(ProposalExecuted {
-            op_hash,
-            executor: ctx.accounts.executor.key()
-        });
+    /// Reports that a signed operation can not be executed, so its failure is acknowledged to the
+    /// source chain.
+    ///
+    /// A failed execution reverts the whole transaction, so the failure acknowledgement can not be
+    /// emitted by `execute_operation`. Instead the executor reports it once it gives up on the
+    /// operation, which moves the operation to the `Failed` state and emits the acknowledgement
+    /// with an empty return data. Only the operations requesting an acknowledgement through the
+    /// `meta` flags can be reported, and only once they have stayed signed for
+    /// `FAILURE_REPORT_DELAY`, so the other executors get the time to execute them.
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx` - The context containing the accounts required for reporting the failure.
+    /// * `op_hash` - The hash of the operation.
+    pub fn report_operation_failure(
+        ctx: Context<ReportOperationFailure>,
+        op_hash: Vec<u8>,
+    ) -> Result<()> {
+        let op_data = &ctx.accounts.op_info.op_data;
+        ctx.accounts
+            .config
+            .require_not_paused(&op_data.protocol_id, &ctx.accounts.protocol_info)?;
+        require!(
+            op_data.meta()?.has_flag(Meta::FLAG_FAILURE_CALLBACK),
+            CustomError::AckNotRequested
+        );
+        require!(
+            Clock::get()?.unix_timestamp
+                >= ctx.accounts.op_info.signed_at.saturating_add(FAILURE_REPORT_DELAY),
+            CustomError::FailureReportTooEarly
+        );
+        if let Some(ack) = ack::acknowledgement(
+            &op_hash,
+            op_data,
+            Some(&mut ctx.accounts.ack_nonce),
+            false,
+            vec![],
+        )? {
+            emit_event!(ctx, ack);
+        }
+
+        ctx.accounts.op_info.status = OpStatus::Failed;
+
+        emit_event!(
+            ctx,
+            ProposalFailed {
+                op_hash,
+                executor: ctx.accounts.executor.key()
+            }
+        );
         Ok(())
     }
 
@@ -515,8 +674,20 @@ pub mod photon {
     ///
     /// This function facilitates cross-chain communication by proposing an operation to be executed
     /// on another blockchain. It handles the creation of a proposal event based on the specified
-    /// details, incrementing the nonce in the system configuration to maintain a unique identifier
-    /// for each proposal.
+    /// details, incrementing the nonce of the protocol to maintain a unique identifier for each
+    /// proposal.
+    ///
+    /// The destination chain must be allowed for the protocol by gov unless its allowlist is empty,
+    /// the protocol address and params sizes are bounded, and the protocol proposal rate limit is
+    /// applied if gov has set one. The rate limit window is counted in the `propose_window` account,
+    /// so the protocol info is not write locked by the proposals.
+    ///
+    /// Proposals are sequenced per protocol and destination chain by the nonce kept in the
+    /// `propose_nonce` account, so the config and the protocol info are only read and the proposers
+    /// do not contend on them.
+    ///
+    /// If gov has set a proposal fee for the protocol, it is charged from the `fee_payer` into the
+    /// protocol fee vault, in lamports or in the SPL token of the fee mint.
     ///
     /// # Arguments
     ///
@@ -538,17 +709,40 @@ pub mod photon {
         function_selector: FunctionSelector,
         params: Vec<u8>,
     ) -> Result<()> {
-        // TODO: check if all requirements are satisfied
-        let nonce = ctx.accounts.config.nonce;
-        ctx.accounts.config.nonce += 1;
-        emit!(ProposeEvent {
-            protocol_id,
-            nonce,
-            dst_chain_id,
-            protocol_address,
-            function_selector: function_selector.to_bytes()?,
-            params
-        });
+        ctx.accounts.config.require_not_paused(&protocol_id, &ctx.accounts.protocol_info)?;
+        require!(
+            ctx.accounts.protocol_info.is_dst_chain_allowed(dst_chain_id),
+            CustomError::DstChainIsNotAllowed
+        );
+        require!(
+            !protocol_address.is_empty() && protocol_address.len() <= MAX_PROTOCOL_ADDRESS_LEN,
+            CustomError::InvalidProtocolAddressLength
+        );
+        require!(params.len() <= MAX_PROPOSE_PARAMS_LEN, CustomError::ParamsTooBig);
+        let rate_limit = ctx.accounts.protocol_info.propose_rate_limit();
+        if rate_limit.0 != 0 {
+            let propose_window = ctx
+                .accounts
+                .propose_window
+                .as_mut()
+                .ok_or(CustomError::ProposeWindowNotProvided)?;
+            propose_window.register_proposal(Clock::get()?.slot, rate_limit)?;
+        }
+        let (fee, fee_mint) = fee::charge_propose_fee(ctx.accounts)?;
+        let nonce = ctx.accounts.propose_nonce.next();
+        emit_event!(
+            ctx,
+            ProposeEvent {
+                protocol_id,
+                nonce,
+                dst_chain_id,
+                protocol_address,
+                function_selector: function_selector.to_bytes()?,
+                params,
+                fee,
+                fee_mint
+            }
+        );
         Ok(())
     }
 
@@ -559,6 +753,13 @@ pub mod photon {
     /// specifically designed for code-based operations that fall under the governance protocol's
     /// scope. It ensures the proper execution path based on the code and parameters of the operation.
     ///
+    /// While the gov timelock is enabled, sensitive operations are not applied but queued into the
+    /// `pending_change` account, to be applied by `apply_gov_change` once their activation time passes
+    /// or dropped by a `CancelGovChange` gov operation.
+    ///
+    /// `RemoveAllowedProtocol` closes the target protocol account, its rent recipient is expected
+    /// among the remaining accounts.
+    ///
     /// # Arguments
     ///
     /// * `ctx` - The context containing the necessary accounts for processing the photon message.
@@ -570,9 +771,9 @@ pub mod photon {
     ///
     /// Returns a result indicating the success or failure of processing the photon message.
     ///
-    pub fn receive_photon_msg(
-        ctx: Context<ReceivePhotonMsg>,
-        _op_hash: Vec<u8>,
+    pub fn receive_photon_msg<'info>(
+        ctx: Context<'_, '_, '_, 'info, ReceivePhotonMsg<'info>>,
+        op_hash: Vec<u8>,
         code: Vec<u8>,
         _params: Vec<u8>,
     ) -> Result<()> {
@@ -581,11 +782,107 @@ pub mod photon {
             op_data.protocol_id == gov_protocol_id() && op_data.protocol_addr == ID,
             CustomError::InvalidEndpoint
         );
+        let gov_operation = parse_gov_operation(&code)?;
+        let pending_change = ctx.accounts.pending_change.to_account_info();
+        let change_id = gov::pending_change_id(&op_data.function_selector, &op_data.params);
+        if gov_operation == GovOperation::CancelGovChange {
+            require!(
+                pending_change.owner == &ID && !pending_change.data_is_empty(),
+                CustomError::GovChangeNotFound
+            );
+            close_account(&pending_change, &ctx.accounts.executor.to_account_info())?;
+            emit!(GovChangeCancelled { change_id, op_hash });
+            return Ok(());
+        }
+        if gov_operation.is_timelocked() && ctx.accounts.config.gov_timelock > 0 {
+            require!(pending_change.data_is_empty(), CustomError::GovChangeAlreadyQueued);
+            let activation_time =
+                Clock::get()?.unix_timestamp + ctx.accounts.config.gov_timelock as i64;
+            create_pda_account(
+                &pending_change,
+                &ctx.accounts.executor.to_account_info(),
+                &ctx.accounts.system_program.to_account_info(),
+                PendingGovChange::len(op_data),
+                &[
+                    ROOT,
+                    b"PENDING_GOV",
+                    &change_id,
+                    &[ctx.bumps.pending_change],
+                ],
+            )?;
+            let change = PendingGovChange {
+                op_hash: op_hash.clone(),
+                op_data: op_data.clone(),
+                activation_time,
+            };
+            change.try_serialize(&mut &mut pending_change.try_borrow_mut_data()?[..])?;
+            emit!(GovChangeQueued {
+                change_id,
+                op_hash,
+                code,
+                activation_time
+            });
+            return Ok(());
+        }
         handle_gov_operation(
             &mut ctx.accounts.config,
             &mut ctx.accounts.target_protocol_info,
-            code,
+            gov_operation,
+            op_data,
+        )?;
+        finalize_target_protocol(
+            gov_operation,
             op_data,
+            &ctx.accounts.target_protocol_info,
+            &ctx.accounts.executor.to_account_info(),
+            &ctx.accounts.system_program.to_account_info(),
+            ctx.remaining_accounts,
+        )
+    }
+
+    /// Applies a timelocked gov operation once its activation time has passed.
+    /// The rent recipient of a removed protocol is expected among the remaining accounts.
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx` - The context containing the necessary accounts.
+    /// * `change_id` - The identifier of the pending change, emitted with `GovChangeQueued`.
+    ///
+    /// # Returns
+    ///
+    /// Returns a result indicating the success or failure of applying the change.
+    ///
+    pub fn apply_gov_change<'info>(
+        ctx: Context<'_, '_, '_, 'info, ApplyGovChange<'info>>,
+        change_id: Vec<u8>,
+    ) -> Result<()> {
+        let change = &ctx.accounts.pending_change;
+        require_gte!(
+            Clock::get()?.unix_timestamp,
+            change.activation_time,
+            CustomError::GovChangeNotActive
+        );
+        let FunctionSelector::ByCode(code) = &change.op_data.function_selector else {
+            return Err(CustomError::InvalidMethodSelector.into());
+        };
+        let gov_operation = parse_gov_operation(code)?;
+        handle_gov_operation(
+            &mut ctx.accounts.config,
+            &mut ctx.accounts.target_protocol_info,
+            gov_operation,
+            &change.op_data,
+        )?;
+        emit!(GovChangeApplied {
+            change_id,
+            op_hash: change.op_hash.clone()
+        });
+        finalize_target_protocol(
+            gov_operation,
+            &change.op_data,
+            &ctx.accounts.target_protocol_info,
+            &ctx.accounts.executor.to_account_info(),
+            &ctx.accounts.system_program.to_account_info(),
+            ctx.remaining_accounts,
         )
     }
 
@@ -604,6 +901,143 @@ pub mod photon {
         ctx.accounts.config.admin = admin;
         Ok(())
     }
+
+    /// Pauses or unpauses the whole messaging layer. Can only be called by the guardian.
+    ///
+    /// Governance operations are not affected by the global pause so it can always be lifted through gov.
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx` - The context containing the necessary accounts.
+    /// * `paused` - Whether the layer should be paused.
+    ///
+    /// # Returns
+    ///
+    /// Returns a result with always Ok(()) status.
+    ///
+    pub fn set_global_paused(ctx: Context<SetGlobalPaused>, paused: bool) -> Result<()> {
+        ctx.accounts.config.set_paused(paused);
+        Ok(())
+    }
+
+    /// Pauses or unpauses a single protocol. Can only be called by the guardian.
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx` - The context containing the necessary accounts.
+    /// * `protocol_id` - The identifier of the protocol, must not be the gov protocol.
+    /// * `paused` - Whether the protocol should be paused.
+    ///
+    /// # Returns
+    ///
+    /// Returns a result indicating the success or failure of the operation.
+    ///
+    pub fn set_protocol_paused(
+        ctx: Context<SetProtocolPaused>,
+        protocol_id: Vec<u8>,
+        paused: bool,
+    ) -> Result<()> {
+        ctx.accounts.protocol_info.set_paused(&protocol_id, paused)
+    }
+
+    /// Withdraws the proposal fees collected in the protocol fee vault. Can only be called by the admin.
+    ///
+    /// Lamports are withdrawn to the `recipient` keeping the vault rent exempt. If the
+    /// `fee_vault_token_account` is provided, its tokens are withdrawn to the `recipient` token account.
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx` - The context containing the necessary accounts.
+    /// * `protocol_id` - The identifier of the protocol the fees were paid to.
+    /// * `amount` - The amount of lamports or tokens to withdraw.
+    ///
+    /// # Returns
+    ///
+    /// Returns a result indicating the success or failure of the operation.
+    ///
+    pub fn withdraw_fees(
+        ctx: Context<WithdrawFees>,
+        protocol_id: Vec<u8>,
+        amount: u64,
+    ) -> Result<()> {
+        fee::withdraw_fees(ctx.accounts, &protocol_id, ctx.bumps.fee_vault, amount)
+    }
+
+    /// Rewrites an operation info account loaded by an earlier program version in the current layout.
+    ///
+    /// The operations loaded before the upgrade can not be signed or executed until they are migrated.
+    /// Anyone can migrate them, the payer covers the rent of the grown account.
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx` - The context containing the operation info account to migrate.
+    /// * `op_hash` - The hash of the operation.
+    pub fn migrate_op_info(ctx: Context<MigrateOpInfo>, op_hash: Vec<u8>) -> Result<()> {
+        let op_info = ctx.accounts.op_info.to_account_info();
+        let legacy = LegacyOpInfo::decode(&op_info.try_borrow_data()?, &op_hash)
+            .ok_or(CustomError::AccountNotInLegacyLayout)?;
+        let mut migrated = OpInfo::from(legacy);
+        // The legacy layout does not record the consensus time, the failure report delay starts now
+        if migrated.status == OpStatus::Signed {
+            migrated.signed_at = Clock::get()?.unix_timestamp;
+        }
+        migrate::write_migrated(
+            &op_info,
+            &ctx.accounts.payer.to_account_info(),
+            &ctx.accounts.system_program.to_account_info(),
+            &migrated,
+            OpInfo::len(&migrated.op_data, migrated.unique_signers.len()),
+        )
+    }
+
+    /// Rewrites a protocol info account initialized by an earlier program version in the current
+    /// layout.
+    ///
+    /// The protocols registered before the upgrade can not be used until they are migrated. Anyone
+    /// can migrate them, the payer covers the rent of the grown account.
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx` - The context containing the protocol info account to migrate.
+    /// * `protocol_id` - The identifier of the protocol.
+    pub fn migrate_protocol_info(
+        ctx: Context<MigrateProtocolInfo>,
+        protocol_id: Vec<u8>,
+    ) -> Result<()> {
+        let protocol_info = ctx.accounts.protocol_info.to_account_info();
+        let legacy = LegacyProtocolInfo::decode(&protocol_info.try_borrow_data()?)
+            .ok_or(CustomError::AccountNotInLegacyLayout)?;
+        let migrated = ProtocolInfo::from(legacy);
+        msg!("protocol_id: {}", hex::encode(&protocol_id));
+        migrate::write_migrated(
+            &protocol_info,
+            &ctx.accounts.payer.to_account_info(),
+            &ctx.accounts.system_program.to_account_info(),
+            &migrated,
+            migrated.space(),
+        )
+    }
+
+    /// Rewrites the config account initialized by an earlier program version in the current layout.
+    ///
+    /// The layer can not be used until the config is migrated. The migrated config has no guardian,
+    /// is not paused and applies gov operations at once, gov can set these up afterwards.
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx` - The context containing the config account to migrate.
+    pub fn migrate_config(ctx: Context<MigrateConfig>) -> Result<()> {
+        let config = ctx.accounts.config.to_account_info();
+        let legacy = LegacyConfig::decode(&config.try_borrow_data()?)
+            .ok_or(CustomError::AccountNotInLegacyLayout)?;
+        migrate::write_migrated(
+            &config,
+            &ctx.accounts.payer.to_account_info(),
+            &ctx.accounts.system_program.to_account_info(),
+            &Config::from(legacy),
+            Config::LEN,
+        )
+    }
 }
 
 /// Represents the accounts required for initializing the Solana program.
@@ -614,11 +1048,11 @@ pub mod photon {
 /// # Fields
 ///
 /// * `admin` - The admin account, which must be a signer and mutable. Additionally, it must either
-/// match the `admin` key in the configuration or be a default public key.
-/// * `protocol_info` - The protocol information account. It is initialized if needed, with space allocated
-/// based on `ProtocolInfo::LEN`, and it utilizes seeds and a bump for addressing.
+///   match the `admin` key in the configuration or be a default public key.
+/// * `protocol_info` - The protocol information account. It is initialized if needed with `ProtocolInfo::INIT_LEN`
+///   space, grown to fit the governance registries, and it utilizes seeds and a bump for addressing.
 /// * `config` - The system configuration account. It is initialized if needed, with space allocated
-/// based on `Config::LEN`, and it utilizes seeds and a bump for addressing.
+///   based on `Config::LEN`, and it utilizes seeds and a bump for addressing.
 /// * `system_program` - The system program.
 ///
 #[derive(Accounts)]
@@ -636,7 +1070,7 @@ pub struct Initialize<'info> {
     #[account(
         init_if_needed,
         payer = admin,
-        space = ProtocolInfo::LEN,
+        space = ProtocolInfo::INIT_LEN,
         seeds = [ROOT, b"PROTOCOL", gov_protocol_id()],
         bump
     )]
@@ -663,6 +1097,7 @@ pub struct Initialize<'info> {
 /// * `protocol_info` - The protocol information account, identified using seeds and a bump.
 /// * `op_info` - The operation information account, initialized and assigned a bump if needed. It must be uninitialized (status is `None`).
 /// * `config` - The system configuration account, which is mutable and identified using seeds and a bump.
+/// * `processed_nonces` - The processed nonces of the protocol from the source chain, covering the operation nonce. Initialized if needed.
 /// * `system_program` - The system program.
 ///
 /// # Arguments
@@ -670,6 +1105,7 @@ pub struct Initialize<'info> {
 /// * `op_data` - The data related to the operation.
 /// * `op_hash_cached` - The cached hash of the operation data.
 ///
+#[cfg_attr(feature = "event-cpi", event_cpi)]
 #[derive(Accounts)]
 #[instruction(op_data: OperationData, op_hash_cached: Vec<u8>)]
 pub struct LoadOperation<'info> {
@@ -677,7 +1113,7 @@ pub struct LoadOperation<'info> {
     #[account(
         signer,
         mut,
-        constraint = protocol_info.executors.contains(&executor.key()) @ CustomError::ExecutorIsNotAllowed
+        constraint = protocol_info.is_executor(&executor.key()) @ CustomError::ExecutorIsNotAllowed
     )]
     executor: Signer<'info>,
 
@@ -692,7 +1128,7 @@ pub struct LoadOperation<'info> {
     #[account(
         init,
         payer = executor,
-        space = OpInfo::len(&op_data),
+        space = OpInfo::len(&op_data, protocol_info.transmitters().len()),
         seeds = [ROOT, b"OP", &op_hash_cached],
         bump,
         constraint = op_info.status == OpStatus::None @ CustomError::OpStateInvalid,
@@ -703,6 +1139,22 @@ pub struct LoadOperation<'info> {
     #[account(mut, seeds = [ROOT, b"CONFIG"], bump)]
     config: Box<Account<'info, Config>>,
 
+    /// Processed nonces
+    #[account(
+        init_if_needed,
+        payer = executor,
+        space = ProcessedNonces::LEN,
+        seeds = [
+            ROOT,
+            b"NONCES",
+            &op_data.protocol_id,
+            &op_data.src_chain_id.to_be_bytes(),
+            &ProcessedNonces::window(op_data.nonce).to_be_bytes()
+        ],
+        bump
+    )]
+    processed_nonces: Box<Account<'info, ProcessedNonces>>,
+
     /// System program
     system_program: Program<'info, System>,
 }
@@ -717,12 +1169,14 @@ pub struct LoadOperation<'info> {
 ///
 /// * `executor` - The executor account, which must be a signer and mutable, and should be an authorized executor.
 /// * `op_info` - The operation information account, which is mutable and identified using seeds and a bump.
-///               It should be in either the `Init` or `Signed` state.
+///   It should be in either the `Init` or `Signed` state.
 /// * `protocol_info` - The protocol information account, identified using seeds and a bump.
+/// * `system_program` - The system program, used to grow `op_info` along with the transmitter set.
 ///
 /// # Arguments
 ///
 /// * `op_hash` - The hash of the operation.
+#[cfg_attr(feature = "event-cpi", event_cpi)]
 #[derive(Accounts)]
 #[instruction(op_hash: Vec<u8>)]
 pub struct SignOperation<'info> {
@@ -730,7 +1184,7 @@ pub struct SignOperation<'info> {
     #[account(
         signer,
         mut,
-        constraint = protocol_info.executors.contains(&executor.key()) @ CustomError::ExecutorIsNotAllowed
+        constraint = protocol_info.is_executor(&executor.key()) @ CustomError::ExecutorIsNotAllowed
     )]
     executor: Signer<'info>,
 
@@ -749,6 +1203,9 @@ pub struct SignOperation<'info> {
         bump
     )]
     protocol_info: Box<Account<'info, ProtocolInfo>>,
+
+    /// System program
+    system_program: Program<'info, System>,
 }
 
 /// Represents the context for executing an operation in the Photon cross-chain messaging layer.
@@ -761,14 +1218,18 @@ pub struct SignOperation<'info> {
 ///
 /// * `executor` - The executor account, which must be a signer and mutable, and should be an authorized executor.
 /// * `op_info` - The operation information account, which is mutable and identified using seeds and a bump.
-///               It should be in the `Signed` state.
+///   It should be in the `Signed` state.
 /// * `protocol_info` - The protocol information account, identified using seeds and a bump.
 /// * `call_authority` - is a Program Derived Address (PDA) verified at the photon layer to ensure
-/// it is invoked via cross-program invocation—handled by the photon layer
+///   it is invoked via cross-program invocation—handled by the photon layer
+/// * `config` - The system configuration account, used to check the global pause.
+/// * `ack_nonce` - The proposal nonce of the protocol to the source chain, required only if the operation requests an acknowledgement.
+/// * `system_program` - The system program, required to create the `ack_nonce` account.
 ///
 /// # Arguments
 ///
 /// * `op_hash` - The hash of the operation.
+#[cfg_attr(feature = "event-cpi", event_cpi)]
 #[derive(Accounts)]
 #[instruction(op_hash: Vec<u8>)]
 pub struct ExecuteOperation<'info> {
@@ -776,7 +1237,7 @@ pub struct ExecuteOperation<'info> {
     #[account(
         signer,
         mut,
-        constraint = protocol_info.executors.contains(&executor.key()) @ CustomError::ExecutorIsNotAllowed
+        constraint = protocol_info.is_executor(&executor.key()) @ CustomError::ExecutorIsNotAllowed
     )]
     executor: Signer<'info>,
 
@@ -803,6 +1264,86 @@ pub struct ExecuteOperation<'info> {
         bump
     )]
     call_authority: AccountInfo<'info>,
+
+    /// System config
+    #[account(seeds = [ROOT, b"CONFIG"], bump)]
+    config: Box<Account<'info, Config>>,
+
+    /// Acknowledgement proposal nonce
+    #[account(
+        init_if_needed,
+        payer = executor,
+        space = ProposeNonce::LEN,
+        seeds = [ROOT, b"NONCE", &op_info.op_data.protocol_id, &op_info.op_data.src_chain_id.to_be_bytes()],
+        bump
+    )]
+    ack_nonce: Option<Box<Account<'info, ProposeNonce>>>,
+
+    /// System program
+    system_program: Option<Program<'info, System>>,
+}
+
+/// Represents the context for reporting the failure of an operation in the Photon cross-chain
+/// messaging layer.
+///
+/// This struct is used as a context for the `report_operation_failure` method.
+///
+/// # Fields
+///
+/// * `executor` - The executor account, which must be a signer and an authorized executor.
+/// * `op_info` - The operation information account, which is mutable and should be in the `Signed` state.
+/// * `protocol_info` - The protocol information account.
+/// * `config` - The system configuration account, used to check the global pause.
+/// * `ack_nonce` - The proposal nonce of the protocol to the source chain, sequencing the acknowledgement.
+/// * `system_program` - The system program, required to create the `ack_nonce` account.
+///
+/// # Arguments
+///
+/// * `op_hash` - The hash of the operation.
+#[cfg_attr(feature = "event-cpi", event_cpi)]
+#[derive(Accounts)]
+#[instruction(op_hash: Vec<u8>)]
+pub struct ReportOperationFailure<'info> {
+    /// Executor account
+    #[account(
+        signer,
+        mut,
+        constraint = protocol_info.is_executor(&executor.key()) @ CustomError::ExecutorIsNotAllowed
+    )]
+    executor: Signer<'info>,
+
+    /// Operation info
+    #[account(
+        mut,
+        seeds = [ROOT, b"OP", &op_hash],
+        bump,
+        constraint = op_info.status == OpStatus::Signed @ CustomError::OpStateInvalid
+    )]
+    op_info: Box<Account<'info, OpInfo>>,
+
+    /// Protocol info
+    #[account(
+        seeds = [ROOT, b"PROTOCOL", &op_info.op_data.protocol_id],
+        bump
+    )]
+    protocol_info: Box<Account<'info, ProtocolInfo>>,
+
+    /// System config
+    #[account(seeds = [ROOT, b"CONFIG"], bump)]
+    config: Box<Account<'info, Config>>,
+
+    /// Acknowledgement proposal nonce
+    #[account(
+        init_if_needed,
+        payer = executor,
+        space = ProposeNonce::LEN,
+        seeds = [ROOT, b"NONCE", &op_info.op_data.protocol_id, &op_info.op_data.src_chain_id.to_be_bytes()],
+        bump
+    )]
+    ack_nonce: Box<Account<'info, ProposeNonce>>,
+
+    /// System program
+    system_program: Program<'info, System>,
 }
 
 /// Represents the accounts context necessary for proposing an operation in the Photon cross-chain messaging layer.
@@ -814,24 +1355,35 @@ pub struct ExecuteOperation<'info> {
 /// # Fields
 ///
 /// * `proposer` - The proposer account, which must be a signer and must be listed as an authorized proposer in the protocol info.
-/// * `config` - The system configuration account, which is mutable and identified using seeds and a bump.
+/// * `config` - The system configuration account, identified using seeds and a bump.
 /// * `protocol_info` - The target protocol information account, identified using seeds and a bump based on the provided `protocol_id`.
+/// * `payer` - The account paying the rent of the accounts created by the proposal.
+/// * `propose_nonce` - The proposal nonce of the protocol to the destination chain.
+/// * `propose_window` - The proposal rate limit window of the protocol, required only if gov has set a rate limit.
+/// * `fee_payer` - The account paying the proposal fee, required only if the protocol charges one.
+/// * `fee_vault` - The protocol fee vault, keeping the lamport fees and owning the token fee accounts.
+/// * `fee_payer_token_account` - The token account the fee is paid from if it is charged in SPL tokens.
+/// * `fee_vault_token_account` - The fee vault token account the SPL token fees are paid to.
+/// * `token_program` - The token program, required for the SPL token fees.
+/// * `system_program` - The system program.
 ///
 /// # Arguments
 ///
 /// * `protocol_id` - The identifier for the protocol, used for deriving the `protocol_info` account.
+/// * `dst_chain_id` - The identifier of the destination chain, used for deriving the `propose_nonce` account.
+#[cfg_attr(feature = "event-cpi", event_cpi)]
 #[derive(Accounts)]
-#[instruction(protocol_id: Vec<u8>)]
+#[instruction(protocol_id: Vec<u8>, dst_chain_id: u128)]
 pub struct Propose<'info> {
     /// Proposer account
     #[account(
         signer,
-        constraint = protocol_info.proposers.contains(&proposer.key()) @ CustomError::ProposerIsNotAllowed
+        constraint = protocol_info.is_proposer(&proposer.key()) @ CustomError::ProposerIsNotAllowed
     )]
     proposer: Signer<'info>,
 
     /// System config
-    #[account(mut, seeds = [ROOT, b"CONFIG"], bump)]
+    #[account(seeds = [ROOT, b"CONFIG"], bump)]
     config: Box<Account<'info, Config>>,
 
     /// Target protocol info
@@ -840,6 +1392,52 @@ pub struct Propose<'info> {
         bump
     )]
     protocol_info: Box<Account<'info, ProtocolInfo>>,
+
+    /// Rent payer
+    #[account(signer, mut)]
+    payer: Signer<'info>,
+
+    /// Proposal nonce
+    #[account(
+        init_if_needed,
+        payer = payer,
+        space = ProposeNonce::LEN,
+        seeds = [ROOT, b"NONCE", &protocol_id, &dst_chain_id.to_be_bytes()],
+        bump
+    )]
+    propose_nonce: Box<Account<'info, ProposeNonce>>,
+
+    /// Proposal rate limit window
+    #[account(
+        init_if_needed,
+        payer = payer,
+        space = ProposeWindow::LEN,
+        seeds = [ROOT, b"PROPOSE_WINDOW", &protocol_id],
+        bump
+    )]
+    propose_window: Option<Box<Account<'info, ProposeWindow>>>,
+
+    /// Proposal fee payer
+    #[account(mut)]
+    fee_payer: Option<Signer<'info>>,
+
+    /// Protocol fee vault
+    #[account(mut, seeds = [ROOT, b"FEE_VAULT", &protocol_id], bump)]
+    fee_vault: Option<SystemAccount<'info>>,
+
+    /// Fee payer token account
+    #[account(mut)]
+    fee_payer_token_account: Option<Box<Account<'info, TokenAccount>>>,
+
+    /// Fee vault token account
+    #[account(mut)]
+    fee_vault_token_account: Option<Box<Account<'info, TokenAccount>>>,
+
+    /// Token program
+    token_program: Option<Program<'info, Token>>,
+
+    /// System program
+    system_program: Program<'info, System>,
 }
 
 /// Represents the account context necessary for receiving and processing a photon message within
@@ -854,12 +1452,13 @@ pub struct Propose<'info> {
 ///
 /// * `executor` - The executor account, which must be a signer, mutable, and an authorized executor within the gov protocol.
 /// * `call_authority` - is a Program Derived Address (PDA) verified at the photon layer to ensure
-/// it is invoked via cross-program invocation—handled by the photon layer
+///   it is invoked via cross-program invocation—handled by the photon layer
 /// * `op_info` - The operation information account, which should be in the `Signed` state.
 /// * `config` - The system configuration account, initialized if needed, with defined space and seeds.
 /// * `gov_info` - The governance protocol information account, which governs the operation.
 /// * `target_protocol_info` - The target protocol information, potentially initialized and set up for the specific operation being handled.
 /// * `system_program` - The system program.
+/// * `pending_change` - The pending change account the operation is queued into or, for `CancelGovChange`, the one it cancels.
 ///
 /// # Arguments
 ///
@@ -874,7 +1473,7 @@ pub struct ReceivePhotonMsg<'info> {
     #[account(
         signer,
         mut,
-        constraint = gov_info.executors.contains(&executor.key()) @ CustomError::ExecutorIsNotAllowed
+        constraint = gov_info.is_executor(&executor.key()) @ CustomError::ExecutorIsNotAllowed
     )]
     executor: Signer<'info>,
 
@@ -904,7 +1503,7 @@ pub struct ReceivePhotonMsg<'info> {
     /// Target protocol info
     #[account(
         init_if_needed,
-        space = ProtocolInfo::LEN,
+        space = ProtocolInfo::INIT_LEN,
         payer = executor,
         seeds = [ROOT, b"PROTOCOL", &gov::target_protocol(&op_info.op_data.function_selector, &op_info.op_data.params)],
         bump
@@ -913,17 +1512,190 @@ pub struct ReceivePhotonMsg<'info> {
 
     /// System program
     system_program: Program<'info, System>,
-}
-
-#[derive(Accounts)]
-pub struct SetAdmin<'info> {
-    /// Deployer address
-    #[account(signer, address = DEPLOYER.parse().expect("Deployer key not set"))]
-    pub deployer: Signer<'info>,
 
-    /// Config address
-    #[account(mut, seeds = [ROOT, b"CONFIG"], bump)]
-    pub config: Box<Account<'info, Config>>,
+    /// Pending gov change
+    /// CHECK: only timelocked and cancel operations use it, so it is created and closed manually
+    #[account(
+        mut,
+        seeds = [ROOT, b"PENDING_GOV", &gov::pending_change_id(&op_info.op_data.function_selector, &op_info.op_data.params)],
+        bump
+    )]
+    pending_change: UncheckedAccount<'info>,
+}
+
+/// Represents the accounts context necessary for applying a timelocked gov operation.
+///
+/// # Fields
+///
+/// * `executor` - The executor account, which must be a signer, mutable, and an authorized executor within the gov protocol.
+/// * `config` - The system configuration account.
+/// * `gov_info` - The governance protocol information account.
+/// * `pending_change` - The queued change, closed to the executor once applied.
+/// * `target_protocol_info` - The protocol information targeted by the queued operation.
+/// * `system_program` - The system program.
+///
+/// # Arguments
+///
+/// * `change_id` - The identifier of the pending change.
+///
+#[derive(Accounts)]
+#[instruction(change_id: Vec<u8>)]
+pub struct ApplyGovChange<'info> {
+    /// Executor account
+    #[account(
+        signer,
+        mut,
+        constraint = gov_info.is_executor(&executor.key()) @ CustomError::ExecutorIsNotAllowed
+    )]
+    executor: Signer<'info>,
+
+    /// System config
+    #[account(mut, seeds = [ROOT, b"CONFIG"], bump)]
+    config: Box<Account<'info, Config>>,
+
+    /// Gov protocol info
+    #[account(
+        seeds = [ROOT, b"PROTOCOL", gov_protocol_id()],
+        bump
+    )]
+    gov_info: Box<Account<'info, ProtocolInfo>>,
+
+    /// Pending gov change
+    #[account(mut, close = executor, seeds = [ROOT, b"PENDING_GOV", &change_id], bump)]
+    pending_change: Box<Account<'info, PendingGovChange>>,
+
+    /// Target protocol info
+    #[account(
+        mut,
+        seeds = [ROOT, b"PROTOCOL", &gov::target_protocol(&pending_change.op_data.function_selector, &pending_change.op_data.params)],
+        bump
+    )]
+    target_protocol_info: Box<Account<'info, ProtocolInfo>>,
+
+    /// System program
+    system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct SetAdmin<'info> {
+    /// Deployer address
+    #[account(signer, address = DEPLOYER.parse().expect("Deployer key not set"))]
+    pub deployer: Signer<'info>,
+
+    /// Config address
+    #[account(mut, seeds = [ROOT, b"CONFIG"], bump)]
+    pub config: Box<Account<'info, Config>>,
+}
+
+#[derive(Accounts)]
+pub struct SetGlobalPaused<'info> {
+    /// Guardian address
+    #[account(
+        signer,
+        constraint = config.is_guardian(&guardian.key()) @ CustomError::IsNotGuardian
+    )]
+    pub guardian: Signer<'info>,
+
+    /// Config address
+    #[account(mut, seeds = [ROOT, b"CONFIG"], bump)]
+    pub config: Box<Account<'info, Config>>,
+}
+
+#[derive(Accounts)]
+#[instruction(protocol_id: Vec<u8>)]
+pub struct SetProtocolPaused<'info> {
+    /// Guardian address
+    #[account(
+        signer,
+        constraint = config.is_guardian(&guardian.key()) @ CustomError::IsNotGuardian
+    )]
+    pub guardian: Signer<'info>,
+
+    /// Config address
+    #[account(seeds = [ROOT, b"CONFIG"], bump)]
+    pub config: Box<Account<'info, Config>>,
+
+    /// Protocol info
+    #[account(mut, seeds = [ROOT, b"PROTOCOL", &protocol_id], bump)]
+    pub protocol_info: Box<Account<'info, ProtocolInfo>>,
+}
+
+#[derive(Accounts)]
+#[instruction(op_hash: Vec<u8>)]
+pub struct MigrateOpInfo<'info> {
+    /// Payer of the rent of the grown account
+    #[account(signer, mut)]
+    pub payer: Signer<'info>,
+
+    /// Operation info stored in the legacy layout
+    /// CHECK: decoded by the migration
+    #[account(mut, seeds = [ROOT, b"OP", &op_hash], bump)]
+    pub op_info: UncheckedAccount<'info>,
+
+    /// System program
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(protocol_id: Vec<u8>)]
+pub struct MigrateProtocolInfo<'info> {
+    /// Payer of the rent of the grown account
+    #[account(signer, mut)]
+    pub payer: Signer<'info>,
+
+    /// Protocol info stored in the legacy layout
+    /// CHECK: decoded by the migration
+    #[account(mut, seeds = [ROOT, b"PROTOCOL", &protocol_id], bump)]
+    pub protocol_info: UncheckedAccount<'info>,
+
+    /// System program
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct MigrateConfig<'info> {
+    /// Payer of the rent of the grown account
+    #[account(signer, mut)]
+    pub payer: Signer<'info>,
+
+    /// Config stored in the legacy layout
+    /// CHECK: decoded by the migration
+    #[account(mut, seeds = [ROOT, b"CONFIG"], bump)]
+    pub config: UncheckedAccount<'info>,
+
+    /// System program
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(protocol_id: Vec<u8>)]
+pub struct WithdrawFees<'info> {
+    /// Admin address
+    #[account(signer, constraint = admin.key() == config.admin @ CustomError::IsNotAdmin)]
+    pub admin: Signer<'info>,
+
+    /// Config address
+    #[account(seeds = [ROOT, b"CONFIG"], bump)]
+    pub config: Box<Account<'info, Config>>,
+
+    /// Protocol fee vault
+    #[account(mut, seeds = [ROOT, b"FEE_VAULT", &protocol_id], bump)]
+    pub fee_vault: SystemAccount<'info>,
+
+    /// Recipient of the lamports, or the token account receiving the tokens
+    /// CHECK: any account can receive the withdrawn fees
+    #[account(mut)]
+    pub recipient: UncheckedAccount<'info>,
+
+    /// Fee vault token account to withdraw the tokens from
+    #[account(mut, token::authority = fee_vault)]
+    pub fee_vault_token_account: Option<Box<Account<'info, TokenAccount>>>,
+
+    /// Token program
+    pub token_program: Option<Program<'info, Token>>,
+
+    /// System program
+    pub system_program: Program<'info, System>,
 }
 
 /// Represents the photon cross-chain messaging configuration stored in a Solana account.
@@ -937,7 +1709,15 @@ pub struct SetAdmin<'info> {
 /// * `admin` - The public key of the administrator.
 /// * `eob_chain_id` - The chain ID for the Entangle Oracle Blockchain.
 /// * `eob_master_smart_contract` - The address of the master smart contract.
-/// * `nonce` - A unique identifier.
+/// * `nonce` - The sequence number of the next proposal gov emits to the EOB master smart contract.
+/// * `guardian` - The key allowed to pause and unpause the layer and its protocols without gov.
+/// * `is_paused` - Indicates whether the whole layer, except for the gov protocol, is paused.
+/// * `gov_timelock` - The delay in seconds before sensitive gov operations apply, zero applies them at once.
+///
+/// # Layout
+///
+/// The config initialized by earlier program versions ends with `nonce` and is rewritten in this
+/// layout by `migrate_config`.
 ///
 /// # Usage
 ///
@@ -949,10 +1729,36 @@ pub struct Config {
     eob_chain_id: u64,
     eob_master_smart_contract: [u8; 32],
     nonce: u64,
+    guardian: Pubkey,
+    is_paused: bool,
+    gov_timelock: u64,
 }
 
 impl Config {
-    pub const LEN: usize = 8 + 32 * 2 + 8 * 2;
+    pub const LEN: usize = 8 + 32 * 3 + 8 * 3 + 1;
+
+    pub fn is_guardian(&self, key: &Pubkey) -> bool {
+        self.guardian != Pubkey::default() && &self.guardian == key
+    }
+
+    /// Fails if either the protocol or the whole layer is paused, the gov protocol ignores the latter
+    pub fn require_not_paused(
+        &self,
+        protocol_id: &[u8],
+        protocol_info: &ProtocolInfo,
+    ) -> Result<()> {
+        require!(!protocol_info.is_paused, CustomError::ProtocolIsPaused);
+        require!(
+            !self.is_paused || protocol_id == gov_protocol_id(),
+            CustomError::ProtocolIsPaused
+        );
+        Ok(())
+    }
+
+    pub(crate) fn set_paused(&mut self, paused: bool) {
+        self.is_paused = paused;
+        emit_pause_event(None, paused);
+    }
 }
 
 /// Represents the information for a protocol within the Photon cross-chain messaging layer.
@@ -962,12 +1768,28 @@ impl Config {
 ///
 /// # Fields
 ///
+/// * `version` - The layout version of the account, `VERSION` once the protocol is initialized.
 /// * `is_init` - Indicates whether the protocol is initialized.
+/// * `is_paused` - Indicates whether operations of the protocol are rejected.
 /// * `consensus_target_rate` - The rate of signing operations to be executed.
 /// * `protocol_address` - The public key of the protocol.
 /// * `transmitters` - The Ethereum addresses of entities that sign operations for execution.
+/// * `transmitter_weights` - The voting weights of the `transmitters`, index for index.
 /// * `executors` - The Solana addresses authorized to execute operations in the Photon Endpoint Solana program.
 /// * `proposers` - The accounts permitted to call the Photon Endpoint for emitting a `Propose` event, which is meant for execution in a destination chain that is not Solana.
+/// * `allowed_dst_chains` - The destination chains the proposers are allowed to propose to, any if empty.
+/// * `propose_rate_limit` - The maximum number of proposals within a slot window, zero if not limited.
+/// * `propose_rate_window` - The length of the proposal rate limit window in slots.
+/// * `propose_fee` - The fee charged per proposal, zero if proposals are free.
+/// * `propose_fee_mint` - The SPL token mint the fee is charged in, lamports if not set.
+///
+/// # Layout
+///
+/// The registries are kept sorted and free of duplicates so membership checks are binary searches.
+/// `transmitter_weights` is kept aligned with `transmitters` by every registry update.
+/// The account is created with `INIT_LEN` bytes and reallocated to `space()` whenever a registry grows.
+/// The accounts initialized by earlier program versions have no `version` and store up to 20 entries
+/// per registry in fixed arrays, they are rewritten in this layout by `migrate_protocol_info`.
 ///
 /// # Usage
 ///
@@ -975,29 +1797,186 @@ impl Config {
 #[account]
 #[derive(Default)]
 pub struct ProtocolInfo {
+    version: u8,
     is_init: bool,
+    is_paused: bool,
     consensus_target_rate: u64,
     protocol_address: Pubkey,
-    transmitters: Box<[EthAddress; 20]>, // cannot use const with anchor
-    executors: Box<[Pubkey; 20]>,
-    proposers: Box<[Pubkey; 20]>,
+    transmitters: Vec<EthAddress>,
+    transmitter_weights: Vec<u64>,
+    executors: Vec<Pubkey>,
+    proposers: Vec<Pubkey>,
+    allowed_dst_chains: Vec<u128>,
+    propose_rate_limit: u64,
+    propose_rate_window: u64,
+    propose_fee: u64,
+    propose_fee_mint: Option<Pubkey>,
 }
 
 impl ProtocolInfo {
-    pub const LEN: usize =
-        8 + 1 + 8 + 32 + (20 * MAX_TRANSMITTERS) + (32 * MAX_EXECUTORS) + (32 * MAX_PROPOSERS);
+    /// Layout version of the protocol info accounts, the legacy ones start with `is_init` instead
+    pub const VERSION: u8 = 2;
+
+    /// Space of a protocol info account with empty registries
+    pub const INIT_LEN: usize = 8 + 1 + 1 + 1 + 8 + 32 + 4 * 5 + 8 * 3 + 1 + 32;
+
+    /// Space required to store the protocol info with its current registries
+    pub fn space(&self) -> usize {
+        Self::INIT_LEN
+            + 20 * self.transmitters.len()
+            + 8 * self.transmitter_weights.len()
+            + 32 * self.executors.len()
+            + 32 * self.proposers.len()
+            + 16 * self.allowed_dst_chains.len()
+    }
+
+    pub fn transmitters(&self) -> &[EthAddress] {
+        &self.transmitters
+    }
+
+    pub fn transmitter_weights(&self) -> &[u64] {
+        &self.transmitter_weights
+    }
+
+    pub fn executors(&self) -> &[Pubkey] {
+        &self.executors
+    }
+
+    pub fn proposers(&self) -> &[Pubkey] {
+        &self.proposers
+    }
 
-    pub fn transmitters(&self) -> Vec<EthAddress> {
-        self.transmitters.into_iter().take_while(|k| k != &EthAddress::default()).collect()
+    pub fn allowed_dst_chains(&self) -> &[u128] {
+        &self.allowed_dst_chains
     }
 
-    pub fn executors(&self) -> Vec<Pubkey> {
-        self.executors.into_iter().take_while(|k| k != &Pubkey::default()).collect()
+    /// Maximum number of proposals and the window length in slots, zero limit if not limited
+    pub fn propose_rate_limit(&self) -> (u64, u64) {
+        (self.propose_rate_limit, self.propose_rate_window)
     }
 
-    pub fn proposers(&self) -> Vec<Pubkey> {
-        self.proposers.into_iter().take_while(|k| k != &Pubkey::default()).collect()
+    /// Fee charged per proposal and the mint it is charged in, lamports if no mint is set
+    pub fn propose_fee(&self) -> (u64, Option<Pubkey>) {
+        (self.propose_fee, self.propose_fee_mint)
+    }
+
+    pub fn is_transmitter(&self, transmitter: &EthAddress) -> bool {
+        self.transmitters.binary_search(transmitter).is_ok()
+    }
+
+    /// Voting weight of the transmitter, zero if it is not registered
+    pub fn transmitter_weight(&self, transmitter: &EthAddress) -> u64 {
+        self.transmitters
+            .binary_search(transmitter)
+            .map(|i| self.transmitter_weights[i])
+            .unwrap_or_default()
+    }
+
+    pub fn total_transmitter_weight(&self) -> u128 {
+        self.transmitter_weights.iter().map(|w| *w as u128).sum()
+    }
+
+    /// Share of the total transmitter weight signed by `signers`, scaled by `RATE_DECIMALS`
+    pub fn consensus_rate(&self, signers: &[EthAddress]) -> u64 {
+        let total_weight = self.total_transmitter_weight();
+        if total_weight == 0 {
+            return 0;
+        }
+        let signed_weight: u128 = signers.iter().map(|x| self.transmitter_weight(x) as u128).sum();
+        (signed_weight * RATE_DECIMALS as u128 / total_weight) as u64
+    }
+
+    pub fn is_executor(&self, executor: &Pubkey) -> bool {
+        self.executors.binary_search(executor).is_ok()
+    }
+
+    pub fn is_proposer(&self, proposer: &Pubkey) -> bool {
+        self.proposers.binary_search(proposer).is_ok()
+    }
+
+    /// Whether the proposers may propose to the chain, any chain is allowed if gov has set none
+    pub fn is_dst_chain_allowed(&self, dst_chain_id: u128) -> bool {
+        self.allowed_dst_chains.is_empty()
+            || self.allowed_dst_chains.binary_search(&dst_chain_id).is_ok()
+    }
+
+    pub(crate) fn add_allowed_dst_chain(&mut self, dst_chain_id: u128) {
+        if let Err(pos) = self.allowed_dst_chains.binary_search(&dst_chain_id) {
+            self.allowed_dst_chains.insert(pos, dst_chain_id);
+        }
+    }
+
+    pub(crate) fn remove_allowed_dst_chain(&mut self, dst_chain_id: u128) {
+        if let Ok(pos) = self.allowed_dst_chains.binary_search(&dst_chain_id) {
+            self.allowed_dst_chains.remove(pos);
+        }
+    }
+
+    pub(crate) fn set_propose_rate_limit(&mut self, max_proposals: u64, window: u64) {
+        self.propose_rate_limit = max_proposals;
+        self.propose_rate_window = window;
+    }
+
+    pub(crate) fn set_propose_fee(&mut self, fee: u64, mint: Option<Pubkey>) {
+        self.propose_fee = fee;
+        self.propose_fee_mint = mint;
+    }
+
+    pub(crate) fn set_paused(&mut self, protocol_id: &[u8], paused: bool) -> Result<()> {
+        require!(protocol_id != gov_protocol_id(), CustomError::GovCannotBePaused);
+        self.is_paused = paused;
+        emit_pause_event(Some(protocol_id.to_vec()), paused);
+        Ok(())
+    }
+
+    pub(crate) fn set_transmitters(&mut self, transmitters: Vec<EthAddress>) {
+        self.transmitters = sorted_registry(transmitters, EthAddress::default());
+        self.transmitter_weights = vec![DEFAULT_TRANSMITTER_WEIGHT; self.transmitters.len()];
+    }
+
+    /// Registers the transmitter with the default weight, returns false if it is already present
+    pub(crate) fn add_transmitter(&mut self, transmitter: EthAddress) -> bool {
+        match self.transmitters.binary_search(&transmitter) {
+            Ok(_) => false,
+            Err(pos) => {
+                self.transmitters.insert(pos, transmitter);
+                self.transmitter_weights.insert(pos, DEFAULT_TRANSMITTER_WEIGHT);
+                true
+            }
+        }
     }
+
+    pub(crate) fn remove_transmitter(&mut self, transmitter: &EthAddress) {
+        if let Ok(pos) = self.transmitters.binary_search(transmitter) {
+            self.transmitters.remove(pos);
+            self.transmitter_weights.remove(pos);
+        }
+    }
+
+    pub(crate) fn set_transmitter_weight(
+        &mut self,
+        transmitter: &EthAddress,
+        weight: u64,
+    ) -> Result<()> {
+        let pos = self
+            .transmitters
+            .binary_search(transmitter)
+            .map_err(|_| CustomError::TransmitterIsNotAllowed)?;
+        self.transmitter_weights[pos] = weight;
+        Ok(())
+    }
+
+    pub(crate) fn set_executors(&mut self, executors: Vec<Pubkey>) {
+        self.executors = sorted_registry(executors, Pubkey::default());
+    }
+}
+
+/// Sorts and deduplicates registry entries dropping the unset ones
+fn sorted_registry<T: Ord>(mut entries: Vec<T>, unset: T) -> Vec<T> {
+    entries.retain(|x| x != &unset);
+    entries.sort_unstable();
+    entries.dedup();
+    entries
 }
 
 /// Represents information about an operation in the Photon cross-chain messaging layer.
@@ -1008,19 +1987,168 @@ impl ProtocolInfo {
 /// # Fields
 ///
 /// * `status` - The current status of the operation.
-/// * `unique_signers` - An array of unique Ethereum addresses that have signed the operation.
 /// * `op_data` - The data related to the operation.
+/// * `unique_signers` - Unique Ethereum addresses of the transmitters that have signed the operation.
+/// * `return_data_hash` - The keccak256 hash of the data returned by the protocol program on execution, if any.
+/// * `signed_at` - The unix timestamp the operation reached the consensus at, zero until then.
+///
+/// # Layout
+///
+/// The signers are stored as a length-prefixed list after `op_data`, so the offsets of `status` and
+/// `op_data` do not depend on the number of collected signatures. The account is allocated for
+/// as many signers as the protocol had transmitters at load time and is grown by `sign_operation`
+/// if the transmitter set has been extended meanwhile. The accounts loaded by earlier program
+/// versions store up to 16 signers in a fixed array ahead of `op_data` and are rewritten in this
+/// layout by `migrate_op_info`.
 #[account]
 #[derive(Default)]
 pub struct OpInfo {
     pub status: OpStatus,
-    unique_signers: [EthAddress; 16],
     pub op_data: OperationData,
+    unique_signers: Vec<EthAddress>,
+    pub return_data_hash: Option<[u8; 32]>,
+    pub signed_at: i64,
 }
 
 impl OpInfo {
+    pub fn len(op_data: &OperationData, signers: usize) -> usize {
+        8 + 1
+            + borsh::to_vec(op_data).expect("fixed struct serialization").len()
+            + 4
+            + 20 * signers
+            + 1
+            + 32
+            + 8
+    }
+
+    pub fn unique_signers(&self) -> &[EthAddress] {
+        &self.unique_signers
+    }
+}
+
+/// Tracks the loaded operation nonces of a protocol from a source chain, one bit per nonce.
+///
+/// Each account covers a window of `NONCES_PER_BITMAP` consecutive nonces and is derived from
+/// `[ROOT, b"NONCES", protocol_id, src_chain_id, window]` with the big-endian `src_chain_id` and `window`.
+///
+/// # Fields
+///
+/// * `bitmap` - The processed flags of the window nonces, the lowest bit of the first byte is the first nonce.
+///
+#[account]
+pub struct ProcessedNonces {
+    bitmap: [u8; NONCES_PER_BITMAP as usize / 8],
+}
+
+impl ProcessedNonces {
+    pub const LEN: usize = 8 + NONCES_PER_BITMAP as usize / 8;
+
+    /// Index of the window the nonce belongs to
+    pub fn window(nonce: u64) -> u64 {
+        nonce / NONCES_PER_BITMAP
+    }
+
+    pub fn is_processed(&self, nonce: u64) -> bool {
+        let (byte, mask) = Self::position(nonce);
+        self.bitmap[byte] & mask != 0
+    }
+
+    /// Marks the nonce as processed, fails if it already is
+    pub(crate) fn mark_processed(&mut self, nonce: u64) -> Result<()> {
+        require!(!self.is_processed(nonce), CustomError::OpAlreadyProcessed);
+        let (byte, mask) = Self::position(nonce);
+        self.bitmap[byte] |= mask;
+        Ok(())
+    }
+
+    fn position(nonce: u64) -> (usize, u8) {
+        let bit = (nonce % NONCES_PER_BITMAP) as usize;
+        (bit / 8, 1 << (bit % 8))
+    }
+}
+
+/// Sequences the proposals of a protocol to a destination chain.
+///
+/// The account is derived from `[ROOT, b"NONCE", protocol_id, dst_chain_id]` with the big-endian
+/// `dst_chain_id` and created by the first proposal to the chain, the acknowledgements of the
+/// operations are sequenced by the account of their source chain.
+///
+/// # Fields
+///
+/// * `nonce` - The sequence number of the next proposal.
+///
+#[account]
+#[derive(Default)]
+pub struct ProposeNonce {
+    nonce: u64,
+}
+
+impl ProposeNonce {
+    pub const LEN: usize = 8 + 8;
+
+    pub fn nonce(&self) -> u64 {
+        self.nonce
+    }
+
+    pub(crate) fn next(&mut self) -> u64 {
+        let nonce = self.nonce;
+        self.nonce += 1;
+        nonce
+    }
+}
+
+/// Counts the proposals of a protocol within its current rate limit window.
+///
+/// The account is derived from `[ROOT, b"PROPOSE_WINDOW", protocol_id]` and created by the first
+/// proposal made while gov has set a rate limit, it is kept apart from the protocol info so that
+/// only the rate limited protocols serialize their proposals on it.
+///
+/// # Fields
+///
+/// * `start_slot` - The slot the current window started at.
+/// * `proposals` - The number of proposals made within the current window.
+///
+#[account]
+#[derive(Default)]
+pub struct ProposeWindow {
+    start_slot: u64,
+    proposals: u64,
+}
+
+impl ProposeWindow {
+    pub const LEN: usize = 8 + 8 + 8;
+
+    /// Counts a proposal within the current slot window, fails if the window limit is reached
+    pub(crate) fn register_proposal(&mut self, slot: u64, rate_limit: (u64, u64)) -> Result<()> {
+        let (max_proposals, window) = rate_limit;
+        if slot >= self.start_slot.saturating_add(window) {
+            self.start_slot = slot;
+            self.proposals = 0;
+        }
+        require!(self.proposals < max_proposals, CustomError::ProposeRateLimitExceeded);
+        self.proposals += 1;
+        Ok(())
+    }
+}
+
+/// Represents a timelocked gov operation waiting for its activation time.
+///
+/// # Fields
+///
+/// * `op_hash` - The hash of the gov operation that queued the change.
+/// * `op_data` - The data of the gov operation, applied as is by `apply_gov_change`.
+/// * `activation_time` - The unix timestamp after which the change can be applied.
+///
+#[account]
+pub struct PendingGovChange {
+    pub op_hash: Vec<u8>,
+    pub op_data: OperationData,
+    pub activation_time: i64,
+}
+
+impl PendingGovChange {
     pub fn len(op_data: &OperationData) -> usize {
-        8 + 1 + 20 * 16 + borsh::to_vec(op_data).expect("fixed struct serialization").len()
+        8 + 4 + 32 + borsh::to_vec(op_data).expect("fixed struct serialization").len() + 8
     }
 }
 
@@ -1033,10 +2161,11 @@ impl OpInfo {
 ///
 /// * `op_hash` - The hash of the operation that has been loaded.
 /// * `executor` - The public key of the account that loaded the operation.
+#[derive(Debug)]
 #[event]
 pub struct ProposalLoaded {
-    op_hash: Vec<u8>,
-    executor: Pubkey,
+    pub op_hash: Vec<u8>,
+    pub executor: Pubkey,
 }
 
 /// Emitted when an operation is approved in the Photon cross-chain messaging layer.
@@ -1050,10 +2179,11 @@ pub struct ProposalLoaded {
 /// * `op_hash` - The hash of the approved operation.
 /// * `executor` - The public key of the account that approved the operation.
 ///
+#[derive(Debug)]
 #[event]
 pub struct ProposalApproved {
-    op_hash: Vec<u8>,
-    executor: Pubkey,
+    pub op_hash: Vec<u8>,
+    pub executor: Pubkey,
 }
 
 /// Emitted when an operation is executed within the Photon cross-chain messaging layer.
@@ -1067,6 +2197,7 @@ pub struct ProposalApproved {
 /// * `op_hash` - The hash of the executed operation.
 /// * `err` - An optional string describing any error that occurred during the execution, if applicable.
 /// * `executor` - The public key of the account that executed the operation.
+/// * `return_data` - The data returned by the protocol program, truncated to `MAX_RETURN_DATA_LEN` bytes.
 ///
 
 #[derive(Debug)]
@@ -1074,6 +2205,20 @@ pub struct ProposalApproved {
 pub struct ProposalExecuted {
     pub op_hash: Vec<u8>,
     pub executor: Pubkey,
+    pub return_data: Vec<u8>,
+}
+
+/// Emitted when an executor reports that an operation can not be executed.
+///
+/// # Fields
+///
+/// * `op_hash` - The hash of the failed operation.
+/// * `executor` - The public key of the account that reported the failure.
+#[derive(Debug)]
+#[event]
+pub struct ProposalFailed {
+    pub op_hash: Vec<u8>,
+    pub executor: Pubkey,
 }
 /// Represents an event emitted when an associated program, registered in the protocol
 /// info as a proposer, proposes an operation.
@@ -1089,11 +2234,15 @@ pub struct ProposalExecuted {
 /// # Fields
 ///
 /// * `protocol_id` - The identifier of the protocol associated with the operation being proposed.
-/// * `nonce` - A unique number that increments with each proposal to ensure the distinctiveness of each event.
+/// * `nonce` - The sequence number of the proposal within its protocol and destination chain, so the EOB
+///   orders proposals by `protocol_id`, `dst_chain_id` and `nonce`. Gov proposals are sequenced by the
+///   config nonce.
 /// * `dst_chain_id` - The identifier of the destination chain where the operation is intended to be executed.
 /// * `protocol_address` - The address of the protocol on the destination chain, represented as a vector of bytes.
 /// * `function_selector` - The function selector for the operation, formatted as a vector of bytes.
 /// * `params` - The parameters required for executing the proposed function, provided as a vector of bytes.
+/// * `fee` - The proposal fee paid into the protocol fee vault, so relayers can prioritise the proposals.
+/// * `fee_mint` - The SPL token mint the fee was paid in, lamports if not set.
 ///
 /// # Usage
 ///
@@ -1110,4 +2259,322 @@ pub struct ProposeEvent {
     pub protocol_address: Vec<u8>,
     pub function_selector: Vec<u8>,
     pub params: Vec<u8>,
+    pub fee: u64,
+    pub fee_mint: Option<Pubkey>,
+}
+
+/// Emitted when the whole messaging layer or a single protocol is paused.
+///
+/// # Fields
+///
+/// * `protocol_id` - The identifier of the paused protocol, `None` for the global pause.
+///
+#[derive(Debug)]
+#[event]
+pub struct Paused {
+    pub protocol_id: Option<Vec<u8>>,
+}
+
+/// Emitted when the whole messaging layer or a single protocol is unpaused.
+///
+/// # Fields
+///
+/// * `protocol_id` - The identifier of the unpaused protocol, `None` for the global pause.
+///
+#[derive(Debug)]
+#[event]
+pub struct Unpaused {
+    pub protocol_id: Option<Vec<u8>>,
+}
+
+fn emit_pause_event(protocol_id: Option<Vec<u8>>, paused: bool) {
+    if paused {
+        emit!(Paused { protocol_id });
+    } else {
+        emit!(Unpaused { protocol_id });
+    }
+}
+
+/// Emitted when a timelocked gov operation is queued instead of being applied.
+///
+/// # Fields
+///
+/// * `change_id` - The identifier of the pending change account.
+/// * `op_hash` - The hash of the queued gov operation.
+/// * `code` - The code of the queued gov operation.
+/// * `activation_time` - The unix timestamp after which the change can be applied.
+///
+#[derive(Debug)]
+#[event]
+pub struct GovChangeQueued {
+    pub change_id: Vec<u8>,
+    pub op_hash: Vec<u8>,
+    pub code: Vec<u8>,
+    pub activation_time: i64,
+}
+
+/// Emitted when a queued gov operation is applied.
+///
+/// # Fields
+///
+/// * `change_id` - The identifier of the pending change account.
+/// * `op_hash` - The hash of the gov operation that queued the change.
+///
+#[derive(Debug)]
+#[event]
+pub struct GovChangeApplied {
+    pub change_id: Vec<u8>,
+    pub op_hash: Vec<u8>,
+}
+
+/// Emitted when a queued gov operation is cancelled through gov.
+///
+/// # Fields
+///
+/// * `change_id` - The identifier of the cancelled pending change account.
+/// * `op_hash` - The hash of the cancelling gov operation.
+///
+#[derive(Debug)]
+#[event]
+pub struct GovChangeCancelled {
+    pub change_id: Vec<u8>,
+    pub op_hash: Vec<u8>,
+}
+
+/// Emitted when gov changes the address of a protocol on Solana.
+///
+/// # Fields
+///
+/// * `protocol_id` - The identifier of the changed protocol.
+/// * `old_address` - The previous protocol address, default if it was not set.
+/// * `new_address` - The new protocol address, default if it was removed.
+///
+#[derive(Debug)]
+#[event]
+pub struct ProtocolAddressChanged {
+    pub protocol_id: Vec<u8>,
+    pub old_address: Pubkey,
+    pub new_address: Pubkey,
+}
+
+/// Emitted when gov allows a new proposer for a protocol.
+///
+/// # Fields
+///
+/// * `protocol_id` - The identifier of the changed protocol.
+/// * `proposer` - The allowed proposer.
+///
+#[derive(Debug)]
+#[event]
+pub struct ProposerAdded {
+    pub protocol_id: Vec<u8>,
+    pub proposer: Pubkey,
+}
+
+/// Emitted when gov removes a proposer of a protocol.
+///
+/// # Fields
+///
+/// * `protocol_id` - The identifier of the changed protocol.
+/// * `proposer` - The removed proposer.
+///
+#[derive(Debug)]
+#[event]
+pub struct ProposerRemoved {
+    pub protocol_id: Vec<u8>,
+    pub proposer: Pubkey,
+}
+
+/// Emitted when gov allows a new executor for a protocol.
+///
+/// # Fields
+///
+/// * `protocol_id` - The identifier of the changed protocol.
+/// * `executor` - The allowed executor.
+///
+#[derive(Debug)]
+#[event]
+pub struct ExecutorAdded {
+    pub protocol_id: Vec<u8>,
+    pub executor: Pubkey,
+}
+
+/// Emitted when gov removes an executor of a protocol.
+///
+/// # Fields
+///
+/// * `protocol_id` - The identifier of the changed protocol.
+/// * `executor` - The removed executor.
+///
+#[derive(Debug)]
+#[event]
+pub struct ExecutorRemoved {
+    pub protocol_id: Vec<u8>,
+    pub executor: Pubkey,
+}
+
+/// Emitted when the transmitter set of a protocol is changed by gov.
+///
+/// # Fields
+///
+/// * `protocol_id` - The identifier of the changed protocol.
+/// * `old_transmitters` - The sorted transmitter set before the change.
+/// * `new_transmitters` - The sorted transmitter set after the change.
+///
+#[derive(Debug)]
+#[event]
+pub struct TransmittersUpdated {
+    pub protocol_id: Vec<u8>,
+    pub old_transmitters: Vec<EthAddress>,
+    pub new_transmitters: Vec<EthAddress>,
+}
+
+/// Emitted when gov changes the voting weights of protocol transmitters.
+///
+/// # Fields
+///
+/// * `protocol_id` - The identifier of the changed protocol.
+/// * `transmitters` - The transmitters whose weights were set.
+/// * `old_weights` - The previous weights, in the order of `transmitters`.
+/// * `new_weights` - The new weights, in the order of `transmitters`.
+///
+#[derive(Debug)]
+#[event]
+pub struct TransmitterWeightsUpdated {
+    pub protocol_id: Vec<u8>,
+    pub transmitters: Vec<EthAddress>,
+    pub old_weights: Vec<u64>,
+    pub new_weights: Vec<u64>,
+}
+
+/// Emitted when the consensus target rate of a protocol is changed by gov.
+///
+/// # Fields
+///
+/// * `protocol_id` - The identifier of the changed protocol.
+/// * `old_rate` - The previous consensus target rate, zero for a newly added protocol.
+/// * `new_rate` - The new consensus target rate.
+///
+#[derive(Debug)]
+#[event]
+pub struct ConsensusRateChanged {
+    pub protocol_id: Vec<u8>,
+    pub old_rate: u64,
+    pub new_rate: u64,
+}
+
+/// Emitted when gov changes the guardian allowed to pause the messaging layer.
+///
+/// # Fields
+///
+/// * `old_guardian` - The previous guardian.
+/// * `new_guardian` - The new guardian.
+///
+#[derive(Debug)]
+#[event]
+pub struct GuardianChanged {
+    pub old_guardian: Pubkey,
+    pub new_guardian: Pubkey,
+}
+
+/// Emitted when gov changes the delay of timelocked gov operations.
+///
+/// # Fields
+///
+/// * `old_timelock` - The previous delay in seconds.
+/// * `new_timelock` - The new delay in seconds.
+///
+#[derive(Debug)]
+#[event]
+pub struct GovTimelockChanged {
+    pub old_timelock: u64,
+    pub new_timelock: u64,
+}
+
+/// Emitted when gov deregisters a protocol and closes its account.
+///
+/// # Fields
+///
+/// * `protocol_id` - The identifier of the removed protocol.
+/// * `rent_recipient` - The account the rent of the protocol account is sent to.
+///
+#[derive(Debug)]
+#[event]
+pub struct ProtocolRemoved {
+    pub protocol_id: Vec<u8>,
+    pub rent_recipient: Pubkey,
+}
+
+/// Emitted when gov changes the destination chains a protocol is allowed to propose to.
+///
+/// # Fields
+///
+/// * `protocol_id` - The identifier of the changed protocol.
+/// * `old_dst_chains` - The sorted allowed chains before the change.
+/// * `new_dst_chains` - The sorted allowed chains after the change.
+///
+#[derive(Debug)]
+#[event]
+pub struct AllowedDstChainsUpdated {
+    pub protocol_id: Vec<u8>,
+    pub old_dst_chains: Vec<u128>,
+    pub new_dst_chains: Vec<u128>,
+}
+
+/// Emitted when gov changes the proposal rate limit of a protocol.
+///
+/// # Fields
+///
+/// * `protocol_id` - The identifier of the changed protocol.
+/// * `old_max_proposals` - The previous limit of proposals per window, zero if it was not limited.
+/// * `old_window` - The previous window length in slots.
+/// * `new_max_proposals` - The new limit of proposals per window, zero if it is not limited.
+/// * `new_window` - The new window length in slots.
+///
+#[derive(Debug)]
+#[event]
+pub struct ProposeRateLimitChanged {
+    pub protocol_id: Vec<u8>,
+    pub old_max_proposals: u64,
+    pub old_window: u64,
+    pub new_max_proposals: u64,
+    pub new_window: u64,
+}
+
+/// Emitted when gov changes the proposal fee of a protocol.
+///
+/// # Fields
+///
+/// * `protocol_id` - The identifier of the changed protocol.
+/// * `old_fee` - The previous fee per proposal.
+/// * `old_mint` - The previous fee mint, lamports if not set.
+/// * `new_fee` - The new fee per proposal, zero if proposals are free.
+/// * `new_mint` - The new fee mint, lamports if not set.
+///
+#[derive(Debug)]
+#[event]
+pub struct ProposeFeeChanged {
+    pub protocol_id: Vec<u8>,
+    pub old_fee: u64,
+    pub old_mint: Option<Pubkey>,
+    pub new_fee: u64,
+    pub new_mint: Option<Pubkey>,
+}
+
+/// Emitted when the admin withdraws the collected proposal fees of a protocol.
+///
+/// # Fields
+///
+/// * `protocol_id` - The identifier of the protocol the fees were paid to.
+/// * `recipient` - The account the fees were withdrawn to.
+/// * `amount` - The withdrawn amount.
+/// * `mint` - The withdrawn token mint, lamports if not set.
+///
+#[derive(Debug)]
+#[event]
+pub struct FeesWithdrawn {
+    pub protocol_id: Vec<u8>,
+    pub recipient: Pubkey,
+    pub amount: u64,
+    pub mint: Option<Pubkey>,
 }
@@ -0,0 +1,294 @@
+//! The `migrate` module rewrites the accounts created by earlier versions of the program into their
+//! current layouts.
+//!
+//! ## Overview
+//! Each migrated account type has a legacy counterpart describing the layout it was stored in by the
+//! earlier versions. An account is only migrated if its data decodes in the legacy layout with no bytes
+//! left and passes the checks specific to its type, so a migration can not be applied twice.
+//!
+//! ## Accounts
+//! - `OpInfo`: the signers used to be stored in a fixed array of 16 addresses ahead of `op_data`. The
+//!   legacy `op_data` must hash to the operation hash the account is derived from.
+//! - `ProtocolInfo`: the registries used to be stored in fixed arrays of 20 entries, with the unused
+//!   entries zeroed. The legacy layout has no version and starts with the `is_init` flag.
+//! - `Config`: the legacy layout ends with the gov `nonce`, so it is too short to be decoded in the
+//!   current one.
+//!
+//! ## Rent
+//! The migrations are permissionless. The payer tops up the rent exemption of the accounts growing in
+//! size, the accounts shrinking in size keep their space and lamports.
+
+use anchor_lang::{prelude::*, Discriminator};
+
+use crate::{
+    protocol_data::{OpStatus, OperationData},
+    sorted_registry,
+    util::{realloc_account, EthAddress},
+    Config, OpInfo, ProtocolInfo,
+};
+
+/// Decodes the account data following the discriminator of `T` if it is entirely taken by `L`
+fn decode_exact<T: Discriminator, L: AnchorDeserialize>(data: &[u8]) -> Option<L> {
+    let mut legacy_data = data.strip_prefix(&T::discriminator()[..])?;
+    let legacy = L::deserialize(&mut legacy_data).ok()?;
+    legacy_data.is_empty().then_some(legacy)
+}
+
+/// Rewrites the account data with the migrated account, growing the account if required
+pub(super) fn write_migrated<'info>(
+    account: &AccountInfo<'info>,
+    payer: &AccountInfo<'info>,
+    system_program: &AccountInfo<'info>,
+    migrated: &impl AccountSerialize,
+    space: usize,
+) -> Result<()> {
+    require_keys_eq!(*account.owner, crate::ID, ErrorCode::AccountOwnedByWrongProgram);
+    realloc_account(account, payer, system_program, space)?;
+    let mut data = account.try_borrow_mut_data()?;
+    data.fill(0);
+    migrated.try_serialize(&mut &mut data[..])
+}
+
+/// The `OpInfo` layout with up to 16 signers stored in a fixed array, unused entries are zeroed
+#[derive(AnchorDeserialize)]
+pub struct LegacyOpInfo {
+    pub status: OpStatus,
+    pub unique_signers: [EthAddress; 16],
+    pub op_data: OperationData,
+}
+
+impl LegacyOpInfo {
+    /// Decodes the data of the operation info account derived from `op_hash` if it is stored in the
+    /// legacy layout
+    pub fn decode(data: &[u8], op_hash: &[u8]) -> Option<LegacyOpInfo> {
+        decode_exact::<OpInfo, LegacyOpInfo>(data)
+            .filter(|legacy| legacy.op_data.op_hash_with_message() == op_hash)
+    }
+}
+
+impl From<LegacyOpInfo> for OpInfo {
+    fn from(legacy: LegacyOpInfo) -> Self {
+        OpInfo {
+            status: legacy.status,
+            op_data: legacy.op_data,
+            unique_signers: legacy
+                .unique_signers
+                .into_iter()
+                .filter(|x| x != &EthAddress::default())
+                .collect(),
+            return_data_hash: None,
+            signed_at: 0,
+        }
+    }
+}
+
+/// The `ProtocolInfo` layout with up to 20 entries per registry stored in fixed arrays, the entries
+/// following the first zeroed one are unused
+#[derive(AnchorDeserialize)]
+pub struct LegacyProtocolInfo {
+    pub is_init: bool,
+    pub consensus_target_rate: u64,
+    pub protocol_address: Pubkey,
+    pub transmitters: Box<[EthAddress; 20]>,
+    pub executors: Box<[Pubkey; 20]>,
+    pub proposers: Box<[Pubkey; 20]>,
+}
+
+impl LegacyProtocolInfo {
+    /// Decodes the data of the protocol info account if it is stored in the legacy layout
+    pub fn decode(data: &[u8]) -> Option<LegacyProtocolInfo> {
+        let versioned = data.get(8) == Some(&ProtocolInfo::VERSION);
+        decode_exact::<ProtocolInfo, LegacyProtocolInfo>(data).filter(|_| !versioned)
+    }
+}
+
+impl From<LegacyProtocolInfo> for ProtocolInfo {
+    fn from(legacy: LegacyProtocolInfo) -> Self {
+        fn registry<T: Default + PartialEq, const N: usize>(entries: [T; N]) -> Vec<T> {
+            entries.into_iter().take_while(|x| x != &T::default()).collect()
+        }
+        let mut protocol_info = ProtocolInfo {
+            version: ProtocolInfo::VERSION,
+            is_init: legacy.is_init,
+            consensus_target_rate: legacy.consensus_target_rate,
+            protocol_address: legacy.protocol_address,
+            ..Default::default()
+        };
+        protocol_info.set_transmitters(registry(*legacy.transmitters));
+        protocol_info.set_executors(registry(*legacy.executors));
+        protocol_info.proposers = sorted_registry(registry(*legacy.proposers), Pubkey::default());
+        protocol_info
+    }
+}
+
+/// The `Config` layout with no guardian, pause flag or gov timelock
+#[derive(AnchorDeserialize)]
+pub struct LegacyConfig {
+    pub admin: Pubkey,
+    pub eob_chain_id: u64,
+    pub eob_master_smart_contract: [u8; 32],
+    pub nonce: u64,
+}
+
+impl LegacyConfig {
+    /// Decodes the data of the config account if it is stored in the legacy layout
+    pub fn decode(data: &[u8]) -> Option<LegacyConfig> {
+        let current = Config::try_deserialize(&mut &data[..]).is_ok();
+        decode_exact::<Config, LegacyConfig>(data).filter(|_| !current)
+    }
+}
+
+impl From<LegacyConfig> for Config {
+    fn from(legacy: LegacyConfig) -> Self {
+        Config {
+            admin: legacy.admin,
+            eob_chain_id: legacy.eob_chain_id,
+            eob_master_smart_contract: legacy.eob_master_smart_contract,
+            nonce: legacy.nonce,
+            ..Default::default()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn op_data() -> OperationData {
+        OperationData {
+            protocol_id: b"onefunc_________________________".to_vec(),
+            src_chain_id: 33133,
+            src_block_number: 1,
+            src_op_tx_id: vec![1; 32],
+            nonce: 7,
+            dest_chain_id: 100000000000000000000,
+            function_selector: crate::protocol_data::FunctionSelector::ByCode(vec![1, 2]),
+            params: vec![3; 40],
+            ..Default::default()
+        }
+    }
+
+    /// An `OpInfo` account as stored by the earlier program versions
+    fn legacy_op_info(signers: &[EthAddress]) -> Vec<u8> {
+        let mut data = OpInfo::discriminator().to_vec();
+        data.push(OpStatus::Signed as u8);
+        for i in 0..16 {
+            data.extend_from_slice(&signers.get(i).copied().unwrap_or_default());
+        }
+        data.extend(borsh::to_vec(&op_data()).unwrap());
+        data
+    }
+
+    #[test]
+    fn test_legacy_op_info_decoded() {
+        let signers = [[1; 20], [2; 20], [3; 20]];
+        let data = legacy_op_info(&signers);
+        assert!(OpInfo::try_deserialize(&mut &data[..]).is_err());
+
+        let op_hash = op_data().op_hash_with_message();
+        let legacy = LegacyOpInfo::decode(&data, &op_hash).expect("Expected legacy layout");
+        let op_info = OpInfo::from(legacy);
+        assert!(op_info.status == OpStatus::Signed);
+        assert_eq!(op_info.unique_signers(), &signers);
+        assert_eq!(op_info.op_data.nonce, 7);
+        assert_eq!(op_info.op_data.params, vec![3; 40]);
+        assert_eq!(op_info.return_data_hash, None);
+
+        let mut migrated = vec![0; OpInfo::len(&op_info.op_data, signers.len())];
+        op_info.try_serialize(&mut &mut migrated[..]).unwrap();
+        let op_info = OpInfo::try_deserialize(&mut &migrated[..]).unwrap();
+        assert_eq!(op_info.unique_signers(), &signers);
+        assert!(LegacyOpInfo::decode(&migrated, &op_hash).is_none());
+    }
+
+    #[test]
+    fn test_unsigned_legacy_op_info_decoded() {
+        // The zeroed signers array of an operation without signatures also decodes in the current layout
+        let data = legacy_op_info(&[]);
+        let op_hash = op_data().op_hash_with_message();
+        let op_info = OpInfo::from(LegacyOpInfo::decode(&data, &op_hash).unwrap());
+        assert!(op_info.unique_signers().is_empty());
+        assert_eq!(op_info.op_data.nonce, 7);
+    }
+
+    #[test]
+    fn test_mismatched_legacy_op_info_rejected() {
+        let data = legacy_op_info(&[[1; 20]]);
+        let op_hash = op_data().op_hash_with_message();
+        assert!(LegacyOpInfo::decode(&data[..data.len() - 1], &op_hash).is_none());
+        assert!(LegacyOpInfo::decode(&[data.clone(), vec![0]].concat(), &op_hash).is_none());
+        assert!(LegacyOpInfo::decode(&data, &[0; 32]).is_none());
+    }
+
+    /// A `ProtocolInfo` account as stored by the earlier program versions
+    fn legacy_protocol_info(transmitters: &[EthAddress], executors: &[Pubkey]) -> Vec<u8> {
+        let mut data = ProtocolInfo::discriminator().to_vec();
+        data.push(1);
+        data.extend_from_slice(&6000u64.to_le_bytes());
+        data.extend_from_slice(&[7; 32]);
+        for i in 0..20 {
+            data.extend_from_slice(&transmitters.get(i).copied().unwrap_or_default());
+        }
+        for i in 0..20 {
+            data.extend_from_slice(executors.get(i).unwrap_or(&Pubkey::default()).as_ref());
+        }
+        data.extend_from_slice(&[0; 32 * 20]);
+        data
+    }
+
+    #[test]
+    fn test_legacy_protocol_info_decoded() {
+        let data = legacy_protocol_info(&[[2; 20], [1; 20]], &[Pubkey::new_from_array([3; 32])]);
+        assert_eq!(data.len(), 1729);
+        assert!(ProtocolInfo::try_deserialize(&mut &data[..]).is_err());
+
+        let legacy = LegacyProtocolInfo::decode(&data).expect("Expected legacy layout");
+        let protocol_info = ProtocolInfo::from(legacy);
+        assert!(protocol_info.is_init);
+        assert_eq!(protocol_info.consensus_target_rate, 6000);
+        assert_eq!(protocol_info.protocol_address, Pubkey::new_from_array([7; 32]));
+        assert_eq!(protocol_info.transmitters(), &[[1; 20], [2; 20]]);
+        assert_eq!(protocol_info.transmitter_weights(), &[1, 1]);
+        assert_eq!(protocol_info.executors(), &[Pubkey::new_from_array([3; 32])]);
+        assert!(protocol_info.proposers().is_empty());
+
+        // The migrated account keeps the legacy size if it needs less space
+        let mut migrated = vec![0; data.len()];
+        protocol_info.try_serialize(&mut &mut migrated[..]).unwrap();
+        let protocol_info = ProtocolInfo::try_deserialize(&mut &migrated[..]).unwrap();
+        assert_eq!(protocol_info.transmitters(), &[[1; 20], [2; 20]]);
+        assert!(LegacyProtocolInfo::decode(&migrated).is_none());
+    }
+
+    #[test]
+    fn test_legacy_protocol_info_without_transmitters_decoded() {
+        let data = legacy_protocol_info(&[], &[]);
+        let protocol_info = ProtocolInfo::from(LegacyProtocolInfo::decode(&data).unwrap());
+        assert!(protocol_info.transmitters().is_empty());
+        assert_eq!(protocol_info.consensus_target_rate, 6000);
+    }
+
+    #[test]
+    fn test_legacy_config_decoded() {
+        let mut data = Config::discriminator().to_vec();
+        data.extend_from_slice(&[5; 32]);
+        data.extend_from_slice(&33133u64.to_le_bytes());
+        data.extend_from_slice(&[6; 32]);
+        data.extend_from_slice(&42u64.to_le_bytes());
+        assert_eq!(data.len(), 88);
+        assert!(Config::try_deserialize(&mut &data[..]).is_err());
+
+        let config = Config::from(LegacyConfig::decode(&data).expect("Expected legacy layout"));
+        assert_eq!(config.admin, Pubkey::new_from_array([5; 32]));
+        assert_eq!(config.eob_chain_id, 33133);
+        assert_eq!(config.eob_master_smart_contract, [6; 32]);
+        assert_eq!(config.nonce, 42);
+        assert_eq!(config.guardian, Pubkey::default());
+        assert!(!config.is_paused);
+
+        let mut migrated = vec![0; Config::LEN];
+        config.try_serialize(&mut &mut migrated[..]).unwrap();
+        assert_eq!(Config::try_deserialize(&mut &migrated[..]).unwrap().nonce, 42);
+        assert!(LegacyConfig::decode(&migrated).is_none());
+    }
+}
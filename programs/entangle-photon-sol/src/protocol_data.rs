@@ -11,6 +11,7 @@
 //!   direct binary code selections and human-readable names.
 //! - `OperationData`: Central structure representing an operation's data throughout its lifecycle,
 //!   including initialization, signing, and execution phases.
+//! - `Meta`: Typed view of the `meta` bytes of an operation, holding its version and feature flags.
 //!
 //! ## Overview
 //! This module encapsulates the core data structures that are used across various stages of operation
@@ -31,7 +32,7 @@
 //! ```rust
 //! let operation_data = OperationData {
 //!     protocol_id: vec![1, 2, 3, 4], // Example protocol ID
-//!     meta: [0; 32], // Metadata for the operation
+//!     meta: Meta::default().encode(), // Metadata for the operation
 //!     src_chain_id: 1, // ID of the source blockchain
 //!     src_block_number: 1024, // Block number on the source chain
 //!     src_op_tx_id: vec![5, 6, 7, 8], // Transaction ID on the source chain
@@ -49,7 +50,7 @@
 //!
 use crate::{
     util::{u128_to_bytes32, u64_to_bytes32, Bytes32, EthAddress},
-    CustomError,
+    CustomError, MAX_OP_PARAMS_LEN, MAX_PROTOCOL_ADDRESS_LEN,
 };
 use anchor_lang::{prelude::*, solana_program::secp256k1_recover::secp256k1_recover};
 use sha3::{Digest, Keccak256};
@@ -63,8 +64,97 @@ pub const fn gov_protocol_id() -> &'static Bytes32 {
     GOV_PROTOCOL_ID
 }
 
-/// Aliasing type for Meta. The `meta` field within the [OperationData] stores protocol version and other subtleties
-pub type Meta = [u8; 32];
+/// Aliasing type for the raw `meta` field within the [OperationData], see [Meta] for its layout
+pub type MetaBytes = [u8; 32];
+
+/// The version of the `meta` layout supported by the program, stored in the last `meta` byte
+pub const META_VERSION: u8 = 1;
+
+/// The `meta` field of the [OperationData], it stores the layout version and the features requested
+/// for the operation.
+///
+/// # Layout
+///
+/// The bytes are read as a big-endian word, so the meta of a plain operation is the number `1` and
+/// is encoded as `[0, .., 0, 1]`.
+///
+/// * bytes `0..30` - Reserved for future use, carried as is.
+/// * byte `30` - The feature flags, see the `FLAG_*` constants, the other bits are reserved.
+/// * byte `31` - The layout version, only [META_VERSION] is supported.
+///
+/// Example:
+///
+/// ```rust
+/// use photon::protocol_data::Meta;
+///
+/// fn main() -> anchor_lang::Result<()> {
+///     let meta = Meta::new(Meta::FLAG_FAILURE_CALLBACK);
+///     let decoded = Meta::decode(&meta.encode())?;
+///     assert!(decoded.has_flag(Meta::FLAG_FAILURE_CALLBACK));
+///     Ok(())
+/// }
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Meta {
+    pub version: u8,
+    pub flags: u8,
+    pub reserved: [u8; 30],
+}
+
+impl Meta {
+    /// Reserved, it was assigned to the expiry which is not implemented. The flag is ignored and
+    /// must not be reused for another feature, meta already signed with it would request that one
+    pub const FLAG_RESERVED_EXPIRY: u8 = 1 << 0;
+
+    /// The source chain requests a callback with the execution outcome, the failure included, sent
+    /// back to the protocol address carried in the `reserved` field of the operation
+    pub const FLAG_FAILURE_CALLBACK: u8 = 1 << 1;
+
+    /// Meta of the supported version with the given flags
+    pub fn new(flags: u8) -> Meta {
+        Meta {
+            flags,
+            ..Default::default()
+        }
+    }
+
+    pub fn has_flag(&self, flag: u8) -> bool {
+        self.flags & flag == flag
+    }
+
+    pub fn encode(&self) -> MetaBytes {
+        let mut bytes = [0; 32];
+        bytes[..30].copy_from_slice(&self.reserved);
+        bytes[30] = self.flags;
+        bytes[31] = self.version;
+        bytes
+    }
+
+    /// Decodes the meta bytes, fails if their version is not supported
+    pub fn decode(bytes: &MetaBytes) -> std::result::Result<Meta, CustomError> {
+        if bytes[31] != META_VERSION {
+            return Err(CustomError::UnsupportedMetaVersion);
+        }
+        Ok(Meta {
+            version: bytes[31],
+            flags: bytes[30],
+            reserved: bytes[..30].try_into().expect("fixed meta layout"),
+        })
+    }
+}
+
+impl Default for Meta {
+    fn default() -> Self {
+        Meta {
+            version: META_VERSION,
+            flags: 0,
+            reserved: [0; 30],
+        }
+    }
+}
+
+/// The maximum length of a function selector code or name
+pub const MAX_SELECTOR_LEN: usize = 32;
 
 const MSG: &str = "\x19Ethereum Signed Message:\n32";
 
@@ -96,42 +186,59 @@ impl FunctionSelector {
     pub fn to_bytes(&self) -> std::result::Result<Vec<u8>, CustomError> {
         match self {
             FunctionSelector::ByCode(code) => {
-                if code.len() > 32 {
+                if code.len() > MAX_SELECTOR_LEN {
                     return Err(CustomError::SelectorTooBig);
                 }
                 Ok([&[0_u8, code.len() as u8][..], code].concat())
             }
             FunctionSelector::ByName(name) => {
-                if name.as_bytes().len() > 32 {
+                if name.len() > MAX_SELECTOR_LEN {
                     return Err(CustomError::SelectorTooBig);
                 }
-                Ok([&[1_u8, name.as_bytes().len() as u8][..], name.as_bytes()].concat())
+                Ok([&[1_u8, name.len() as u8][..], name.as_bytes()].concat())
             }
             FunctionSelector::Dummy => Ok(vec![2_u8, 0]),
         }
     }
+
+    /// Checks the selector can be executed: it is either a code or a name of a bounded, non-zero length
+    pub fn validate(&self) -> std::result::Result<(), CustomError> {
+        let len = match self {
+            FunctionSelector::ByCode(code) => code.len(),
+            FunctionSelector::ByName(name) => name.len(),
+            FunctionSelector::Dummy => return Err(CustomError::InvalidSelectorType),
+        };
+        if len == 0 {
+            return Err(CustomError::InvalidSelectorLength);
+        }
+        if len > MAX_SELECTOR_LEN {
+            return Err(CustomError::SelectorTooBig);
+        }
+        Ok(())
+    }
 }
 
+/// Decodes the selector from its `[type, length, selector..]` encoding, the length has to match the
+/// actual selector length
 impl TryFrom<&[u8]> for FunctionSelector {
-    type Error = Vec<u8>;
-    fn try_from(value: &[u8]) -> std::result::Result<Self, Vec<u8>> {
-        let mut iter = value.iter().copied();
-        Ok(match iter.next().ok_or(vec![])? {
-            0 => {
-                let _len = iter.next().ok_or(vec![])?;
-                let code: Vec<u8> = iter.collect();
-                FunctionSelector::ByCode(code)
-            }
-            1 => {
-                let _len = iter.next().ok_or(vec![])?;
-                let name: Vec<u8> = iter.collect();
-                FunctionSelector::ByName(String::from_utf8(name).map_err(|err| {
-                    log::error!("Failed to get function_selector by name from data: {}", err);
-                    vec![]
-                })?)
-            }
-            _ => panic!("Unexpected function_selector type byte"),
-        })
+    type Error = CustomError;
+    fn try_from(value: &[u8]) -> std::result::Result<Self, CustomError> {
+        let [selector_type, len, selector @ ..] = value else {
+            return Err(CustomError::InvalidSelectorLength);
+        };
+        if *len as usize != selector.len() {
+            return Err(CustomError::InvalidSelectorLength);
+        }
+        let function_selector = match selector_type {
+            0 => FunctionSelector::ByCode(selector.to_vec()),
+            1 => FunctionSelector::ByName(
+                String::from_utf8(selector.to_vec())
+                    .map_err(|_| CustomError::InvalidSelectorName)?,
+            ),
+            _ => return Err(CustomError::InvalidSelectorType),
+        };
+        function_selector.validate()?;
+        Ok(function_selector)
     }
 }
 
@@ -140,7 +247,7 @@ impl TryFrom<&[u8]> for FunctionSelector {
 #[derive(Clone, AnchorSerialize, AnchorDeserialize, Debug, Default)]
 pub struct OperationData {
     pub protocol_id: Vec<u8>, // [u8; 32] is zeroed out due to bug
-    pub meta: Meta,
+    pub meta: MetaBytes,
     pub src_chain_id: u128,
     pub src_block_number: u64,
     pub src_op_tx_id: Vec<u8>,
@@ -153,6 +260,31 @@ pub struct OperationData {
 }
 
 impl OperationData {
+    /// Checks the operation data is well-formed before it is loaded: the protocol id is a non-zero
+    /// 32 bytes identifier, the `meta` version is supported, the acknowledgement address is set if
+    /// an acknowledgement is requested, the function selector is valid and the params size is bounded
+    pub fn validate(&self) -> std::result::Result<(), CustomError> {
+        if self.protocol_id.len() != 32 || self.protocol_id == [0; 32] {
+            return Err(CustomError::InvalidProtocolId);
+        }
+        let meta = self.meta()?;
+        if meta.has_flag(Meta::FLAG_FAILURE_CALLBACK)
+            && (self.reserved.is_empty() || self.reserved.len() > MAX_PROTOCOL_ADDRESS_LEN)
+        {
+            return Err(CustomError::InvalidProtocolAddressLength);
+        }
+        self.function_selector.validate()?;
+        if self.params.len() > MAX_OP_PARAMS_LEN {
+            return Err(CustomError::OpParamsTooBig);
+        }
+        Ok(())
+    }
+
+    /// Decodes the typed `meta` of the operation
+    pub fn meta(&self) -> std::result::Result<Meta, CustomError> {
+        Meta::decode(&self.meta)
+    }
+
     fn op_data_evm(&self) -> Vec<u8> {
         let mut buf = Vec::new();
         buf.extend_from_slice(&self.protocol_id);
@@ -172,7 +304,8 @@ impl OperationData {
                 buf.extend_from_slice(&[1, name.len() as u8]);
                 buf.extend_from_slice(name.as_bytes())
             }
-            FunctionSelector::Dummy => panic!("function_selector is not initialized"),
+            // Rejected by `validate`, encoded the same way as `to_bytes` does
+            FunctionSelector::Dummy => buf.extend_from_slice(&[2, 0]),
         }
         buf.extend_from_slice(&self.params);
         buf.extend_from_slice(&self.reserved);
@@ -267,13 +400,14 @@ pub(crate) fn ecrecover(op_hash: &[u8], sig: &TransmitterSignature) -> Result<Et
 /// # Variants
 ///
 /// * `None` - Represents the default state of an operation before any processing has begun.
-/// This is the initial state when an operation is first created.
+///   This is the initial state when an operation is first created.
 /// * `Init` - Indicates that the operation has been initialized. This state is assigned after the
-/// operation is loaded and ready for further action, such as signing.
+///   operation is loaded and ready for further action, such as signing.
 /// * `Signed` - Signifies that the operation has been signed by the required parties.
-/// This state is crucial for validating that all necessary consents have been obtained before execution.
+///   This state is crucial for validating that all necessary consents have been obtained before execution.
 /// * `Executed` - Marks that the operation has been successfully executed. This final state
-/// confirms that the operation's intended effects have been applied.
+///   confirms that the operation's intended effects have been applied.
+/// * `Failed` - Marks that an executor reported the operation as not executable to the source chain.
 ///
 /// Each state transition reflects significant checkpoints in the handling and processing of cross-chain messages, ensuring that each step is clearly delineated and verified.
 #[derive(AnchorSerialize, AnchorDeserialize, Clone, Default, PartialEq, Eq)]
@@ -284,4 +418,5 @@ pub enum OpStatus {
     Init,
     Signed,
     Executed,
+    Failed,
 }
@@ -20,6 +20,10 @@
 //! ## Internal Utilities
 //! - `sighash`: Generates a signature hash for a given namespace and function name, commonly used for creating a
 //!   consistent identifier for function calls within smart contracts or other programmatic contexts.
+//! - `realloc_account`: Grows a program owned account up to the required size, topping up its rent-exempt balance
+//!   from the payer beforehand.
+//! - `create_pda_account`: Creates a program owned PDA on demand, for accounts only some instruction paths need.
+//! - `close_account`: Returns the lamports of a program owned account and hands it back to the system program.
 //!
 //! ## Usage
 //! The utilities provided by this module are integral to the operation of the Photon messaging layer, ensuring data
@@ -38,6 +42,11 @@
 //! This function is critical for situations where uniform byte arrays are necessary, such as cryptographic operations
 //! or when interfacing with systems that require fixed-length byte arrays.
 
+use anchor_lang::{
+    prelude::*,
+    system_program::{self, allocate, assign, transfer, Allocate, Assign, Transfer},
+};
+
 /// Aliasing type for 32 raw bytes sequence
 pub type Bytes32 = [u8; 32];
 /// Aliasing type for evm compatible shortened address
@@ -66,6 +75,72 @@ pub(super) fn sighash(namespace: &str, name: &str) -> [u8; 8] {
     sighash
 }
 
+pub(super) fn realloc_account<'info>(
+    account: &AccountInfo<'info>,
+    payer: &AccountInfo<'info>,
+    system_program: &AccountInfo<'info>,
+    new_len: usize,
+) -> Result<()> {
+    if new_len <= account.data_len() {
+        return Ok(());
+    }
+    let rent_exempt = Rent::get()?.minimum_balance(new_len);
+    let lamports = rent_exempt.saturating_sub(account.lamports());
+    if lamports > 0 {
+        let cpi_accounts = Transfer {
+            from: payer.clone(),
+            to: account.clone(),
+        };
+        transfer(CpiContext::new(system_program.clone(), cpi_accounts), lamports)?;
+    }
+    account.realloc(new_len, false)?;
+    Ok(())
+}
+
+pub(super) fn create_pda_account<'info>(
+    account: &AccountInfo<'info>,
+    payer: &AccountInfo<'info>,
+    system_program: &AccountInfo<'info>,
+    space: usize,
+    signer_seeds: &[&[u8]],
+) -> Result<()> {
+    // The PDA may already hold lamports sent by anyone, so it is not created with a single call
+    let rent_exempt = Rent::get()?.minimum_balance(space);
+    let lamports = rent_exempt.saturating_sub(account.lamports());
+    if lamports > 0 {
+        let cpi_accounts = Transfer {
+            from: payer.clone(),
+            to: account.clone(),
+        };
+        transfer(CpiContext::new(system_program.clone(), cpi_accounts), lamports)?;
+    }
+    let cpi_accounts = Allocate {
+        account_to_allocate: account.clone(),
+    };
+    allocate(
+        CpiContext::new_with_signer(system_program.clone(), cpi_accounts, &[signer_seeds]),
+        space as u64,
+    )?;
+    let cpi_accounts = Assign {
+        account_to_assign: account.clone(),
+    };
+    assign(
+        CpiContext::new_with_signer(system_program.clone(), cpi_accounts, &[signer_seeds]),
+        &crate::ID,
+    )
+}
+
+pub(super) fn close_account<'info>(
+    account: &AccountInfo<'info>,
+    destination: &AccountInfo<'info>,
+) -> Result<()> {
+    **destination.try_borrow_mut_lamports()? += account.lamports();
+    **account.try_borrow_mut_lamports()? = 0;
+    account.assign(&system_program::ID);
+    account.realloc(0, false)?;
+    Ok(())
+}
+
 /// Checks whether a result is `Ok` and returns the value if it is.
 /// If the result is an `Err`, it triggers an error check using `require_eq!`
 /// and then panics with an `unreachable!` call.
@@ -37,11 +37,11 @@ pub mod onefunc {
         Ok(())
     }
 
-    /// Example call by method name
-    pub fn increment(ctx: Context<Increment>, params: Vec<u8>) -> Result<()> {
+    /// Example call by method name, the returned counter is captured by photon as the return data
+    pub fn increment(ctx: Context<Increment>, params: Vec<u8>) -> Result<u64> {
         let inc_item = decode_increment_item(params);
         ctx.accounts.counter.count += inc_item;
-        Ok(())
+        Ok(ctx.accounts.counter.count)
     }
 
     pub fn to_be_failed(_ctx: Context<ToBeFailed>) -> Result<()> {
@@ -61,7 +61,9 @@ pub mod onefunc {
         Ok(())
     }
     /// Example, call propose within the entangle multichain environment
-    pub fn propose_to_other_chain(ctx: Context<ProposeToOtherChain>) -> Result<()> {
+    pub fn propose_to_other_chain<'info>(
+        ctx: Context<'_, '_, '_, 'info, ProposeToOtherChain<'info>>,
+    ) -> Result<()> {
         let protocol_id: Vec<u8> = PROTOCOL_ID.to_vec();
         let dst_chain_id = 33133_u128;
         let protocol_address: Vec<u8> = vec![1; 20];
@@ -74,6 +76,19 @@ pub mod onefunc {
             proposer: ctx.accounts.proposer.to_account_info(),
             config: ctx.accounts.config.to_account_info(),
             protocol_info: ctx.accounts.protocol_info.to_account_info(),
+            payer: ctx.accounts.owner.to_account_info(),
+            propose_nonce: ctx.accounts.propose_nonce.to_account_info(),
+            propose_window: ctx.accounts.propose_window.as_ref().map(|x| x.to_account_info()),
+            fee_payer: None,
+            fee_vault: None,
+            fee_payer_token_account: None,
+            fee_vault_token_account: None,
+            token_program: None,
+            system_program: ctx.accounts.system_program.to_account_info(),
+            #[cfg(feature = "event-cpi")]
+            event_authority: photon_event_authority(ctx.remaining_accounts)?,
+            #[cfg(feature = "event-cpi")]
+            program: cpi_program.clone(),
         };
         let bump = [ctx.bumps.proposer];
         let proposer_seeds = [ROOT, b"PROPOSER", &bump[..]];
@@ -92,7 +107,9 @@ pub mod onefunc {
         // TODO: implement the `receive_photon_msg` to check if the code based function_selector works well
     }
 
-    pub fn propose_to_other_chain_big_selector(ctx: Context<ProposeToOtherChain>) -> Result<()> {
+    pub fn propose_to_other_chain_big_selector<'info>(
+        ctx: Context<'_, '_, '_, 'info, ProposeToOtherChain<'info>>,
+    ) -> Result<()> {
         let protocol_id: Vec<u8> = PROTOCOL_ID.to_vec();
         let dst_chain_id = 33133_u128;
         let protocol_address: Vec<u8> = vec![1; 20];
@@ -104,6 +121,19 @@ pub mod onefunc {
             proposer: ctx.accounts.proposer.to_account_info(),
             config: ctx.accounts.config.to_account_info(),
             protocol_info: ctx.accounts.protocol_info.to_account_info(),
+            payer: ctx.accounts.owner.to_account_info(),
+            propose_nonce: ctx.accounts.propose_nonce.to_account_info(),
+            propose_window: ctx.accounts.propose_window.as_ref().map(|x| x.to_account_info()),
+            fee_payer: None,
+            fee_vault: None,
+            fee_payer_token_account: None,
+            fee_vault_token_account: None,
+            token_program: None,
+            system_program: ctx.accounts.system_program.to_account_info(),
+            #[cfg(feature = "event-cpi")]
+            event_authority: photon_event_authority(ctx.remaining_accounts)?,
+            #[cfg(feature = "event-cpi")]
+            program: cpi_program.clone(),
         };
         let bump = [ctx.bumps.proposer];
         let proposer_seeds = [ROOT, b"PROPOSER", &bump[..]];
@@ -283,15 +313,31 @@ pub struct ProposeToOtherChain<'info> {
     /// seeds = ["root-0", "CONFIG"]
     /// seeds::program = photon_program
     /// CHECK: Due to be validated within the aggregation spotter program
-    #[account(mut)]
     config: UncheckedAccount<'info>,
 
     /// Protocol info to be used by entangle aggregation spotter program
     /// seeds = ["root-0", "PROTOCOL", "aggregation-gov_________________"]
     /// seeds::program = photon_program
     /// CHECK: Due to be validated within the aggregation spotter program
+    #[account(mut)]
     protocol_info: UncheckedAccount<'info>,
 
+    /// Proposal nonce to be used by entangle aggregation spotter program, created by the first
+    /// proposal to the destination chain
+    /// seeds = ["root-0", "NONCE", "aggregation-gov_________________", dst_chain_id]
+    /// seeds::program = photon_program
+    /// CHECK: Due to be validated within the aggregation spotter program
+    #[account(mut)]
+    propose_nonce: UncheckedAccount<'info>,
+
+    /// Proposal rate limit window to be used by entangle aggregation spotter program if the protocol
+    /// proposals are rate limited
+    /// seeds = ["root-0", "PROPOSE_WINDOW", "aggregation-gov_________________"]
+    /// seeds::program = photon_program
+    /// CHECK: Due to be validated within the aggregation spotter program
+    #[account(mut)]
+    propose_window: Option<UncheckedAccount<'info>>,
+
     /// Proposer account that was registered by the entangle spotter program previously
     /// CHECK: Due to be validated within the aggregation spotter program as a signer and a registered proposer
     #[account(init_if_needed, payer = owner, space = 0, seeds = [ROOT, b"PROPOSER"], bump)]
@@ -301,6 +347,15 @@ pub struct ProposeToOtherChain<'info> {
     system_program: Program<'info, System>,
 }
 
+/// Photon emits the proposal as a self CPI signed by its event authority, passed as the first
+/// remaining account
+#[cfg(feature = "event-cpi")]
+fn photon_event_authority<'info>(
+    remaining_accounts: &[AccountInfo<'info>],
+) -> Result<AccountInfo<'info>> {
+    remaining_accounts.first().cloned().ok_or_else(|| ErrorCode::AccountNotEnoughKeys.into())
+}
+
 fn decode_increment_item(params: Vec<u8>) -> u64 {
     ethabi::decode(&[ParamType::Uint(256)], &params)
         .expect("Expected params to be decoded as ethabi tokens")
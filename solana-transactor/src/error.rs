@@ -11,7 +11,7 @@ pub enum TransactorError {
     #[error("Transaction bundle is empty")]
     EmptyBundle,
     #[error("Client error {0}")]
-    ClientError(#[from] solana_client::client_error::ClientError),
+    ClientError(Box<solana_client::client_error::ClientError>),
     #[error("Instruction error {0}")]
     InstructionError(#[from] solana_sdk::instruction::InstructionError),
     #[error("Transaction partial sign failed {0}")]
@@ -21,3 +21,9 @@ pub enum TransactorError {
     #[error("Instruction too big")]
     InstructionTooBig,
 }
+
+impl From<solana_client::client_error::ClientError> for TransactorError {
+    fn from(err: solana_client::client_error::ClientError) -> Self {
+        TransactorError::ClientError(Box::new(err))
+    }
+}
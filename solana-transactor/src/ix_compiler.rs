@@ -72,7 +72,7 @@ impl IxCompiler {
             &[
                 &[get_compute_units_ix(compute_units)],
                 &self.get_ix_price_if_any()[..],
-                &[ix.clone()],
+                std::slice::from_ref(&ix),
             ]
             .concat(),
             address_lookup_table_accounts,
@@ -89,7 +89,7 @@ impl IxCompiler {
             &[get_compute_units_ix(total_compute_units)],
             &self.get_ix_price_if_any()[..],
             &self.ix_buffer[..],
-            &[ix.clone()],
+            std::slice::from_ref(&ix),
         ]
         .concat();
         let address_lookup_table_accounts_all = [
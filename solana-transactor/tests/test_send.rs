@@ -32,7 +32,7 @@ async fn test_send() {
     let ix_transfer = transfer(&spl_token::ID, &address1, &address2, &k1.pubkey(), &[], 1).unwrap();
     let start = Instant::now();
     let transfers =
-        std::iter::repeat(ix_transfer).take(100).map(|ix| InstructionBundle::new(ix, 100000));
+        std::iter::repeat_n(ix_transfer, 100).map(|ix| InstructionBundle::new(ix, 100000));
     /*transactor
     .run_ix_stream(
         futures::stream::iter(transfers),
@@ -1,5 +1,5 @@
 import * as anchor from "@coral-xyz/anchor";
-import { AnchorError, BorshCoder, EventParser, Program, web3 } from "@coral-xyz/anchor";
+import { AnchorError, Program, web3 } from "@coral-xyz/anchor";
 import { Photon } from "../target/types/photon";
 import { Onefunc } from "../target/types/onefunc";
 import { utf8 } from "@coral-xyz/anchor/dist/cjs/utils/bytes";
@@ -15,8 +15,22 @@ import {
     signOp,
     addTransmitter,
     setConsensusTargetRate,
+    setTransmitterWeights,
+    setProtocolPaused,
+    setGuardian,
+    setGovTimelock,
+    cancelGovChange,
+    pendingGovChangeId,
+    removeAllowedProtocol,
+    addAllowedDstChains,
+    removeAllowedDstChains,
+    setProposeRateLimit,
+    setProposeFee,
     sleep,
     updateTransmitter,
+    eventCpiAccounts,
+    eventCpiRemainingAccounts,
+    parseEvents,
 } from "./utils";
 import { Wallet, ethers, BigNumber } from "ethers";
 import { assert, expect } from "chai";
@@ -28,6 +42,8 @@ const SOLANA_CHAIN_ID = "100000000000000000000";
 const CONSENSUS_TARGET_RATE = 6000;
 const TRANSMITTERS = 3;
 const TRANSMITTERS_PER_CALL = 4;
+const LARGE_TRANSMITTER_SET = 32;
+const NONCES_PER_BITMAP = 4096;
 const GOV_PROTOCOL_ID = Buffer.from(
     utf8.encode(
         "photon-gov\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00"
@@ -58,6 +74,7 @@ describe("photon", () => {
     let transmittersRaw = [];
     let nonce = 0;
     let onefuncProtocol;
+    let onefuncProposeWindow;
 
     before(async () => {
         let tx = await program.provider.connection.requestAirdrop(
@@ -102,6 +119,10 @@ describe("photon", () => {
             [ROOT, utf8.encode("PROTOCOL"), ONE_FUNC_ID],
             program.programId
         )[0];
+        onefuncProposeWindow = web3.PublicKey.findProgramAddressSync(
+            [ROOT, utf8.encode("PROPOSE_WINDOW"), ONE_FUNC_ID],
+            program.programId
+        )[0];
 
         proposer = web3.PublicKey.findProgramAddressSync(
             [ROOT, utf8.encode("PROPOSER")],
@@ -109,14 +130,53 @@ describe("photon", () => {
         )[0];
     });
 
+    function proposeNoncePda(protocolId: Buffer, dstChainId: number): anchor.web3.PublicKey {
+        return web3.PublicKey.findProgramAddressSync(
+            [
+                ROOT,
+                utf8.encode("NONCE"),
+                protocolId,
+                new anchor.BN(dstChainId).toArrayLike(Buffer, "be", 16),
+            ],
+            program.programId
+        )[0];
+    }
+
+    async function proposeNonceOf(protocolId: Buffer, dstChainId: number): Promise<number> {
+        const account = await program.account.proposeNonce.fetchNullable(
+            proposeNoncePda(protocolId, dstChainId)
+        );
+        return account ? account.nonce.toNumber() : 0;
+    }
+
+    function processedNoncesPda(
+        protocolId: Buffer,
+        srcChainId: number,
+        opNonce: number
+    ): anchor.web3.PublicKey {
+        return web3.PublicKey.findProgramAddressSync(
+            [
+                ROOT,
+                utf8.encode("NONCES"),
+                protocolId,
+                new anchor.BN(srcChainId).toArrayLike(Buffer, "be", 16),
+                new anchor.BN(Math.floor(opNonce / NONCES_PER_BITMAP)).toArrayLike(Buffer, "be", 8),
+            ],
+            program.programId
+        )[0];
+    }
+
     async function executeOperation(
         protocolId: Buffer,
         protocolAddr: anchor.web3.PublicKey,
         functionSelector: number | string | Buffer,
         params: Buffer,
         targetProtocol: Buffer,
-        remainingAccounts?: anchor.web3.AccountMeta[]
-    ) {
+        remainingAccounts?: anchor.web3.AccountMeta[],
+        opSigners: Wallet[] = transmitters,
+        opOverrides: object = {},
+        execute: boolean = true
+    ): Promise<anchor.web3.PublicKey> {
         let fs: FunctionSelector;
         if (typeof functionSelector == "number") {
             let functionSelectorBuf = Buffer.alloc(4);
@@ -128,9 +188,9 @@ describe("photon", () => {
             assert(false, "Unexpected functionSelector");
         }
         let meta = new anchor.BN(
-            "0100000000000000000000000000000000000000000000000000000000000000",
+            "0000000000000000000000000000000000000000000000000000000000000001",
             16
-        ).toBuffer();
+        ).toBuffer("be", 32);
         let op = {
             protocolId,
             meta,
@@ -145,6 +205,7 @@ describe("photon", () => {
             functionSelector: fs,
             params,
             reserved: Buffer.from([]),
+            ...opOverrides,
         };
         let op_hash = opHashFull(op);
         let opInfo = web3.PublicKey.findProgramAddressSync(
@@ -155,6 +216,7 @@ describe("photon", () => {
             [ROOT, utf8.encode("PROTOCOL"), op.protocolId],
             program.programId
         )[0];
+        let processedNonces = processedNoncesPda(op.protocolId, EOB_CHAIN_ID, nonce);
         // Load
         let signature = await program.methods
             .loadOperation(op, op_hash)
@@ -163,7 +225,9 @@ describe("photon", () => {
                 protocolInfo,
                 opInfo,
                 config,
+                processedNonces,
                 systemProgram: web3.SystemProgram.programId,
+                ...eventCpiAccounts(program),
             })
             .signers([executor])
             .rpc();
@@ -172,8 +236,8 @@ describe("photon", () => {
         const chunkSize = TRANSMITTERS_PER_CALL;
         // console.debug("load_operation:", signature);
         let signatures = [];
-        for (let i = 0; i < transmitters.length; i++) {
-            const sig = await signOp(transmitters[i], op);
+        for (let i = 0; i < opSigners.length; i++) {
+            const sig = await signOp(opSigners[i], op);
             signatures.push(sig);
         }
         for (let i = 0; i < signatures.length; i += chunkSize) {
@@ -184,11 +248,17 @@ describe("photon", () => {
                     executor: executor.publicKey,
                     opInfo,
                     protocolInfo,
+                    systemProgram: web3.SystemProgram.programId,
+                    ...eventCpiAccounts(program),
                 })
                 .signers([executor])
                 .rpc();
             console.debug("sign_operation:", signature);
         }
+        if (!execute) {
+            nonce++;
+            return opInfo;
+        }
         // Execute
         if (protocolId.equals(GOV_PROTOCOL_ID)) {
             let target_protocol_info_pda = web3.PublicKey.findProgramAddressSync(
@@ -199,6 +269,10 @@ describe("photon", () => {
                 [ROOT, utf8.encode("CONFIG")],
                 program.programId
             )[0];
+            let pending_change_pda = web3.PublicKey.findProgramAddressSync(
+                [ROOT, utf8.encode("PENDING_GOV"), pendingGovChangeId(functionSelector as number, params)],
+                program.programId
+            )[0];
 
             let signature = await program.methods
                 .executeOperation(op_hash)
@@ -207,6 +281,10 @@ describe("photon", () => {
                     opInfo,
                     protocolInfo: govProtocolInfo,
                     callAuthority: govCallAuthority,
+                    config,
+                    ackNonce: null,
+                    systemProgram: null,
+                    ...eventCpiAccounts(program),
                 })
                 .remainingAccounts([
                     { pubkey: program.programId, isSigner: false, isWritable: false },
@@ -214,11 +292,14 @@ describe("photon", () => {
                     { pubkey: govProtocolInfo, isSigner: false, isWritable: true },
                     { pubkey: target_protocol_info_pda, isSigner: false, isWritable: true },
                     { pubkey: web3.SystemProgram.programId, isSigner: false, isWritable: true },
-                ])
+                    { pubkey: pending_change_pda, isSigner: false, isWritable: true },
+                ].concat(remainingAccounts ?? []))
                 .signers([executor])
                 .rpc();
             console.debug("execute_gov_operation:", signature);
         } else {
+            // The acknowledgement is sequenced by the proposal nonce to the source chain
+            const ackRequested = (op.meta[30] & 0x02) != 0;
             let signature = await program.methods
                 .executeOperation(op_hash)
                 .accounts({
@@ -226,6 +307,10 @@ describe("photon", () => {
                     opInfo,
                     protocolInfo,
                     callAuthority,
+                    config,
+                    ackNonce: ackRequested ? proposeNoncePda(op.protocolId, EOB_CHAIN_ID) : null,
+                    systemProgram: web3.SystemProgram.programId,
+                    ...eventCpiAccounts(program),
                 })
                 .signers([executor])
                 .remainingAccounts(remainingAccounts)
@@ -234,6 +319,7 @@ describe("photon", () => {
         }
         console.log("Proposal", nonce, "executed");
         nonce++;
+        return opInfo;
     }
 
 
@@ -286,8 +372,17 @@ describe("photon", () => {
     });
 
     it("setConsensusTargetRate", async () => {
+        let rateChanges = [];
+        const listener = program.addEventListener("ConsensusRateChanged", (event) => {
+            rateChanges.push(event);
+        });
         let params = setConsensusTargetRate(ONE_FUNC_ID, 6000);
         await executeOperation(GOV_PROTOCOL_ID, program.programId, 0x970b6109, params, ONE_FUNC_ID);
+        await new Promise((resolve) => setTimeout(resolve, 1000));
+        await program.removeEventListener(listener);
+        assert.equal(rateChanges.length, 1);
+        assert.equal(rateChanges[0].oldRate.toNumber(), CONSENSUS_TARGET_RATE);
+        assert.equal(rateChanges[0].newRate.toNumber(), 6000);
     });
 
     it("addAllowedProtocolAddress", async () => {
@@ -408,8 +503,8 @@ describe("photon", () => {
         }
 
         let protocolInfo = await program.account.protocolInfo.fetch(onefuncProtocol);
-        let actual = protocolInfo.transmitters.slice(0, 3).map(x => "0x" + Buffer.from(x).toString("hex"));
-        let expected = transmittersRaw.map(x => "0x" + Buffer.from(x).toString("hex"));
+        let actual = protocolInfo.transmitters.map(x => "0x" + Buffer.from(x).toString("hex"));
+        let expected = transmittersRaw.map(x => "0x" + Buffer.from(x).toString("hex")).sort();
         assert.deepEqual(actual, expected);
     });
 
@@ -433,8 +528,8 @@ describe("photon", () => {
         );
 
         let protocolInfo = await program.account.protocolInfo.fetch(onefuncProtocol);
-        let actual = protocolInfo.transmitters.slice(0, 3).map(x => "0x" + Buffer.from(x).toString("hex"));
-        let expected = tempTransmittersRaw.map(x => "0x" + Buffer.from(x).toString("hex"));
+        let actual = protocolInfo.transmitters.map(x => "0x" + Buffer.from(x).toString("hex"));
+        let expected = tempTransmittersRaw.map(x => "0x" + Buffer.from(x).toString("hex")).sort();
 
         assert.deepEqual(actual, expected);
 
@@ -447,15 +542,15 @@ describe("photon", () => {
             ONE_FUNC_ID
         );
         protocolInfo = await program.account.protocolInfo.fetch(onefuncProtocol);
-        actual = protocolInfo.transmitters.slice(0, 3).map(x => "0x" + Buffer.from(x).toString("hex"));
-        expected = transmittersRaw.map(x => "0x" + Buffer.from(x).toString("hex"));
+        actual = protocolInfo.transmitters.map(x => "0x" + Buffer.from(x).toString("hex"));
+        expected = transmittersRaw.map(x => "0x" + Buffer.from(x).toString("hex")).sort();
         assert.deepEqual(actual, expected);
     });
 
     it("executeOperation by name", async () => {
         let params = hexToBytes(ethers.utils.defaultAbiCoder.encode(["uint256"], [3]));
         let keys = [{ isSigner: false, isWritable: true, pubkey: counter }];
-        await executeOperation(
+        const opInfo = await executeOperation(
             ONE_FUNC_ID,
             onefunc.programId,
             "increment",
@@ -465,6 +560,18 @@ describe("photon", () => {
         );
         const state = await onefunc.account.counter.fetch(counter);
         expect(state.count.toNumber()).eq(3);
+        // The counter returned by the protocol is captured as the execution return data
+        const returnData = new anchor.BN(3).toArrayLike(Buffer, "le", 8);
+        const [event] = await eventsOf(opInfo, "ProposalExecuted");
+        expect((event.data.returnData as Buffer).compare(returnData)).eq(
+            0,
+            "Unexpected return data"
+        );
+        const { returnDataHash } = await program.account.opInfo.fetch(opInfo);
+        expect("0x" + Buffer.from(returnDataHash).toString("hex")).eq(
+            ethers.utils.keccak256(returnData),
+            "Unexpected return data hash"
+        );
     });
 
     it("executeOperation by code", async () => {
@@ -482,7 +589,466 @@ describe("photon", () => {
         expect(state.count.toNumber()).eq(3);
     });
 
+    // Waits for the last transaction of the account to emit the event, returns all its events
+    async function eventsOf(account: anchor.web3.PublicKey, lastEvent: string) {
+        const connection = anchor.getProvider().connection;
+        for (let counter = 0; ; counter++) {
+            expect(counter).lte(30, "Transaction has not been found in time");
+            const [last] = await connection.getSignaturesForAddress(
+                account,
+                { limit: 1 },
+                "confirmed"
+            );
+            const tx = await connection.getParsedTransaction(last.signature, {
+                commitment: "confirmed",
+            });
+            const events = parseEvents(program, tx);
+            if (events.some(e => e.name == lastEvent)) {
+                return events;
+            }
+            await sleep(10);
+        }
+    }
+
+    function expectAck(
+        event,
+        opHash: Buffer,
+        ackAddress: Buffer,
+        success: boolean,
+        returnData: Buffer
+    ) {
+        expect(event.name).eq("ProposeEvent");
+        expect((event.data.protocolId as Buffer).compare(ONE_FUNC_ID)).eq(
+            0,
+            "Unexpected protocolId"
+        );
+        expect((event.data.dstChainId as anchor.BN).toNumber()).eq(
+            EOB_CHAIN_ID,
+            "Unexpected dst_chain_id"
+        );
+        expect((event.data.protocolAddress as Buffer).compare(ackAddress)).eq(
+            0,
+            "Unexpected protocolAddress"
+        );
+        let selector = Buffer.alloc(32);
+        selector.writeUInt32BE(0xd87aecbf);
+        expect(
+            (event.data.functionSelector as Buffer).compare(
+                Buffer.concat([Buffer.from([0, 32]), selector])
+            )
+        ).eq(0, "Unexpected function selector");
+        const [ack] = ethers.utils.defaultAbiCoder.decode(
+            ["tuple(bytes32, bool, bytes)"],
+            event.data.params as Buffer
+        );
+        expect(ack[0]).eq("0x" + opHash.toString("hex"), "Unexpected op_hash");
+        expect(ack[1]).eq(success, "Unexpected success");
+        expect(ack[2]).eq("0x" + returnData.toString("hex"), "Unexpected return data");
+    }
+
+    it("executeOperation acknowledged to the source chain", async () => {
+        const ackAddress = Buffer.alloc(20, 2);
+        const ackMeta = new anchor.BN(
+            "0000000000000000000000000000000000000000000000000000000000000201",
+            16
+        ).toBuffer("be", 32);
+        let params = hexToBytes(ethers.utils.defaultAbiCoder.encode(["uint256"], [0]));
+        let keys = [{ isSigner: false, isWritable: true, pubkey: counter }];
+        const ackNonce = await proposeNonceOf(ONE_FUNC_ID, EOB_CHAIN_ID);
+        const opInfo = await executeOperation(
+            ONE_FUNC_ID,
+            onefunc.programId,
+            "increment",
+            params,
+            null,
+            [{ pubkey: onefunc.programId, isSigner: false, isWritable: false }].concat(keys),
+            transmitters,
+            { meta: ackMeta, reserved: ackAddress }
+        );
+        let events = await eventsOf(opInfo, "ProposalExecuted");
+        expect(events.map(e => e.name)).deep.eq(["ProposeEvent", "ProposalExecuted"]);
+        const opHash = events[1].data.opHash as Buffer;
+        const returnData = new anchor.BN(3).toArrayLike(Buffer, "le", 8);
+        expectAck(events[0], opHash, ackAddress, true, returnData);
+        expect((events[0].data.nonce as anchor.BN).toNumber()).eq(ackNonce, "Unexpected nonce");
+
+        // The failure of an operation which is not executed is reported by the executor
+        const failedOpInfo = await executeOperation(
+            ONE_FUNC_ID,
+            onefunc.programId,
+            "increment",
+            params,
+            null,
+            [],
+            transmitters,
+            { meta: ackMeta, reserved: ackAddress },
+            false
+        );
+        const failedOpHash = opHashFull((await program.account.opInfo.fetch(failedOpInfo)).opData);
+        const reportFailure = () =>
+            program.methods
+                .reportOperationFailure(failedOpHash)
+                .accounts({
+                    executor: executor.publicKey,
+                    opInfo: failedOpInfo,
+                    protocolInfo: onefuncProtocol,
+                    config,
+                    ackNonce: proposeNoncePda(ONE_FUNC_ID, EOB_CHAIN_ID),
+                    systemProgram: web3.SystemProgram.programId,
+                    ...eventCpiAccounts(program),
+                })
+                .signers([executor])
+                .rpc({ commitment: "confirmed" });
+        // The other executors get the time to execute the operation before it can be failed
+        try {
+            await reportFailure();
+            assert.ok(false, "Failure report should be delayed");
+        } catch (_err) {
+            assert.isTrue(_err instanceof AnchorError);
+            assert.strictEqual((_err as AnchorError).error.errorMessage, "FailureReportTooEarly");
+        }
+        await sleep(3000);
+        await reportFailure();
+        expect((await program.account.opInfo.fetch(failedOpInfo)).status).deep.eq({ failed: {} });
+        events = await eventsOf(failedOpInfo, "ProposalFailed");
+        expect(events.map(e => e.name)).deep.eq(["ProposeEvent", "ProposalFailed"]);
+        expectAck(events[0], failedOpHash, ackAddress, false, Buffer.from([]));
+        expect((events[0].data.nonce as anchor.BN).toNumber()).eq(
+            ackNonce + 1,
+            "Unexpected nonce"
+        );
+    });
+
+    it("setTransmitterWeights", async () => {
+        let params = setTransmitterWeights(ONE_FUNC_ID, transmittersRaw, [8, 1, 1]);
+        await executeOperation(GOV_PROTOCOL_ID, program.programId, 0x2b28eb2a, params, ONE_FUNC_ID);
+
+        let protocolInfo = await program.account.protocolInfo.fetch(onefuncProtocol);
+        let totalWeight = protocolInfo.transmitterWeights.reduce((acc, x) => acc + x.toNumber(), 0);
+        expect(totalWeight).eq(10);
+
+        // The heaviest transmitter alone reaches the 60% target rate
+        let keys = [{ isSigner: false, isWritable: true, pubkey: counter }];
+        await executeOperation(
+            ONE_FUNC_ID,
+            onefunc.programId,
+            0x01020304,
+            Buffer.from([]),
+            null,
+            [{ pubkey: onefunc.programId, isSigner: false, isWritable: false }].concat(keys),
+            transmitters.slice(0, 1)
+        );
+
+        params = setTransmitterWeights(ONE_FUNC_ID, transmittersRaw, [1, 1, 1]);
+        await executeOperation(GOV_PROTOCOL_ID, program.programId, 0x2b28eb2a, params, ONE_FUNC_ID);
+    });
+
+    it("pause and unpause", async () => {
+        const guardian = anchor.web3.Keypair.generate();
+        let params = setGuardian(GOV_PROTOCOL_ID, guardian.publicKey);
+        await executeOperation(GOV_PROTOCOL_ID, program.programId, 0x062a8a55, params, GOV_PROTOCOL_ID);
+
+        const keys = [
+            { pubkey: onefunc.programId, isSigner: false, isWritable: false },
+            { pubkey: counter, isSigner: false, isWritable: true },
+        ];
+        const expectPaused = async () => {
+            try {
+                await executeOperation(ONE_FUNC_ID, onefunc.programId, 0x01020304, Buffer.from([]), null, keys);
+                assert.ok(false, "Paused protocol should reject operations");
+            } catch (_err) {
+                assert.isTrue(_err instanceof AnchorError);
+                assert.strictEqual((_err as AnchorError).error.errorMessage, "ProtocolIsPaused");
+            }
+        };
+
+        await program.methods
+            .setProtocolPaused(ONE_FUNC_ID, true)
+            .accounts({ guardian: guardian.publicKey, config, protocolInfo: onefuncProtocol })
+            .signers([guardian])
+            .rpc();
+        await expectPaused();
+        await program.methods
+            .setProtocolPaused(ONE_FUNC_ID, false)
+            .accounts({ guardian: guardian.publicKey, config, protocolInfo: onefuncProtocol })
+            .signers([guardian])
+            .rpc();
+
+        // Gov keeps working while the whole layer is paused, so it can lift the pause
+        await program.methods
+            .setGlobalPaused(true)
+            .accounts({ guardian: guardian.publicKey, config })
+            .signers([guardian])
+            .rpc();
+        await expectPaused();
+        params = setProtocolPaused(GOV_PROTOCOL_ID, false);
+        await executeOperation(GOV_PROTOCOL_ID, program.programId, 0x5a01ef28, params, GOV_PROTOCOL_ID);
+        await executeOperation(ONE_FUNC_ID, onefunc.programId, 0x01020304, Buffer.from([]), null, keys);
+    });
+
+    it("timelocked gov operations", async () => {
+        const applyGovChange = async (code: number, params: Buffer, targetProtocol: Buffer) => {
+            const changeId = pendingGovChangeId(code, params);
+            await program.methods
+                .applyGovChange(changeId)
+                .accounts({
+                    executor: executor.publicKey,
+                    config,
+                    govInfo: govProtocolInfo,
+                    pendingChange: web3.PublicKey.findProgramAddressSync(
+                        [ROOT, utf8.encode("PENDING_GOV"), changeId],
+                        program.programId
+                    )[0],
+                    targetProtocolInfo: web3.PublicKey.findProgramAddressSync(
+                        [ROOT, utf8.encode("PROTOCOL"), targetProtocol],
+                        program.programId
+                    )[0],
+                    systemProgram: web3.SystemProgram.programId,
+                })
+                .signers([executor])
+                .rpc();
+        };
+
+        let params = setGovTimelock(GOV_PROTOCOL_ID, 1);
+        await executeOperation(GOV_PROTOCOL_ID, program.programId, 0x1e9e1ec7, params, GOV_PROTOCOL_ID);
+
+        // Sensitive operations are queued instead of being applied
+        params = setConsensusTargetRate(ONE_FUNC_ID, 7000);
+        await executeOperation(GOV_PROTOCOL_ID, program.programId, 0x970b6109, params, ONE_FUNC_ID);
+        let protocolInfo = await program.account.protocolInfo.fetch(onefuncProtocol);
+        expect(protocolInfo.consensusTargetRate.toNumber()).eq(6000);
+
+        // Adding an existing protocol again can not set its rate and transmitters past the timelock
+        try {
+            let addParams = addAllowedProtocol(ONE_FUNC_ID, [], 7000);
+            await executeOperation(GOV_PROTOCOL_ID, program.programId, 0x45a004b9, addParams, ONE_FUNC_ID);
+            assert.ok(false, "Existing protocol should not be added again");
+        } catch (_err) {
+            assert.isTrue(_err instanceof AnchorError);
+            assert.strictEqual((_err as AnchorError).error.errorMessage, "ProtocolAlreadyInit");
+            nonce++;
+        }
+        protocolInfo = await program.account.protocolInfo.fetch(onefuncProtocol);
+        expect(protocolInfo.consensusTargetRate.toNumber()).eq(6000);
+        expect(protocolInfo.transmitters.length).gt(0);
+
+        await sleep(3000);
+        await applyGovChange(0x970b6109, params, ONE_FUNC_ID);
+        protocolInfo = await program.account.protocolInfo.fetch(onefuncProtocol);
+        expect(protocolInfo.consensusTargetRate.toNumber()).eq(7000);
+
+        // Queued changes can be cancelled through gov
+        params = setConsensusTargetRate(ONE_FUNC_ID, 6000);
+        await executeOperation(GOV_PROTOCOL_ID, program.programId, 0x970b6109, params, ONE_FUNC_ID);
+        let cancelParams = cancelGovChange(GOV_PROTOCOL_ID, pendingGovChangeId(0x970b6109, params));
+        await executeOperation(GOV_PROTOCOL_ID, program.programId, 0xdf107031, cancelParams, GOV_PROTOCOL_ID);
+        await sleep(3000);
+        try {
+            await applyGovChange(0x970b6109, params, ONE_FUNC_ID);
+            assert.ok(false, "Cancelled change should not be applied");
+        } catch (_err) {
+            assert.isTrue(_err instanceof AnchorError);
+            assert.strictEqual((_err as AnchorError).error.errorCode.code, "AccountNotInitialized");
+        }
+
+        // Disabling the timelock is timelocked as well
+        let timelockParams = setGovTimelock(GOV_PROTOCOL_ID, 0);
+        await executeOperation(GOV_PROTOCOL_ID, program.programId, 0x1e9e1ec7, timelockParams, GOV_PROTOCOL_ID);
+        await sleep(3000);
+        await applyGovChange(0x1e9e1ec7, timelockParams, GOV_PROTOCOL_ID);
+
+        await executeOperation(GOV_PROTOCOL_ID, program.programId, 0x970b6109, params, ONE_FUNC_ID);
+        protocolInfo = await program.account.protocolInfo.fetch(onefuncProtocol);
+        expect(protocolInfo.consensusTargetRate.toNumber()).eq(6000);
+    });
+
+    // Builds a gov operation replaying the given nonce, the loading only is expected to be tried
+    function govOperation(opNonce: number, params: Buffer, overrides: object = {}) {
+        let functionSelectorBuf = Buffer.alloc(4);
+        functionSelectorBuf.writeUInt32BE(0x84579a53);
+        return {
+            protocolId: GOV_PROTOCOL_ID,
+            meta: new anchor.BN(
+                "0000000000000000000000000000000000000000000000000000000000000001",
+                16
+            ).toBuffer("be", 32),
+            srcChainId: new anchor.BN(EOB_CHAIN_ID),
+            srcBlockNumber: new anchor.BN(1),
+            srcOpTxId: hexToBytes(
+                "ce25f58a7fd8625deadc00a59b67c530c7d92acec1e5753c588269ade6ebf99f"
+            ),
+            nonce: new anchor.BN(opNonce),
+            destChainId: new anchor.BN(SOLANA_CHAIN_ID),
+            protocolAddr: program.programId,
+            functionSelector: { byCode: [functionSelectorBuf] },
+            params,
+            reserved: Buffer.from([]),
+            ...overrides,
+        };
+    }
+
+    async function expectLoadError(op, errMsg: string) {
+        let op_hash = opHashFull(op);
+        try {
+            await program.methods
+                .loadOperation(op, op_hash)
+                .accounts({
+                    executor: executor.publicKey,
+                    protocolInfo: govProtocolInfo,
+                    opInfo: web3.PublicKey.findProgramAddressSync(
+                        [ROOT, utf8.encode("OP"), op_hash],
+                        program.programId
+                    )[0],
+                    config,
+                    processedNonces: processedNoncesPda(
+                        GOV_PROTOCOL_ID,
+                        EOB_CHAIN_ID,
+                        op.nonce.toNumber()
+                    ),
+                    systemProgram: web3.SystemProgram.programId,
+                    ...eventCpiAccounts(program),
+                })
+                .signers([executor])
+                .rpc();
+            assert.ok(false, "Operation should not be loaded, expected " + errMsg);
+        } catch (_err) {
+            assert.isTrue(_err instanceof AnchorError);
+            assert.strictEqual((_err as AnchorError).error.errorMessage, errMsg);
+        }
+    }
+
+    it("loadOperation rejects a replayed source operation", async () => {
+        let params = setProposeRateLimit(ONE_FUNC_ID, 0, 0);
+        await executeOperation(GOV_PROTOCOL_ID, program.programId, 0x84579a53, params, ONE_FUNC_ID);
+
+        // The same source operation with altered meta and reserved bytes hashes differently
+        let op = govOperation(nonce - 1, params, {
+            meta: new anchor.BN(
+                "0100000000000000000000000000000000000000000000000000000000000001",
+                16
+            ).toBuffer(),
+            reserved: Buffer.from([1]),
+        });
+        await expectLoadError(op, "OpAlreadyProcessed");
+    });
+
+    it("loadOperation rejects malformed operation data", async () => {
+        let params = setProposeRateLimit(ONE_FUNC_ID, 0, 0);
+        let op = govOperation(nonce, params, {
+            meta: new anchor.BN(
+                "0000000000000000000000000000000000000000000000000000000000000002",
+                16
+            ).toBuffer("be", 32),
+        });
+        await expectLoadError(op, "UnsupportedMetaVersion");
+        op = govOperation(nonce, params, { functionSelector: { byName: [""] } });
+        await expectLoadError(op, "InvalidSelectorLength");
+    });
+
+    it("executeOperation with full consensus of a large transmitter set", async () => {
+        const protocolId = Buffer.from(utf8.encode("large_transmitter_set___________"));
+        const protocolInfoPda = web3.PublicKey.findProgramAddressSync(
+            [ROOT, utf8.encode("PROTOCOL"), protocolId],
+            program.programId
+        )[0];
+        const signers = randomSigners(LARGE_TRANSMITTER_SET);
+        const signersRaw = signers.map((x) => hexToBytes(x.address));
+
+        let params = addAllowedProtocol(protocolId, [], 10000);
+        await executeOperation(GOV_PROTOCOL_ID, program.programId, 0x45a004b9, params, protocolId);
+        const chunkSize = TRANSMITTERS_PER_CALL;
+        for (let i = 0; i < signersRaw.length; i += chunkSize) {
+            params = addTransmitter(protocolId, signersRaw.slice(i, i + chunkSize));
+            await executeOperation(GOV_PROTOCOL_ID, program.programId, 0x6c5f5666, params, protocolId);
+        }
+        params = addAllowedProtocolAddress(protocolId, onefunc.programId);
+        await executeOperation(GOV_PROTOCOL_ID, program.programId, 0xd296a0ff, params, protocolId);
+        params = addExecutor(protocolId, executor.publicKey);
+        await executeOperation(GOV_PROTOCOL_ID, program.programId, 0xe0aafb68, params, protocolId);
+
+        const protocolInfo = await program.account.protocolInfo.fetch(protocolInfoPda);
+        expect(protocolInfo.transmitters.length).eq(LARGE_TRANSMITTER_SET);
+
+        // All signatures but one are not enough for the 100% target rate
+        try {
+            await executeOperation(
+                protocolId,
+                onefunc.programId,
+                0x01020304,
+                Buffer.from([]),
+                null,
+                [{ pubkey: onefunc.programId, isSigner: false, isWritable: false }],
+                signers.slice(1)
+            );
+            assert.ok(false, "Execution without full consensus should fail");
+        } catch (_err) {
+            assert.isTrue(_err instanceof AnchorError);
+            assert.strictEqual((_err as AnchorError).error.errorMessage, "OpStateInvalid");
+            // The operation stays loaded, so the next one needs a fresh nonce
+            nonce++;
+        }
+
+        const opInfoPda = await executeOperation(
+            protocolId,
+            onefunc.programId,
+            0x01020304,
+            Buffer.from([]),
+            null,
+            [{ pubkey: onefunc.programId, isSigner: false, isWritable: false }],
+            signers
+        );
+        const opInfo = await program.account.opInfo.fetch(opInfoPda);
+        expect(opInfo.status).to.have.property("executed");
+        expect(opInfo.uniqueSigners.length).eq(LARGE_TRANSMITTER_SET);
+    });
+
+    it("removeAllowedProtocol", async () => {
+        const protocolId = Buffer.from(utf8.encode("removed_protocol________________"));
+        const protocolInfoPda = web3.PublicKey.findProgramAddressSync(
+            [ROOT, utf8.encode("PROTOCOL"), protocolId],
+            program.programId
+        )[0];
+        let params = addAllowedProtocol(protocolId, [], CONSENSUS_TARGET_RATE);
+        await executeOperation(GOV_PROTOCOL_ID, program.programId, 0x45a004b9, params, protocolId);
+        const rent = await program.provider.connection.getBalance(protocolInfoPda);
+
+        const rentRecipient = anchor.web3.Keypair.generate().publicKey;
+        const recipientAccounts = [{ pubkey: rentRecipient, isSigner: false, isWritable: true }];
+        params = removeAllowedProtocol(protocolId, rentRecipient);
+        await executeOperation(
+            GOV_PROTOCOL_ID,
+            program.programId,
+            0xe6acf3e8,
+            params,
+            protocolId,
+            recipientAccounts
+        );
+        expect(await program.provider.connection.getAccountInfo(protocolInfoPda)).to.be.null;
+        expect(await program.provider.connection.getBalance(rentRecipient)).eq(rent);
+
+        try {
+            params = removeAllowedProtocol(GOV_PROTOCOL_ID, rentRecipient);
+            await executeOperation(
+                GOV_PROTOCOL_ID,
+                program.programId,
+                0xe6acf3e8,
+                params,
+                GOV_PROTOCOL_ID,
+                recipientAccounts
+            );
+            assert.ok(false, "Gov protocol should not be removable");
+        } catch (_err) {
+            assert.isTrue(_err instanceof AnchorError);
+            assert.strictEqual((_err as AnchorError).error.errorMessage, "GovCannotBeRemoved");
+            nonce++;
+        }
+    });
+
     it("propose", async () => {
+        let params = addAllowedDstChains(ONE_FUNC_ID, [EOB_CHAIN_ID]);
+        await executeOperation(GOV_PROTOCOL_ID, program.programId, 0xe390ac88, params, ONE_FUNC_ID);
+        const configNonce = (await program.account.config.fetch(config)).nonce.toNumber();
+        const expectedNonce = await proposeNonceOf(ONE_FUNC_ID, EOB_CHAIN_ID);
         let signature = await onefunc.methods
             .proposeToOtherChain()
             .accounts({
@@ -491,7 +1057,10 @@ describe("photon", () => {
                 photonProgram: program.programId,
                 config,
                 protocolInfo: onefuncProtocol,
+                proposeWindow: null,
+                proposeNonce: proposeNoncePda(ONE_FUNC_ID, EOB_CHAIN_ID),
             })
+            .remainingAccounts(eventCpiRemainingAccounts(program))
             .signers([owner])
             .rpc();
         let [tx, counter] = [null, 0];
@@ -502,8 +1071,7 @@ describe("photon", () => {
             });
             expect((counter += 1)).lte(30, "Propose transaction has not been found in time");
         }
-        const eventParser = new EventParser(program.programId, new BorshCoder(program.idl));
-        const events = Array.from(eventParser.parseLogs(tx.meta.logMessages));
+        const events = parseEvents(program, tx);
         expect(events.length).eq(1, "Expected exact one ProposeEvent");
         let event = events[0];
         expect(event.name).eq("ProposeEvent");
@@ -531,7 +1099,153 @@ describe("photon", () => {
             0,
             "Unexpected data"
         );
-        expect((event.data.nonce as anchor.BN).toNumber()).eq(1, "Unexpected nonce");
+        expect((event.data.nonce as anchor.BN).toNumber()).eq(expectedNonce, "Unexpected nonce");
+        expect(await proposeNonceOf(ONE_FUNC_ID, EOB_CHAIN_ID)).eq(
+            expectedNonce + 1,
+            "Unexpected protocol nonce"
+        );
+        expect((await program.account.config.fetch(config)).nonce.toNumber()).eq(
+            configNonce,
+            "Config nonce is changed"
+        );
+    });
+
+    it("propose requirements", async () => {
+        const propose = () =>
+            onefunc.methods
+                .proposeToOtherChain()
+                .accounts({
+                    owner: owner.publicKey,
+                    proposer,
+                    photonProgram: program.programId,
+                    config,
+                    protocolInfo: onefuncProtocol,
+                    proposeWindow: onefuncProposeWindow,
+                    proposeNonce: proposeNoncePda(ONE_FUNC_ID, EOB_CHAIN_ID),
+                })
+                .remainingAccounts(eventCpiRemainingAccounts(program))
+                .signers([owner])
+                .rpc();
+        const expectProposeError = async (errMsg: string) => {
+            try {
+                await propose();
+                assert.ok(false, "Propose should fail with " + errMsg);
+            } catch (_err) {
+                assert.isTrue(_err instanceof AnchorError);
+                assert.strictEqual((_err as AnchorError).error.errorMessage, errMsg);
+            }
+        };
+
+        // An empty allowlist does not restrict the destination chains
+        let params = removeAllowedDstChains(ONE_FUNC_ID, [EOB_CHAIN_ID]);
+        await executeOperation(GOV_PROTOCOL_ID, program.programId, 0xf59219a7, params, ONE_FUNC_ID);
+        await propose();
+        params = addAllowedDstChains(ONE_FUNC_ID, [EOB_CHAIN_ID + 1]);
+        await executeOperation(GOV_PROTOCOL_ID, program.programId, 0xe390ac88, params, ONE_FUNC_ID);
+        await expectProposeError("DstChainIsNotAllowed");
+        params = addAllowedDstChains(ONE_FUNC_ID, [EOB_CHAIN_ID]);
+        await executeOperation(GOV_PROTOCOL_ID, program.programId, 0xe390ac88, params, ONE_FUNC_ID);
+        params = removeAllowedDstChains(ONE_FUNC_ID, [EOB_CHAIN_ID + 1]);
+        await executeOperation(GOV_PROTOCOL_ID, program.programId, 0xf59219a7, params, ONE_FUNC_ID);
+
+        params = setProposeRateLimit(ONE_FUNC_ID, 1, 1000);
+        await executeOperation(GOV_PROTOCOL_ID, program.programId, 0x84579a53, params, ONE_FUNC_ID);
+        await propose();
+        await expectProposeError("ProposeRateLimitExceeded");
+        params = setProposeRateLimit(ONE_FUNC_ID, 0, 0);
+        await executeOperation(GOV_PROTOCOL_ID, program.programId, 0x84579a53, params, ONE_FUNC_ID);
+        await propose();
+    });
+
+    it("propose fee", async () => {
+        const FEE = 1000;
+        let feeVault = web3.PublicKey.findProgramAddressSync(
+            [ROOT, utf8.encode("FEE_VAULT"), ONE_FUNC_ID],
+            program.programId
+        )[0];
+        let params = setProposeFee(ONE_FUNC_ID, FEE);
+        await executeOperation(GOV_PROTOCOL_ID, program.programId, 0x5688e481, params, ONE_FUNC_ID);
+        try {
+            await onefunc.methods
+                .proposeToOtherChain()
+                .accounts({
+                    owner: owner.publicKey,
+                    proposer,
+                    photonProgram: program.programId,
+                    config,
+                    protocolInfo: onefuncProtocol,
+                    proposeWindow: null,
+                    proposeNonce: proposeNoncePda(ONE_FUNC_ID, EOB_CHAIN_ID),
+                })
+                .remainingAccounts(eventCpiRemainingAccounts(program))
+                .signers([owner])
+                .rpc();
+            assert.ok(false, "Propose without the fee accounts should fail");
+        } catch (_err) {
+            assert.isTrue(_err instanceof AnchorError);
+            assert.strictEqual((_err as AnchorError).error.errorMessage, "FeeAccountsNotProvided");
+        }
+
+        // The owner proposes directly paying the fee
+        params = addExecutor(ONE_FUNC_ID, owner.publicKey);
+        await executeOperation(GOV_PROTOCOL_ID, program.programId, 0xce0940a5, params, ONE_FUNC_ID);
+        await program.methods
+            .propose(
+                ONE_FUNC_ID,
+                new anchor.BN(EOB_CHAIN_ID),
+                Buffer.alloc(20, 1),
+                { byName: ["fee_paid"] },
+                utf8.encode("an arbitrary data")
+            )
+            .accounts({
+                proposer: owner.publicKey,
+                config,
+                protocolInfo: onefuncProtocol,
+                payer: owner.publicKey,
+                proposeWindow: null,
+                proposeNonce: proposeNoncePda(ONE_FUNC_ID, EOB_CHAIN_ID),
+                feePayer: owner.publicKey,
+                feeVault,
+                feePayerTokenAccount: null,
+                feeVaultTokenAccount: null,
+                tokenProgram: null,
+                systemProgram: web3.SystemProgram.programId,
+                ...eventCpiAccounts(program),
+            })
+            .signers([owner])
+            .rpc();
+        const rentExempt = await program.provider.connection.getMinimumBalanceForRentExemption(0);
+        expect(await program.provider.connection.getBalance(feeVault)).eq(rentExempt + FEE);
+
+        // Only the collected fee can be withdrawn, the vault stays rent exempt
+        const withdraw = (amount: number) =>
+            program.methods
+                .withdrawFees(ONE_FUNC_ID, new anchor.BN(amount))
+                .accounts({
+                    admin: owner.publicKey,
+                    config,
+                    feeVault,
+                    recipient: executor.publicKey,
+                    feeVaultTokenAccount: null,
+                    tokenProgram: null,
+                    systemProgram: web3.SystemProgram.programId,
+                })
+                .signers([owner])
+                .rpc();
+        try {
+            await withdraw(FEE + 1);
+            assert.ok(false, "Withdrawing more than collected should fail");
+        } catch (_err) {
+            assert.isTrue(_err instanceof AnchorError);
+            assert.strictEqual((_err as AnchorError).error.errorMessage, "InsufficientFees");
+        }
+        await withdraw(FEE);
+        expect(await program.provider.connection.getBalance(feeVault)).eq(rentExempt);
+
+        // removeAllowedProposerAddress(bytes)
+        await executeOperation(GOV_PROTOCOL_ID, program.programId, 0xb8e5f3f4, params, ONE_FUNC_ID);
+        params = setProposeFee(ONE_FUNC_ID, 0);
+        await executeOperation(GOV_PROTOCOL_ID, program.programId, 0x5688e481, params, ONE_FUNC_ID);
     });
 
     it("propose with selector too big", async () => {
@@ -544,7 +1258,10 @@ describe("photon", () => {
                     photonProgram: program.programId,
                     config,
                     protocolInfo: onefuncProtocol,
+                    proposeWindow: null,
+                    proposeNonce: proposeNoncePda(ONE_FUNC_ID, EOB_CHAIN_ID),
                 })
+                .remainingAccounts(eventCpiRemainingAccounts(program))
                 .signers([owner])
                 .rpc();
             assert.ok(false, "Selector too big should fail");
@@ -214,3 +214,180 @@ export function setConsensusTargetRate(
         hex
     );
 }
+
+export function setTransmitterWeights(
+    protocolId: Buffer,
+    transmitterRaw: number[][],
+    weights: number[],
+): Buffer {
+    let hex = ethers.utils.defaultAbiCoder.encode(
+        ["tuple(bytes32, address[], uint256[])"],
+        [[protocolId, transmitterRaw.map((x) => Buffer.from(x).toString("hex")), weights]],
+    );
+    return hexToBytes(
+        hex
+    );
+}
+
+export function setProtocolPaused(protocolId: Buffer, paused: boolean): Buffer {
+    let hex = ethers.utils.defaultAbiCoder.encode(
+        ["tuple(bytes32, bool)"],
+        [[protocolId, paused]],
+    );
+    return hexToBytes(
+        hex
+    );
+}
+
+export function setGuardian(govProtocolId: Buffer, guardian: anchor.web3.PublicKey): Buffer {
+    let hex = ethers.utils.defaultAbiCoder.encode(
+        ["tuple(bytes32, bytes)"],
+        [[govProtocolId, guardian.toBuffer()]],
+    );
+    return hexToBytes(
+        hex
+    );
+}
+
+export function setGovTimelock(govProtocolId: Buffer, timelock: number): Buffer {
+    let hex = ethers.utils.defaultAbiCoder.encode(
+        ["tuple(bytes32, uint256)"],
+        [[govProtocolId, timelock]],
+    );
+    return hexToBytes(
+        hex
+    );
+}
+
+export function cancelGovChange(govProtocolId: Buffer, changeId: Buffer): Buffer {
+    let hex = ethers.utils.defaultAbiCoder.encode(
+        ["tuple(bytes32, bytes32)"],
+        [[govProtocolId, changeId]],
+    );
+    return hexToBytes(
+        hex
+    );
+}
+
+export function removeAllowedProtocol(
+    protocolId: Buffer,
+    rentRecipient: anchor.web3.PublicKey,
+): Buffer {
+    return hexToBytes(
+        ethers.utils.defaultAbiCoder.encode(
+            ["tuple(bytes32, bytes)"],
+            [[protocolId, rentRecipient.toBuffer()]],
+        ),
+    );
+}
+
+export function addAllowedDstChains(protocolId: Buffer, dstChainIds: number[]): Buffer {
+    return hexToBytes(
+        ethers.utils.defaultAbiCoder.encode(
+            ["tuple(bytes32, uint256[])"],
+            [[protocolId, dstChainIds]],
+        ),
+    );
+}
+
+export function removeAllowedDstChains(protocolId: Buffer, dstChainIds: number[]): Buffer {
+    return addAllowedDstChains(protocolId, dstChainIds);
+}
+
+export function setProposeRateLimit(
+    protocolId: Buffer,
+    maxProposals: number,
+    windowSlots: number,
+): Buffer {
+    return hexToBytes(
+        ethers.utils.defaultAbiCoder.encode(
+            ["tuple(bytes32, uint256, uint256)"],
+            [[protocolId, maxProposals, windowSlots]],
+        ),
+    );
+}
+
+export function setProposeFee(
+    protocolId: Buffer,
+    fee: number,
+    mint?: anchor.web3.PublicKey,
+): Buffer {
+    return hexToBytes(
+        ethers.utils.defaultAbiCoder.encode(
+            ["tuple(bytes32, uint256, bytes)"],
+            [[protocolId, fee, mint ? mint.toBuffer() : Buffer.from([])]],
+        ),
+    );
+}
+
+// Mirrors `gov::pending_change_id_by_code`
+export function pendingGovChangeId(code: number, params: Buffer): Buffer {
+    if (code == 0xdf107031) {
+        // cancelGovChange refers to the change it cancels
+        let decoded = ethers.utils.defaultAbiCoder.decode(["tuple(bytes32, bytes32)"], params);
+        return hexToBytes(decoded[0][1]);
+    }
+    let codeBuf = Buffer.alloc(4);
+    codeBuf.writeUInt32BE(code);
+    return hexToBytes(ethers.utils.keccak256(Buffer.concat([codeBuf, params])));
+}
+
+// The programs built with the `event-cpi` feature emit the events as self CPIs signed by the event authority
+export function eventCpiEnabled(program: anchor.Program<Photon>): boolean {
+    return program.idl.instructions
+        .find((ix) => ix.name == "executeOperation")
+        .accounts.some((account) => account.name == "eventAuthority");
+}
+
+export function eventAuthorityPda(program: anchor.Program<Photon>): anchor.web3.PublicKey {
+    return anchor.web3.PublicKey.findProgramAddressSync(
+        [anchor.utils.bytes.utf8.encode("__event_authority")],
+        program.programId,
+    )[0];
+}
+
+// The `event_authority` and `program` accounts of the instructions emitting events
+export function eventCpiAccounts(program: anchor.Program<Photon>): object {
+    if (!eventCpiEnabled(program)) {
+        return {};
+    }
+    return { eventAuthority: eventAuthorityPda(program), program: program.programId };
+}
+
+// The onefunc proposals expect the photon event authority as the first remaining account
+export function eventCpiRemainingAccounts(
+    program: anchor.Program<Photon>,
+): anchor.web3.AccountMeta[] {
+    if (!eventCpiEnabled(program)) {
+        return [];
+    }
+    return [{ pubkey: eventAuthorityPda(program), isSigner: false, isWritable: false }];
+}
+
+// Anchor `EVENT_IX_TAG_LE`, prefixes the event data of the self CPIs
+const EVENT_IX_TAG_LE = Buffer.from([0xe4, 0x45, 0xa5, 0x2e, 0x51, 0xcb, 0x9a, 0x1d]);
+
+// Returns the events written into the logs followed by the ones emitted as self CPIs
+export function parseEvents(
+    program: anchor.Program<Photon>,
+    tx: anchor.web3.ParsedTransactionWithMeta,
+): anchor.Event[] {
+    const eventParser = new anchor.EventParser(program.programId, new anchor.BorshCoder(program.idl));
+    const events: anchor.Event[] = Array.from(eventParser.parseLogs(tx.meta.logMessages));
+    for (const inner of tx.meta.innerInstructions ?? []) {
+        for (const ix of inner.instructions) {
+            if (!ix.programId.equals(program.programId) || !("data" in ix)) {
+                continue;
+            }
+            const data = Buffer.from(anchor.utils.bytes.bs58.decode(ix.data));
+            if (!data.subarray(0, 8).equals(EVENT_IX_TAG_LE)) {
+                continue;
+            }
+            const event = program.coder.events.decode(data.subarray(8).toString("base64"));
+            if (event) {
+                events.push(event);
+            }
+        }
+    }
+    return events;
+}
@@ -1,6 +1,7 @@
 use hex;
 use photon::{
-    protocol_data::FunctionSelector,
+    error::CustomError,
+    protocol_data::{FunctionSelector, Meta as TypedMeta, MetaBytes},
     util::{u128_to_bytes32, u64_to_bytes32},
 };
 use serde::{Deserialize, Serialize};
@@ -12,13 +13,11 @@ use std::fmt::{Display, Formatter};
 pub struct ProtocolId(pub ProtocolIdImpl);
 pub type ProtocolIdImpl = [u8; 32];
 pub type OpHash = [u8; 32];
-pub type Meta = [u8; 32];
+pub type Meta = MetaBytes;
 
+/// Meta of the supported version without any feature requested
 pub fn default_meta() -> Meta {
-    [
-        0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
-        0, 1,
-    ]
+    TypedMeta::default().encode()
 }
 
 impl Display for ProtocolId {
@@ -27,7 +26,7 @@ impl Display for ProtocolId {
     }
 }
 
-#[derive(Debug, Deserialize, Serialize)]
+#[derive(Clone, Debug, Deserialize, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct TransmitterMsg {
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -45,20 +44,30 @@ impl TransmitterMsg {
     }
 }
 
-#[derive(Debug, Deserialize, Serialize)]
+#[derive(Clone, Debug, Deserialize, Serialize)]
 #[serde(tag = "version")]
 pub enum TransmitterMsgVersioned {
     #[serde(rename = "1.0")]
     V1(TransmitterMsgImpl),
 }
 
-#[derive(Debug, Deserialize, Serialize)]
+#[derive(Clone, Debug, Deserialize, Serialize)]
 #[serde(tag = "type", content = "data", rename_all = "camelCase")]
 pub enum TransmitterMsgImpl {
     Propose(Propose),
     ProposalExecuted(ProposalExecuted),
     #[serde(rename = "signedOperation")]
     SignedOperationData(SignedOperation),
+    GovChange(GovChange),
+    ProposalLoaded(OperationStage),
+    ProposalApproved(OperationStage),
+    ProposalFailed(OperationStage),
+    GovChangeQueued(GovChangeQueued),
+    GovChangeApplied(GovChangeApplied),
+    GovChangeCancelled(GovChangeCancelled),
+    TentativePropose(Propose),
+    ProposalsFinalized(TxFinality),
+    ProposalsRetracted(TxFinality),
 }
 
 #[derive(Clone, Debug, derive_more::Display, Deserialize, Serialize)]
@@ -159,10 +168,11 @@ impl OperationData {
     }
 }
 
+/// Fails with the same errors the program rejects the malformed operation data with
 impl TryFrom<OperationData> for photon::protocol_data::OperationData {
-    type Error = Vec<u8>;
+    type Error = CustomError;
     fn try_from(value: OperationData) -> Result<Self, Self::Error> {
-        Ok(photon::protocol_data::OperationData {
+        let op_data = photon::protocol_data::OperationData {
             protocol_id: <Vec<u8>>::from(value.protocol_id.0),
             meta: value.meta,
             src_chain_id: value.src_chain_id,
@@ -170,11 +180,14 @@ impl TryFrom<OperationData> for photon::protocol_data::OperationData {
             src_op_tx_id: value.src_op_tx_id,
             nonce: value.nonce,
             dest_chain_id: value.dest_chain_id,
-            protocol_addr: Pubkey::try_from(value.protocol_addr)?,
+            protocol_addr: Pubkey::try_from(value.protocol_addr)
+                .map_err(|_| CustomError::InvalidAddress)?,
             function_selector: FunctionSelector::try_from(value.function_selector.as_slice())?,
             params: value.params,
             reserved: value.reserved,
-        })
+        };
+        op_data.validate()?;
+        Ok(op_data)
     }
 }
 
@@ -183,9 +196,20 @@ impl TryFrom<OperationData> for photon::protocol_data::OperationData {
 pub struct Propose {
     #[serde(skip)]
     pub need_check: bool,
-    pub latest_block_id: String,
+    /// Set for the last proposal of its transaction, the checkpoint may pass the transaction then
+    #[serde(skip)]
+    pub last_in_tx: bool,
+    /// Not set for the republished proposals, the consumers keep their checkpoint then
+    #[serde(default)]
+    pub latest_block_id: Option<String>,
     #[serde(flatten)]
     pub operation_data: OperationData,
+    /// The proposal fee paid on Solana, lets the relayers prioritise the proposals
+    #[serde(default)]
+    pub fee: u64,
+    /// Not set for the lamport fees
+    #[serde(default)]
+    pub fee_mint: Option<Pubkey>,
 }
 
 mod u128_serialization {
@@ -207,6 +231,29 @@ mod u128_serialization {
     }
 }
 
+mod u128_vec_serialization {
+    use serde::{ser::SerializeSeq, Deserialize, Deserializer, Serializer};
+
+    pub(super) fn serialize<S>(chain_ids: &[u128], serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let mut seq = serializer.serialize_seq(Some(chain_ids.len()))?;
+        for chain_id in chain_ids {
+            seq.serialize_element(&chain_id.to_be_bytes())?;
+        }
+        seq.end()
+    }
+
+    pub(super) fn deserialize<'de, D>(deserializer: D) -> Result<Vec<u128>, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let data = <Vec<[u8; 16]>>::deserialize(deserializer)?;
+        Ok(data.into_iter().map(u128::from_be_bytes).collect())
+    }
+}
+
 mod tx_id_serialization {
     use log::error;
     use serde::{
@@ -270,4 +317,159 @@ pub struct ProposalExecuted {
     pub last_watched_block: String,
     pub op_hash: OpHash,
     pub executor: Pubkey,
+    #[serde(default)]
+    pub return_data: Vec<u8>,
+    #[serde(default)]
+    pub slot: u64,
+}
+
+/// Lifecycle stage reached by an operation on Solana, forwarded by the watcher
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OperationStage {
+    pub tx_signature: String,
+    pub slot: u64,
+    pub op_hash: OpHash,
+    pub executor: Pubkey,
+}
+
+/// Resolves the tentative proposals of a Solana transaction, published by the listener once the
+/// transaction is finalized or its fork is dropped
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TxFinality {
+    pub tx_signature: String,
+    /// The slot the transaction is finalized in, or the one it was tentatively read from
+    pub slot: u64,
+}
+
+/// Gov change queued behind the timelock, forwarded by the watcher
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GovChangeQueued {
+    pub tx_signature: String,
+    pub slot: u64,
+    pub change_id: Vec<u8>,
+    pub op_hash: OpHash,
+    pub code: Vec<u8>,
+    pub activation_time: i64,
+}
+
+/// Pending gov change applied once its timelock expired, forwarded by the watcher
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GovChangeApplied {
+    pub tx_signature: String,
+    pub slot: u64,
+    pub change_id: Vec<u8>,
+    pub op_hash: OpHash,
+}
+
+/// Pending gov change dropped by a cancelling gov operation, forwarded by the watcher
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GovChangeCancelled {
+    pub tx_signature: String,
+    pub slot: u64,
+    pub change_id: Vec<u8>,
+    pub op_hash: OpHash,
+}
+
+/// Registry change made by gov on Solana, forwarded by the listener for off-chain indexers
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GovChange {
+    #[serde(skip)]
+    pub need_check: bool,
+    pub tx_signature: String,
+    pub slot: u64,
+    #[serde(flatten)]
+    pub change: GovChangeImpl,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(
+    tag = "event",
+    rename_all = "camelCase",
+    rename_all_fields = "camelCase"
+)]
+pub enum GovChangeImpl {
+    ProtocolAddressChanged {
+        protocol_id: Vec<u8>,
+        old_address: Pubkey,
+        new_address: Pubkey,
+    },
+    ProposerAdded {
+        protocol_id: Vec<u8>,
+        proposer: Pubkey,
+    },
+    ProposerRemoved {
+        protocol_id: Vec<u8>,
+        proposer: Pubkey,
+    },
+    ExecutorAdded {
+        protocol_id: Vec<u8>,
+        executor: Pubkey,
+    },
+    ExecutorRemoved {
+        protocol_id: Vec<u8>,
+        executor: Pubkey,
+    },
+    TransmittersUpdated {
+        protocol_id: Vec<u8>,
+        old_transmitters: Vec<[u8; 20]>,
+        new_transmitters: Vec<[u8; 20]>,
+    },
+    TransmitterWeightsUpdated {
+        protocol_id: Vec<u8>,
+        transmitters: Vec<[u8; 20]>,
+        old_weights: Vec<u64>,
+        new_weights: Vec<u64>,
+    },
+    ConsensusRateChanged {
+        protocol_id: Vec<u8>,
+        old_rate: u64,
+        new_rate: u64,
+    },
+    GuardianChanged {
+        old_guardian: Pubkey,
+        new_guardian: Pubkey,
+    },
+    GovTimelockChanged {
+        old_timelock: u64,
+        new_timelock: u64,
+    },
+    ProtocolRemoved {
+        protocol_id: Vec<u8>,
+        rent_recipient: Pubkey,
+    },
+    AllowedDstChainsUpdated {
+        protocol_id: Vec<u8>,
+        #[serde(with = "u128_vec_serialization")]
+        old_dst_chains: Vec<u128>,
+        #[serde(with = "u128_vec_serialization")]
+        new_dst_chains: Vec<u128>,
+    },
+    ProposeRateLimitChanged {
+        protocol_id: Vec<u8>,
+        old_max_proposals: u64,
+        old_window: u64,
+        new_max_proposals: u64,
+        new_window: u64,
+    },
+    /// The mints are not set for the lamport fees
+    ProposeFeeChanged {
+        protocol_id: Vec<u8>,
+        old_fee: u64,
+        old_mint: Option<Pubkey>,
+        new_fee: u64,
+        new_mint: Option<Pubkey>,
+    },
+    /// `protocol_id` is not set for the global pause
+    Paused {
+        protocol_id: Option<Vec<u8>>,
+    },
+    Unpaused {
+        protocol_id: Option<Vec<u8>>,
+    },
 }
@@ -1,4 +1,5 @@
 use clap::{Parser, Subcommand};
+use solana_sdk::clock::Slot;
 
 use super::{executor_app::ExecutorApp, listener_app::ListenerApp, watcher_app::WatcherApp};
 
@@ -10,6 +11,8 @@ enum Command {
     Listener {
         #[arg(long, help = "Listener module config path")]
         config: String,
+        #[command(subcommand)]
+        mode: Option<ListenerMode>,
     },
     #[command(about = "Starts executing operation data to the solana photon messaging circuit")]
     Executor {
@@ -23,6 +26,17 @@ enum Command {
     },
 }
 
+#[derive(Subcommand)]
+enum ListenerMode {
+    #[command(about = "Republishes the events of the slot range, the checkpoint is kept")]
+    Backfill {
+        #[arg(long, help = "The first slot to read")]
+        from_slot: Slot,
+        #[arg(long, help = "The last slot to read")]
+        to_slot: Slot,
+    },
+}
+
 #[derive(Parser)]
 #[clap(author, version, about, long_about = None)]
 pub(super) struct Cli {
@@ -34,9 +48,65 @@ impl Cli {
     pub(super) async fn execute(args: impl Iterator<Item = String>) {
         let mut parsed_cli = Self::parse_from(args);
         match &mut parsed_cli.command {
-            Command::Listener { config } => ListenerApp::execute(config).await,
+            Command::Listener { config, mode } => match mode {
+                None => ListenerApp::execute(config).await,
+                Some(ListenerMode::Backfill { from_slot, to_slot }) => {
+                    ListenerApp::backfill(config, *from_slot, *to_slot).await
+                }
+            },
             Command::Executor { config } => ExecutorApp::execute(config).await,
             Command::Watcher { config } => WatcherApp::execute(config).await,
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(args: &[&str]) -> Result<Cli, clap::Error> {
+        Cli::try_parse_from(["transmitter-module"].iter().chain(args))
+    }
+
+    #[test]
+    fn test_listener_parsing() {
+        let cli = parse(&["listener", "--config", "listener.yml"]).unwrap();
+        let Command::Listener { config, mode: None } = cli.command else {
+            panic!("Expected live listener");
+        };
+        assert_eq!(config, "listener.yml");
+    }
+
+    #[test]
+    fn test_backfill_parsing() {
+        let args = [
+            "listener",
+            "--config",
+            "listener.yml",
+            "backfill",
+            "--from-slot",
+            "10",
+        ];
+        let cli = parse(&[&args[..], &["--to-slot", "20"]].concat()).unwrap();
+        let Command::Listener {
+            config,
+            mode: Some(ListenerMode::Backfill { from_slot, to_slot }),
+        } = cli.command
+        else {
+            panic!("Expected backfill listener");
+        };
+        assert_eq!((config.as_str(), from_slot, to_slot), ("listener.yml", 10, 20));
+
+        // Both bounds of the slot range are required
+        assert!(parse(&args).is_err());
+        assert!(parse(&[
+            "listener",
+            "--config",
+            "l.yml",
+            "backfill",
+            "--from-slot",
+            "x"
+        ])
+        .is_err());
+    }
+}
@@ -0,0 +1,271 @@
+//! Minimal in-process AMQP 0-9-1 broker the rabbitmq clients are tested against. It replies to
+//! the methods the clients use, acks every publish and sends the server-initiated methods the
+//! tests ask for.
+
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+use tokio::{
+    io::{AsyncReadExt, AsyncWriteExt},
+    net::{tcp::OwnedReadHalf, TcpListener},
+    sync::mpsc::{self, UnboundedReceiver, UnboundedSender},
+};
+use transmitter_common::rabbitmq_client::RabbitmqConnectConfig;
+
+const PROTOCOL_HEADER: &[u8; 8] = b"AMQP\x00\x00\x09\x01";
+const FRAME_METHOD: u8 = 1;
+const FRAME_END: u8 = 0xCE;
+
+pub(crate) const CONNECTION_CLASS: u16 = 10;
+pub(crate) const CHANNEL_CLASS: u16 = 20;
+pub(crate) const QUEUE_CLASS: u16 = 50;
+pub(crate) const BASIC_CLASS: u16 = 60;
+
+/// AMQP method as its class and method ids
+pub(crate) type Method = (u16, u16);
+/// Shared between the stub and its connections
+type Received = Mutex<Vec<(u16, Method)>>;
+
+pub(crate) const CONNECTION_BLOCKED: Method = (CONNECTION_CLASS, 60);
+pub(crate) const CONNECTION_UNBLOCKED: Method = (CONNECTION_CLASS, 61);
+pub(crate) const CHANNEL_FLOW: Method = (CHANNEL_CLASS, 20);
+pub(crate) const CHANNEL_FLOW_OK: Method = (CHANNEL_CLASS, 21);
+pub(crate) const QUEUE_DECLARE: Method = (QUEUE_CLASS, 10);
+pub(crate) const BASIC_CONSUME: Method = (BASIC_CLASS, 20);
+pub(crate) const BASIC_CANCEL: Method = (BASIC_CLASS, 30);
+pub(crate) const BASIC_PUBLISH: Method = (BASIC_CLASS, 40);
+
+/// Method frame sent by the broker on its own initiative
+struct ServerMethod {
+    channel: u16,
+    method: Method,
+    args: Vec<u8>,
+}
+
+pub(crate) struct AmqpStub {
+    port: u16,
+    /// The methods received from the clients with their channels
+    received: Arc<Received>,
+    server_sender: UnboundedSender<ServerMethod>,
+}
+
+impl AmqpStub {
+    /// Serves the connections one after another on a local port
+    pub(crate) async fn start() -> AmqpStub {
+        let listener = TcpListener::bind("127.0.0.1:0").await.expect("Expected stub to bind");
+        let port = listener.local_addr().expect("Expected stub address").port();
+        let received = Arc::new(Mutex::new(Vec::new()));
+        let (server_sender, mut server_receiver) = mpsc::unbounded_channel();
+        let stub_received = received.clone();
+        tokio::spawn(async move {
+            while let Ok((stream, _)) = listener.accept().await {
+                serve(stream, &stub_received, &mut server_receiver).await;
+            }
+        });
+        AmqpStub {
+            port,
+            received,
+            server_sender,
+        }
+    }
+
+    pub(crate) fn connect_config(&self) -> RabbitmqConnectConfig {
+        RabbitmqConnectConfig {
+            host: "127.0.0.1".to_string(),
+            port: self.port,
+            user: "guest".to_string(),
+            password: "guest".to_string(),
+        }
+    }
+
+    pub(crate) fn received(&self, method: Method) -> usize {
+        let received = self.received.lock().expect("Expected received lock");
+        received.iter().filter(|(_, received)| *received == method).count()
+    }
+
+    /// Waits for the method to be received the given number of times
+    pub(crate) async fn wait_received(&self, method: Method, count: usize) {
+        wait_until(|| self.received(method) >= count).await;
+    }
+
+    pub(crate) fn send(&self, channel: u16, method: Method, args: Vec<u8>) {
+        self.server_sender
+            .send(ServerMethod {
+                channel,
+                method,
+                args,
+            })
+            .expect("Expected stub to be serving");
+    }
+}
+
+/// Polls the condition until it holds, fails the test after a while
+pub(crate) async fn wait_until(condition: impl Fn() -> bool) {
+    tokio::time::timeout(Duration::from_secs(5), async {
+        while !condition() {
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+    })
+    .await
+    .expect("Expected condition to hold in time");
+}
+
+pub(crate) fn short_str(value: &str) -> Vec<u8> {
+    let mut buf = vec![value.len() as u8];
+    buf.extend_from_slice(value.as_bytes());
+    buf
+}
+
+fn long_str(value: &str) -> Vec<u8> {
+    let mut buf = (value.len() as u32).to_be_bytes().to_vec();
+    buf.extend_from_slice(value.as_bytes());
+    buf
+}
+
+async fn serve(
+    stream: tokio::net::TcpStream,
+    received: &Received,
+    server_receiver: &mut UnboundedReceiver<ServerMethod>,
+) {
+    let (mut reader, mut writer) = stream.into_split();
+    let mut header = [0u8; 8];
+    if reader.read_exact(&mut header).await.is_err() || header != *PROTOCOL_HEADER {
+        return;
+    }
+    let (frame_sender, mut frame_receiver) = mpsc::unbounded_channel::<Vec<u8>>();
+    let start = [
+        vec![0, 9],
+        0u32.to_be_bytes().to_vec(),
+        long_str("PLAIN"),
+        long_str("en_US"),
+    ]
+    .concat();
+    let _ = frame_sender.send(method_frame(0, (CONNECTION_CLASS, 10), &start));
+
+    let client_sender = frame_sender.clone();
+    let mut client = Box::pin(async move {
+        let mut delivery_tags = HashMap::new();
+        while let Some((channel, method, args)) = read_method(&mut reader).await {
+            received.lock().expect("Expected received lock").push((channel, method));
+            let frames = reply(channel, method, &args, &mut delivery_tags);
+            let closing = method == (CONNECTION_CLASS, 50);
+            for frame in frames {
+                let _ = client_sender.send(frame);
+            }
+            if closing {
+                break;
+            }
+        }
+    });
+    loop {
+        tokio::select! {
+            _ = &mut client => break,
+            Some(server_method) = server_receiver.recv() => {
+                let ServerMethod { channel, method, args } = server_method;
+                let _ = frame_sender.send(method_frame(channel, method, &args));
+            }
+            Some(frame) = frame_receiver.recv() => {
+                if writer.write_all(&frame).await.is_err() {
+                    break;
+                }
+            }
+        }
+    }
+    while let Ok(frame) = frame_receiver.try_recv() {
+        let _ = writer.write_all(&frame).await;
+    }
+}
+
+/// The frames the broker answers the client method with
+fn reply(
+    channel: u16,
+    method: Method,
+    args: &[u8],
+    delivery_tags: &mut HashMap<u16, u64>,
+) -> Vec<Vec<u8>> {
+    let (reply_method, reply_args) = match method {
+        // Start-Ok is followed by Tune: 2047 channels, 128KB frames, no heartbeats
+        (10, 11) => {
+            let tune = [
+                &2047u16.to_be_bytes()[..],
+                &131072u32.to_be_bytes(),
+                &0u16.to_be_bytes(),
+            ];
+            ((CONNECTION_CLASS, 30), tune.concat())
+        }
+        (10, 40) => ((CONNECTION_CLASS, 41), short_str("")),
+        (10, 50) => ((CONNECTION_CLASS, 51), Vec::new()),
+        (20, 10) => ((CHANNEL_CLASS, 11), long_str("")),
+        (20, 40) => ((CHANNEL_CLASS, 41), Vec::new()),
+        (40, 10) => ((40, 11), Vec::new()),
+        (50, 10) => {
+            let queue = read_short_str(&args[2..]);
+            let args = [
+                short_str(&queue),
+                0u32.to_be_bytes().to_vec(),
+                0u32.to_be_bytes().to_vec(),
+            ];
+            ((QUEUE_CLASS, 11), args.concat())
+        }
+        (50, 20) => ((QUEUE_CLASS, 21), Vec::new()),
+        (60, 20) => {
+            let queue = read_short_str(&args[2..]);
+            let consumer_tag = match read_short_str(&args[3 + queue.len()..]) {
+                consumer_tag if consumer_tag.is_empty() => "amq.ctag-stub".to_string(),
+                consumer_tag => consumer_tag,
+            };
+            ((BASIC_CLASS, 21), short_str(&consumer_tag))
+        }
+        // Publish is acked right away, the channel is expected to be in the confirm mode
+        (60, 40) => {
+            let delivery_tag = delivery_tags.entry(channel).or_insert(0);
+            *delivery_tag += 1;
+            ((BASIC_CLASS, 80), [delivery_tag.to_be_bytes().to_vec(), vec![0]].concat())
+        }
+        (85, 10) if args.first().is_some_and(|bits| bits & 1 == 0) => ((85, 11), Vec::new()),
+        _ => return Vec::new(),
+    };
+    vec![method_frame(channel, reply_method, &reply_args)]
+}
+
+fn method_frame(channel: u16, method: Method, args: &[u8]) -> Vec<u8> {
+    let payload = [
+        &method.0.to_be_bytes()[..],
+        &method.1.to_be_bytes()[..],
+        args,
+    ]
+    .concat();
+    let mut frame = vec![FRAME_METHOD];
+    frame.extend_from_slice(&channel.to_be_bytes());
+    frame.extend_from_slice(&(payload.len() as u32).to_be_bytes());
+    frame.extend_from_slice(&payload);
+    frame.push(FRAME_END);
+    frame
+}
+
+/// Reads the frames up to the next method, the content and heartbeat frames are skipped
+async fn read_method(reader: &mut OwnedReadHalf) -> Option<(u16, Method, Vec<u8>)> {
+    loop {
+        let frame_type = reader.read_u8().await.ok()?;
+        let channel = reader.read_u16().await.ok()?;
+        let size = reader.read_u32().await.ok()?;
+        let mut payload = vec![0u8; size as usize];
+        reader.read_exact(&mut payload).await.ok()?;
+        if reader.read_u8().await.ok()? != FRAME_END {
+            return None;
+        }
+        if frame_type != FRAME_METHOD || payload.len() < 4 {
+            continue;
+        }
+        let class = u16::from_be_bytes([payload[0], payload[1]]);
+        let method = u16::from_be_bytes([payload[2], payload[3]]);
+        return Some((channel, (class, method), payload.split_off(4)));
+    }
+}
+
+fn read_short_str(buf: &[u8]) -> String {
+    let len = buf[0] as usize;
+    String::from_utf8_lossy(&buf[1..1 + len]).into_owned()
+}
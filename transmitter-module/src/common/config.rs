@@ -19,10 +19,35 @@ where
     Ok(CommitmentConfig { commitment })
 }
 
+/// How the events read before their transactions are finalized are published
+#[derive(Clone, Copy, Debug, Default, Deserialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub(crate) enum FinalityMode {
+    /// The events are published as read at the configured commitment
+    #[default]
+    Immediate,
+    /// The proposals are published as tentative and then finalized or retracted, the other events
+    /// are delayed until finalized
+    TwoPhase,
+    /// The events are published once their transactions are finalized
+    Delayed,
+}
+
+fn default_finality_check_interval_ms() -> u64 {
+    2000
+}
+
 #[derive(Deserialize)]
 pub(crate) struct SolanaListenerConfig {
     #[serde(flatten)]
     pub(crate) client: SolanaClientConfig,
     #[serde(alias = "txreadfrom")]
     pub(crate) tx_read_from: Option<String>,
+    #[serde(default)]
+    pub(crate) finality: FinalityMode,
+    #[serde(
+        default = "default_finality_check_interval_ms",
+        alias = "finalitycheckintervalms"
+    )]
+    pub(crate) finality_check_interval_ms: u64,
 }
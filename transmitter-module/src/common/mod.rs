@@ -1,3 +1,6 @@
+#[cfg(test)]
+pub(crate) mod amqp_stub;
 pub(crate) mod config;
-pub(crate) mod rabbitmq;
+pub(crate) mod outbox;
 pub(crate) mod solana_logs;
+pub(crate) mod transport;
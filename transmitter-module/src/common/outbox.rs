@@ -0,0 +1,483 @@
+use log::{debug, error, info, warn};
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::VecDeque,
+    fs,
+    io::{self, BufRead, BufReader, Write},
+    path::PathBuf,
+    time::{Duration, Instant},
+};
+
+use crate::common::transport::PublishConfirm;
+
+/// Delay before a message nacked or returned by the broker is published again
+const RETRY_DELAY: Duration = Duration::from_secs(1);
+
+/// The log is not compacted until it has grown to this many records at least
+const COMPACT_MIN_RECORDS: usize = 1024;
+
+/// Encoded message to be published with the routing key
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct OutboxMsg {
+    pub(crate) id: u64,
+    pub(crate) routing_key: String,
+    pub(crate) payload: String,
+}
+
+/// Change of the outbox, appended to its log file
+#[derive(Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+enum LogRecord {
+    Push(OutboxMsg),
+    Ack(Vec<u64>),
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Delivery {
+    Queued,
+    Published(u64),
+    /// Returned as unroutable, the broker acks it regardless
+    Returned(u64),
+}
+
+struct Entry {
+    msg: OutboxMsg,
+    delivery: Delivery,
+    retry_at: Option<Instant>,
+}
+
+/// Append-only log of the outbox changes, rewritten with the queued messages only once it has
+/// grown twice as long as needed
+struct OutboxLog {
+    path: PathBuf,
+    file: fs::File,
+    /// The records in the file
+    records: usize,
+}
+
+impl OutboxLog {
+    /// Replays the log into the queued messages and compacts it
+    fn open(path: PathBuf) -> io::Result<(OutboxLog, Vec<OutboxMsg>)> {
+        let mut msgs: Vec<OutboxMsg> = Vec::new();
+        if path.exists() {
+            let lines =
+                BufReader::new(fs::File::open(&path)?).lines().collect::<io::Result<Vec<_>>>()?;
+            for (index, line) in lines.iter().enumerate() {
+                match serde_json::from_str(line) {
+                    Ok(LogRecord::Push(msg)) => msgs.push(msg),
+                    Ok(LogRecord::Ack(ids)) => msgs.retain(|msg| !ids.contains(&msg.id)),
+                    // The record being appended at the crash
+                    Err(err) if index + 1 == lines.len() => {
+                        warn!("Partially written outbox record skipped: {}", err)
+                    }
+                    Err(err) => return Err(err.into()),
+                }
+            }
+            info!("Outbox loaded: {}, messages: {}", path.display(), msgs.len());
+        }
+        let file = Self::rewrite(&path, msgs.iter())?;
+        let log = OutboxLog {
+            path,
+            file,
+            records: msgs.len(),
+        };
+        Ok((log, msgs))
+    }
+
+    /// Appends the record, synced to the disk if it is not to be lost
+    fn append(&mut self, record: &LogRecord, sync: bool) -> io::Result<()> {
+        let mut line = serde_json::to_vec(record)?;
+        line.push(b'\n');
+        self.file.write_all(&line)?;
+        if sync {
+            self.file.sync_data()?;
+        }
+        self.records += 1;
+        Ok(())
+    }
+
+    fn compact<'a>(
+        &mut self,
+        msgs: impl ExactSizeIterator<Item = &'a OutboxMsg>,
+    ) -> io::Result<()> {
+        let records = msgs.len();
+        self.file = Self::rewrite(&self.path, msgs)?;
+        self.records = records;
+        Ok(())
+    }
+
+    /// Replaces the file at once, so it is never left partially written, and opens it to append
+    fn rewrite<'a>(
+        path: &PathBuf,
+        msgs: impl Iterator<Item = &'a OutboxMsg>,
+    ) -> io::Result<fs::File> {
+        let tmp_path = path.with_extension("tmp");
+        let mut file = io::BufWriter::new(fs::File::create(&tmp_path)?);
+        for msg in msgs {
+            serde_json::to_writer(&mut file, &LogRecord::Push(msg.clone()))?;
+            file.write_all(b"\n")?;
+        }
+        file.into_inner().map_err(io::IntoInnerError::into_error)?.sync_all()?;
+        fs::rename(tmp_path, path)?;
+        fs::OpenOptions::new().append(true).open(path)
+    }
+}
+
+/// Bounded queue of the outbound messages, which are removed only once the broker confirms them.
+/// The nacked, returned and the ones published through a closed channel are published again. If a
+/// file is given, the queue is kept in its log over restarts.
+pub(crate) struct Outbox {
+    log: Option<OutboxLog>,
+    capacity: usize,
+    next_id: u64,
+    /// Delivery tags are numbered per channel starting with 1
+    next_delivery_tag: u64,
+    entries: VecDeque<Entry>,
+}
+
+impl Outbox {
+    pub(crate) fn open(path: Option<&str>, capacity: usize) -> io::Result<Outbox> {
+        let (log, msgs) = match path {
+            Some(path) => {
+                let (log, msgs) = OutboxLog::open(PathBuf::from(path))?;
+                (Some(log), msgs)
+            }
+            None => (None, Vec::new()),
+        };
+        let entries: VecDeque<Entry> = msgs
+            .into_iter()
+            .map(|msg| Entry {
+                msg,
+                delivery: Delivery::Queued,
+                retry_at: None,
+            })
+            .collect();
+        Ok(Outbox {
+            log,
+            capacity,
+            next_id: entries.back().map_or(0, |entry| entry.msg.id + 1),
+            next_delivery_tag: 1,
+            entries,
+        })
+    }
+
+    pub(crate) fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// The messages are not taken further while the outbox is full
+    pub(crate) fn is_full(&self) -> bool {
+        self.entries.len() >= self.capacity
+    }
+
+    /// Queues the message, returns its id
+    pub(crate) fn push(&mut self, routing_key: &str, payload: String) -> u64 {
+        let id = self.next_id;
+        self.next_id += 1;
+        let msg = OutboxMsg {
+            id,
+            routing_key: routing_key.to_string(),
+            payload,
+        };
+        self.entries.push_back(Entry {
+            msg: msg.clone(),
+            delivery: Delivery::Queued,
+            retry_at: None,
+        });
+        self.persist(LogRecord::Push(msg));
+        id
+    }
+
+    /// The first queued message due to be published
+    pub(crate) fn next_queued(&self) -> Option<&OutboxMsg> {
+        let now = Instant::now();
+        self.entries
+            .iter()
+            .find(|entry| {
+                entry.delivery == Delivery::Queued
+                    && entry.retry_at.filter(|retry_at| *retry_at > now).is_none()
+            })
+            .map(|entry| &entry.msg)
+    }
+
+    /// How long until a queued message is due to be retried, if any is waiting for it
+    pub(crate) fn retry_in(&self) -> Option<Duration> {
+        let now = Instant::now();
+        self.entries
+            .iter()
+            .filter(|entry| entry.delivery == Delivery::Queued)
+            .filter_map(|entry| entry.retry_at)
+            .min()
+            .map(|retry_at| retry_at.saturating_duration_since(now))
+    }
+
+    /// Marks the message as published through the channel, so waiting for the broker confirm,
+    /// returns the delivery tag the confirm is expected to carry
+    pub(crate) fn published(&mut self, id: u64) -> u64 {
+        let delivery_tag = self.next_delivery_tag;
+        self.next_delivery_tag += 1;
+        if let Some(entry) = self.entries.iter_mut().find(|entry| entry.msg.id == id) {
+            entry.delivery = Delivery::Published(delivery_tag);
+        }
+        delivery_tag
+    }
+
+    /// Marks the message as failed to be published, it is retried after the delay
+    pub(crate) fn publish_failed(&mut self, id: u64) {
+        if let Some(entry) = self.entries.iter_mut().find(|entry| entry.msg.id == id) {
+            entry.retry_at = Some(Instant::now() + RETRY_DELAY);
+        }
+    }
+
+    /// Applies the broker feedback, returns the ids of the messages acked and removed
+    pub(crate) fn confirm(&mut self, confirm: PublishConfirm) -> Vec<u64> {
+        let (delivery_tag, multiple, ack) = match confirm {
+            PublishConfirm::Ack {
+                delivery_tag,
+                multiple,
+            } => (delivery_tag, multiple, true),
+            PublishConfirm::Nack {
+                delivery_tag,
+                multiple,
+            } => (delivery_tag, multiple, false),
+            PublishConfirm::Return { message_id } => {
+                self.returned(message_id);
+                return Vec::new();
+            }
+        };
+        let confirmed = |tag: u64| tag == delivery_tag || (multiple && tag < delivery_tag);
+        let mut acked = Vec::new();
+        let retry_at = Instant::now() + RETRY_DELAY;
+        self.entries.retain_mut(|entry| {
+            let (tag, returned) = match entry.delivery {
+                Delivery::Published(tag) => (tag, false),
+                Delivery::Returned(tag) => (tag, true),
+                Delivery::Queued => return true,
+            };
+            if !confirmed(tag) {
+                return true;
+            }
+            if ack && !returned {
+                acked.push(entry.msg.id);
+                return false;
+            }
+            warn!("Message to be published again: {}", entry.msg.id);
+            entry.delivery = Delivery::Queued;
+            entry.retry_at = Some(retry_at);
+            true
+        });
+        if !acked.is_empty() {
+            debug!("Messages confirmed: {}", acked.len());
+            self.persist(LogRecord::Ack(acked.clone()));
+        }
+        acked
+    }
+
+    /// The messages published through the closed channel are not going to be confirmed
+    pub(crate) fn reset_channel(&mut self) {
+        for entry in self.entries.iter_mut() {
+            if entry.delivery != Delivery::Queued {
+                entry.delivery = Delivery::Queued;
+                entry.retry_at = None;
+            }
+        }
+        self.next_delivery_tag = 1;
+    }
+
+    fn returned(&mut self, message_id: Option<String>) {
+        let Some(id) = message_id.and_then(|id| id.parse::<u64>().ok()) else {
+            warn!("Returned message without the outbox id");
+            return;
+        };
+        let entry = self.entries.iter_mut().find(|entry| entry.msg.id == id);
+        match entry.map(|entry| (entry.delivery, entry)) {
+            Some((Delivery::Published(tag), entry)) => entry.delivery = Delivery::Returned(tag),
+            _ => warn!("Returned message not waiting for the confirm: {}", id),
+        }
+    }
+
+    /// Logs the change, only the pushed messages are synced as a lost ack publishes the message
+    /// again at most
+    fn persist(&mut self, record: LogRecord) {
+        let Some(log) = &mut self.log else {
+            return;
+        };
+        let sync = matches!(record, LogRecord::Push(_));
+        if let Err(err) = log.append(&record, sync) {
+            error!("Failed to persist outbox: {}, error: {}", log.path.display(), err);
+        }
+        if log.records > COMPACT_MIN_RECORDS.max(2 * self.entries.len()) {
+            let msgs = self.entries.iter().map(|entry| &entry.msg);
+            if let Err(err) = log.compact(msgs) {
+                error!("Failed to compact outbox: {}, error: {}", log.path.display(), err);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ack(delivery_tag: u64, multiple: bool) -> PublishConfirm {
+        PublishConfirm::Ack {
+            delivery_tag,
+            multiple,
+        }
+    }
+
+    fn publish_all(outbox: &mut Outbox) -> Vec<u64> {
+        let mut published = Vec::new();
+        while let Some(msg) = outbox.next_queued() {
+            let id = msg.id;
+            outbox.published(id);
+            published.push(id);
+        }
+        published
+    }
+
+    #[test]
+    fn test_removed_only_on_ack() {
+        let mut outbox = Outbox::open(None, 10).unwrap();
+        let first = outbox.push("key", "first".to_string());
+        let second = outbox.push("key", "second".to_string());
+        assert_eq!(publish_all(&mut outbox), vec![first, second]);
+
+        assert_eq!(outbox.confirm(ack(1, false)), vec![first]);
+        assert!(outbox.next_queued().is_none());
+        assert!(!outbox.is_empty());
+        assert_eq!(outbox.confirm(ack(2, true)), vec![second]);
+        assert!(outbox.is_empty());
+    }
+
+    #[test]
+    fn test_nack_and_return_retried() {
+        let mut outbox = Outbox::open(None, 10).unwrap();
+        let nacked = outbox.push("key", "nacked".to_string());
+        let returned = outbox.push("key", "returned".to_string());
+        publish_all(&mut outbox);
+
+        let nack = PublishConfirm::Nack {
+            delivery_tag: 1,
+            multiple: false,
+        };
+        assert!(outbox.confirm(nack).is_empty());
+        let message_id = Some(returned.to_string());
+        assert!(outbox.confirm(PublishConfirm::Return { message_id }).is_empty());
+        assert!(outbox.confirm(ack(2, false)).is_empty());
+
+        // Both are published again once the delay is over
+        assert!(outbox.next_queued().is_none());
+        assert!(outbox.retry_in().is_some());
+        for entry in outbox.entries.iter_mut() {
+            entry.retry_at = Some(Instant::now());
+        }
+        assert_eq!(publish_all(&mut outbox), vec![nacked, returned]);
+        assert_eq!(outbox.confirm(ack(4, true)), vec![nacked, returned]);
+    }
+
+    #[test]
+    fn test_reset_channel_republishes() {
+        let mut outbox = Outbox::open(None, 2).unwrap();
+        let first = outbox.push("key", "first".to_string());
+        publish_all(&mut outbox);
+        let second = outbox.push("key", "second".to_string());
+        assert!(outbox.is_full());
+
+        outbox.reset_channel();
+        assert_eq!(publish_all(&mut outbox), vec![first, second]);
+        assert_eq!(outbox.confirm(ack(2, true)), vec![first, second]);
+        assert!(!outbox.is_full());
+    }
+
+    #[test]
+    fn test_kept_over_restarts() {
+        let path = std::env::temp_dir().join(format!("outbox-{}.jsonl", std::process::id()));
+        let path_str = path.to_str().unwrap();
+        let mut outbox = Outbox::open(Some(path_str), 10).unwrap();
+        outbox.push("key", "{\"first\":1}".to_string());
+        let second = outbox.push("other_key", "{\"second\":2}".to_string());
+        publish_all(&mut outbox);
+        outbox.confirm(ack(1, false));
+
+        let mut reopened = Outbox::open(Some(path_str), 10).unwrap();
+        fs::remove_file(&path).unwrap();
+        let msg = reopened.next_queued().cloned().unwrap();
+        assert_eq!(msg.id, second);
+        assert_eq!(msg.routing_key, "other_key");
+        assert_eq!(msg.payload, "{\"second\":2}");
+        assert_eq!(reopened.push("key", String::new()), second + 1);
+    }
+
+    fn temp_path(name: &str) -> PathBuf {
+        std::env::temp_dir().join(format!("outbox-{}-{}.jsonl", name, std::process::id()))
+    }
+
+    fn line_count(path: &PathBuf) -> usize {
+        fs::read_to_string(path).unwrap().lines().count()
+    }
+
+    #[test]
+    fn test_log_appended_and_compacted() {
+        let path = temp_path("compacted");
+        let path_str = path.to_str().unwrap();
+        let mut outbox = Outbox::open(Some(path_str), 2 * COMPACT_MIN_RECORDS).unwrap();
+        let kept = outbox.push("key", "kept".to_string());
+        outbox.published(kept);
+        // Every change is a single record
+        let acked = outbox.push("key", "acked".to_string());
+        outbox.published(acked);
+        assert_eq!(outbox.confirm(ack(2, false)), vec![acked]);
+        assert_eq!(line_count(&path), 3);
+
+        // Rewritten with the queued only once the log has grown past the limit
+        let mut delivery_tag = 2;
+        for _ in 0..COMPACT_MIN_RECORDS / 2 - 1 {
+            let id = outbox.push("key", String::new());
+            outbox.published(id);
+            delivery_tag += 1;
+            assert_eq!(outbox.confirm(ack(delivery_tag, false)), vec![id]);
+        }
+        assert_eq!(line_count(&path), 1);
+        let last = outbox.push("key", "last".to_string());
+        assert_eq!(line_count(&path), 2);
+
+        let mut reopened = Outbox::open(Some(path_str), 10).unwrap();
+        fs::remove_file(&path).unwrap();
+        let queued: Vec<u64> = reopened.entries.iter().map(|entry| entry.msg.id).collect();
+        assert_eq!(queued, vec![kept, last]);
+        assert_eq!(reopened.push("key", String::new()), last + 1);
+    }
+
+    #[test]
+    fn test_log_replayed() {
+        let path = temp_path("replayed");
+        let msg = |id: u64| OutboxMsg {
+            id,
+            routing_key: "key".to_string(),
+            payload: id.to_string(),
+        };
+        let lines = [
+            serde_json::to_string(&LogRecord::Push(msg(1))).unwrap(),
+            serde_json::to_string(&LogRecord::Push(msg(2))).unwrap(),
+            serde_json::to_string(&LogRecord::Push(msg(3))).unwrap(),
+            serde_json::to_string(&LogRecord::Ack(vec![1, 3])).unwrap(),
+            // Partially written at the crash
+            "{\"push\":{\"id\":4,".to_string(),
+        ];
+        fs::write(&path, lines.join("\n")).unwrap();
+
+        let outbox = Outbox::open(Some(path.to_str().unwrap()), 10).unwrap();
+        let queued: Vec<&OutboxMsg> = outbox.entries.iter().map(|entry| &entry.msg).collect();
+        assert_eq!(queued, vec![&msg(2)]);
+        // Compacted on open
+        assert_eq!(line_count(&path), 1);
+        fs::remove_file(&path).unwrap();
+
+        // Only the last record may be partially written
+        fs::write(&path, lines.iter().rev().cloned().collect::<Vec<_>>().join("\n")).unwrap();
+        assert!(Outbox::open(Some(path.to_str().unwrap()), 10).is_err());
+        fs::remove_file(&path).unwrap();
+    }
+}
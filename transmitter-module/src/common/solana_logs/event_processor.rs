@@ -1,21 +1,25 @@
-use anchor_lang::prelude::borsh::BorshDeserialize;
-use log::debug;
+use log::{debug, warn};
 
-use super::solana_event_listener::LogsBunch;
+use super::{photon_events::ParsedEvent, solana_event_listener::LogsBunch};
 use crate::common::solana_logs::parse_logs;
 
 pub trait EventProcessor {
-    type Event: anchor_lang::Event + BorshDeserialize;
-
     fn on_logs(&self, logs_bunch: LogsBunch) {
         let logs = &logs_bunch.logs[..];
         let logs: Vec<&str> = logs.iter().by_ref().map(String::as_str).collect();
-        let Ok(events) =
-            parse_logs::parse_logs::<Self::Event>(logs.as_slice(), photon::ID.to_string().as_str())
-        else {
-            log::error!("Failed to parse logs: {:?}", logs);
-            return;
-        };
+        let parsed = parse_logs::parse_logs(logs.as_slice(), photon::ID.to_string().as_str());
+        let mut events = parsed.events;
+        let cpi_events = parse_logs::parse_inner_instructions(&logs_bunch.inner_instructions);
+        if parsed.truncated && cpi_events.is_empty() {
+            warn!(
+                "Logs truncated and no event CPIs to recover events from, tx_signature: {}",
+                logs_bunch.tx_signature
+            );
+        }
+        if !cpi_events.is_empty() {
+            events.extend(cpi_events);
+            events.sort_by_key(|event| event.ix_index);
+        }
         if !events.is_empty() {
             debug!(
                 "Logs intercepted, tx_signature: {}, events: {}, need_check: {}",
@@ -26,9 +30,17 @@ pub trait EventProcessor {
         }
 
         for event in events {
+            debug!(
+                "Photon event intercepted, ix_index: {}, cpi_depth: {}, event: {:?}",
+                event.ix_index, event.cpi_depth, event.event
+            );
             self.on_event(event, &logs_bunch.tx_signature, logs_bunch.slot, logs_bunch.need_check);
         }
+        self.on_tx_end(&logs_bunch.tx_signature);
     }
 
-    fn on_event(&self, event: Self::Event, signature: &str, slot: u64, need_check: bool);
+    fn on_event(&self, event: ParsedEvent, signature: &str, slot: u64, need_check: bool);
+
+    /// Called once all the events of the transaction are processed
+    fn on_tx_end(&self, _signature: &str) {}
 }
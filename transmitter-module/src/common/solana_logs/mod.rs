@@ -1,9 +1,10 @@
-use log::error;
 use solana_transactor::TransactorError;
 use thiserror::Error;
 
 pub(crate) mod event_processor;
 pub(crate) mod parse_logs;
+pub(crate) mod photon_events;
+pub(crate) mod solana_block_reader;
 pub(crate) mod solana_event_listener;
 pub(crate) mod solana_retro_reader;
 
@@ -15,6 +16,4 @@ pub(crate) enum EventListenerError {
     SolanaTransacto(#[from] TransactorError),
     #[error("Mongodb client error")]
     Mongodb(#[from] mongodb::error::Error),
-    #[error("Solana parse logs error")]
-    SolanaParseLogs,
 }
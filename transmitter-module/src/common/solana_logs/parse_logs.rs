@@ -1,187 +1,464 @@
-use log::error;
+use log::warn;
 use regex::Regex;
+use std::sync::OnceLock;
 
-use super::EventListenerError;
+use super::{
+    photon_events::{ParsedEvent, PhotonEvent},
+    solana_event_listener::InnerInstruction,
+};
 
+const PROGRAM_LOG: &str = "Program log: ";
+const PROGRAM_DATA: &str = "Program data: ";
+/// Logged by the runtime in place of everything exceeding the transaction log limit
+const LOG_TRUNCATED: &str = "Log truncated";
+
+fn invoke_regex() -> &'static Regex {
+    static INVOKE: OnceLock<Regex> = OnceLock::new();
+    // Only the invoke logs of the runtime, a program may log anything mentioning an invoke
+    INVOKE.get_or_init(|| {
+        Regex::new(r"^Program (\S+) invoke \[\d+\]$")
+            .expect("Expected regexp to be constructed well")
+    })
+}
+
+fn exit_regex() -> &'static Regex {
+    static EXIT: OnceLock<Regex> = OnceLock::new();
+    EXIT.get_or_init(|| {
+        Regex::new(r"^Program \S+ (success|failed: .*)$")
+            .expect("Expected regexp to be constructed well")
+    })
+}
+
+#[derive(Default)]
 struct Execution {
     stack: Vec<String>,
+    /// The number of top level instructions met so far
+    instructions: usize,
 }
 
 impl Execution {
-    fn program(&self) -> Result<String, EventListenerError> {
-        if self.stack.is_empty() {
-            error!("Failed to get program from the empty stack");
-            return Err(EventListenerError::SolanaParseLogs);
-        }
-        Ok(self.stack[self.stack.len() - 1].clone())
+    fn program(&self) -> Option<&str> {
+        self.stack.last().map(String::as_str)
     }
 
-    fn push(&mut self, new_program: String) {
-        self.stack.push(new_program);
+    fn depth(&self) -> usize {
+        self.stack.len()
+    }
+
+    fn instruction_index(&self) -> usize {
+        self.instructions.saturating_sub(1)
     }
 
-    fn pop(&mut self) -> Result<(), EventListenerError> {
+    fn push(&mut self, new_program: String) {
         if self.stack.is_empty() {
-            error!("Failed to get program from the empty stack");
-            return Err(EventListenerError::SolanaParseLogs);
+            self.instructions += 1;
         }
-        self.stack.pop().expect("Stack should not be empty");
-        Ok(())
+        self.stack.push(new_program);
     }
 
-    fn update(&mut self, log: &str) -> Result<String, EventListenerError> {
-        let re =
-            Regex::new(r"^Program (.*) invoke.*$").expect("Expected regexp to be constructed well");
-        let Some(c) = re.captures(log) else {
-            return self.program();
-        };
-        let program = c
-            .get(1)
-            .expect("Expected captured program address to be available")
-            .as_str()
-            .to_string();
-        self.push(program);
-        self.program()
+    fn pop(&mut self) {
+        if self.stack.pop().is_none() {
+            warn!("Program exit met on the empty stack, ignored");
+        }
     }
-}
 
-pub(crate) fn parse_logs<T: anchor_lang::Event + anchor_lang::AnchorDeserialize>(
-    logs: &[&str],
-    program_id_str: &str,
-) -> Result<Vec<T>, EventListenerError> {
-    let mut events: Vec<T> = Vec::new();
-    let mut do_pop = false;
-    if !logs.is_empty() {
-        let mut execution = Execution {
-            stack: <Vec<String>>::default(),
-        };
-        for log in logs {
-            let (event, pop) = {
-                if do_pop {
-                    execution.pop()?;
-                }
-                execution.update(log)?;
-                if program_id_str == execution.program()? {
-                    handle_program_log(program_id_str, log).map_err(|e| {
-                        error!("Failed to parse log: {}", e);
-                        EventListenerError::SolanaParseLogs
-                    })?
-                } else {
-                    let (_, pop) = handle_irrelevant_log(program_id_str, log);
-                    (None, pop)
-                }
-            };
-            do_pop = pop;
-            if let Some(e) = event {
-                events.push(e);
-            }
+    /// Tracks the invoked programs, returns false if the log is not a part of the invocation flow
+    fn update(&mut self, log: &str) -> bool {
+        if let Some(c) = invoke_regex().captures(log) {
+            let program = c
+                .get(1)
+                .expect("Expected captured program address to be available")
+                .as_str()
+                .to_string();
+            self.push(program);
+        } else if exit_regex().is_match(log) {
+            self.pop();
+        } else {
+            return false;
         }
+        true
     }
-    Ok(events)
 }
 
-fn handle_program_log<T: anchor_lang::Event + anchor_lang::AnchorDeserialize>(
-    self_program_str: &str,
-    l: &str,
-) -> Result<(Option<T>, bool), EventListenerError> {
-    const PROGRAM_LOG: &str = "Program log: ";
-    const PROGRAM_DATA: &str = "Program data: ";
+/// The photon events decoded from the transaction logs
+pub(crate) struct ParsedLogs {
+    pub events: Vec<ParsedEvent>,
+    /// The runtime has cut the logs off, the events emitted afterwards are missing
+    pub truncated: bool,
+}
 
-    if let Some(log) = l.strip_prefix(PROGRAM_LOG).or_else(|| l.strip_prefix(PROGRAM_DATA)) {
-        let borsh_bytes = match anchor_lang::__private::base64::decode(log) {
-            Ok(borsh_bytes) => borsh_bytes,
-            _ => {
-                #[cfg(feature = "debug")]
-                println!("Could not base64 decode log: {}", log);
-                return Ok((None, false));
-            }
+/// Decodes every photon event met in the transaction logs in a single pass, in the emission order
+///
+/// Malformed and undecodable entries are skipped with a warning so that a single bad line does not
+/// cost the rest of the transaction events
+pub(crate) fn parse_logs(logs: &[&str], program_id_str: &str) -> ParsedLogs {
+    let mut parsed = ParsedLogs {
+        events: Vec::new(),
+        truncated: false,
+    };
+    let mut execution = Execution::default();
+    for log in logs {
+        if *log == LOG_TRUNCATED {
+            warn!("Logs truncated, {} events decoded before", parsed.events.len());
+            parsed.truncated = true;
+            break;
+        }
+        if execution.update(log) || execution.program() != Some(program_id_str) {
+            continue;
+        }
+        let Some(data) = log.strip_prefix(PROGRAM_DATA).or_else(|| log.strip_prefix(PROGRAM_LOG))
+        else {
+            continue;
         };
-
-        let mut slice: &[u8] = &borsh_bytes[..];
-        let disc: [u8; 8] = {
-            let mut disc = [0; 8];
-            disc.copy_from_slice(&borsh_bytes[..8]);
-            slice = &slice[8..];
-            disc
+        // Plain text messages are logged with the same prefix
+        let Ok(borsh_bytes) = anchor_lang::__private::base64::decode(data) else {
+            continue;
         };
-        let mut event = None;
-        if disc == T::discriminator() {
-            let e: T = anchor_lang::AnchorDeserialize::deserialize(&mut slice).map_err(|err| {
-                error!("Failed to deserialize event: {}", err);
-                EventListenerError::SolanaParseLogs
-            })?;
-            event = Some(e);
+        if let Some(event) = decode_event(&borsh_bytes) {
+            parsed.events.push(ParsedEvent {
+                event,
+                cpi_depth: execution.depth(),
+                ix_index: execution.instruction_index(),
+            });
         }
-        Ok((event, false))
-    } else {
-        let (_program, did_pop) = handle_irrelevant_log(self_program_str, l);
-        Ok((None, did_pop))
     }
+    parsed
 }
 
-fn handle_irrelevant_log(this_program_str: &str, log: &str) -> (Option<String>, bool) {
-    let re = Regex::new(r"^Program (.*) invoke$")
-        .expect("Expected invoke regexp to be constructed well");
-    if log.starts_with(&format!("Program {this_program_str} log:")) {
-        (Some(this_program_str.to_string()), false)
-    } else if let Some(c) = re.captures(log) {
-        (
-            c.get(1)
-                .expect("Expected the captured program to be available")
-                .as_str()
-                .to_string()
-                .into(),
-            false,
-        )
-    } else {
-        let re =
-            Regex::new(r"^Program (.*) success$").expect("Expected regexp to be constructed well");
-        (None, re.is_match(log))
+/// Decodes the photon events emitted as self CPIs, which do not depend on the logs being complete
+pub(crate) fn parse_inner_instructions(instructions: &[InnerInstruction]) -> Vec<ParsedEvent> {
+    instructions
+        .iter()
+        .filter_map(|ix| {
+            let data = ix.data.strip_prefix(&anchor_lang::event::EVENT_IX_TAG_LE[..])?;
+            decode_event(data).map(|event| ParsedEvent {
+                event,
+                cpi_depth: ix.cpi_depth,
+                ix_index: ix.ix_index,
+            })
+        })
+        .collect()
+}
+
+fn decode_event(borsh_bytes: &[u8]) -> Option<PhotonEvent> {
+    let Some((disc, data)) = borsh_bytes.split_first_chunk::<8>() else {
+        warn!("Skipping the event data shorter than a discriminator: {}", hex::encode(borsh_bytes));
+        return None;
+    };
+    match PhotonEvent::decode(disc, data)? {
+        Ok(event) => Some(event),
+        Err(err) => {
+            warn!("Skipping undecodable event: {}, data: {}", err, hex::encode(borsh_bytes));
+            None
+        }
     }
 }
 
 #[cfg(test)]
 mod test {
-    use crate::common::solana_logs::parse_logs;
-    use photon::{ProposeEvent, ID as PROGRAM_ID};
+    use crate::common::solana_logs::{
+        parse_logs::{self, LOG_TRUNCATED},
+        photon_events::{ParsedEvent, PhotonEvent},
+        solana_event_listener::InnerInstruction,
+    };
+    use anchor_lang::{__private::base64, event::EVENT_IX_TAG_LE, prelude::Pubkey, Event};
+    use photon::{ProposalExecuted, ProposalLoaded, ProposeEvent, ID as PROGRAM_ID};
+    use rand::{rngs::StdRng, seq::SliceRandom, Rng, SeedableRng};
 
-    #[test]
-    fn test_logs_parsing() {
-        static SAMPLE: &[&str] = &[
-            "Program EjpcUpcuJV2Mq9vjELMZHhgpvJ4ggoWtUYCTFqw6D9CZ invoke [1]",
-            "Program log: Instruction: ShareMessage",
-            "Program log: Share message invoked",
-            "Program pccm961CjaR7T7Hcht9omrXQb9w54ntJo95FFT7N9AJ invoke [2]",
-            "Program log: Instruction: Propose",
-            "Program data: 8vb9LnW1kqUgAAAAb25lZnVuY19fX19fX19fX19fX19fX19fX19fX19fX18IAAAAAAAAAG2BAAAAAAAAAAAAAAAAAAADAAAAAQIDAwAAAAECAwMAAAABAgM=",
-            "Program pccm961CjaR7T7Hcht9omrXQb9w54ntJo95FFT7N9AJ consumed 16408 of 181429 compute units",
-            "Program pccm961CjaR7T7Hcht9omrXQb9w54ntJo95FFT7N9AJ success",
-            "Program EjpcUpcuJV2Mq9vjELMZHhgpvJ4ggoWtUYCTFqw6D9CZ consumed 35308 of 200000 compute units",
-            "Program EjpcUpcuJV2Mq9vjELMZHhgpvJ4ggoWtUYCTFqw6D9CZ success",
+    static PROPOSE_SAMPLE: &[&str] = &[
+        "Program EjpcUpcuJV2Mq9vjELMZHhgpvJ4ggoWtUYCTFqw6D9CZ invoke [1]",
+        "Program log: Instruction: ShareMessage",
+        "Program log: Share message invoked",
+        "Program pccm961CjaR7T7Hcht9omrXQb9w54ntJo95FFT7N9AJ invoke [2]",
+        "Program log: Instruction: Propose",
+        "Program data: 8vb9LnW1kqUgAAAAb25lZnVuY19fX19fX19fX19fX19fX19fX19fX19fX18IAAAAAAAAAG2BAAAAAAAAAAAAAAAAAAADAAAAAQIDAwAAAAECAwMAAAABAgM=",
+        "Program pccm961CjaR7T7Hcht9omrXQb9w54ntJo95FFT7N9AJ consumed 16408 of 181429 compute units",
+        "Program pccm961CjaR7T7Hcht9omrXQb9w54ntJo95FFT7N9AJ success",
+        "Program EjpcUpcuJV2Mq9vjELMZHhgpvJ4ggoWtUYCTFqw6D9CZ consumed 35308 of 200000 compute units",
+        "Program EjpcUpcuJV2Mq9vjELMZHhgpvJ4ggoWtUYCTFqw6D9CZ success",
+    ];
+
+    static DEPLOY_SAMPLE: &[&str] = &[
+        "Program 11111111111111111111111111111111 invoke [1]",
+        "Program 11111111111111111111111111111111 success",
+        "Program BPFLoaderUpgradeab1e11111111111111111111111 invoke [1]",
+        "Program 11111111111111111111111111111111 invoke [2]",
+        "Program 11111111111111111111111111111111 success",
+        "Deployed program 3cAFEXstVzff2dXH8PFMgm81h8sQgpdskFGZqqoDgQkJ",
+        "Program BPFLoaderUpgradeab1e11111111111111111111111 success",
+    ];
+
+    fn program_data(event: Vec<u8>) -> String {
+        format!("Program data: {}", base64::encode(event))
+    }
+
+    fn multiple_events_sample() -> (ProposalLoaded, ProposalExecuted, Vec<String>) {
+        let program = PROGRAM_ID.to_string();
+        let executor = Pubkey::new_unique();
+        let loaded = ProposalLoaded {
+            op_hash: vec![1; 32],
+            executor,
+        };
+        let executed = ProposalExecuted {
+            op_hash: vec![2; 32],
+            executor,
+            return_data: vec![3, 4],
+        };
+        let logs = vec![
+            format!("Program {program} invoke [1]"),
+            "Program log: Instruction: LoadOperation".to_owned(),
+            program_data(loaded.data()),
+            format!("Program {program} success"),
+            format!("Program {program} invoke [1]"),
+            "Program log: Instruction: ExecuteOperation".to_owned(),
+            "Program EjpcUpcuJV2Mq9vjELMZHhgpvJ4ggoWtUYCTFqw6D9CZ invoke [2]".to_owned(),
+            "Program log: Instruction: Increment".to_owned(),
+            "Program EjpcUpcuJV2Mq9vjELMZHhgpvJ4ggoWtUYCTFqw6D9CZ success".to_owned(),
+            program_data(executed.data()),
+            format!("Program {program} success"),
         ];
+        (loaded, executed, logs)
+    }
 
-        let events: Vec<ProposeEvent> = parse_logs::parse_logs(SAMPLE, &PROGRAM_ID.to_string())
-            .expect("Processing logs should not result in errors");
-        assert_eq!(events.len(), 1);
-        let propose_event = events.first().expect("No events caught");
+    fn parse(logs: &[String]) -> parse_logs::ParsedLogs {
+        let logs: Vec<&str> = logs.iter().map(String::as_str).collect();
+        parse_logs::parse_logs(&logs, &PROGRAM_ID.to_string())
+    }
+
+    fn describe(events: &[ParsedEvent]) -> Vec<String> {
+        events.iter().map(|event| format!("{:?}", event)).collect()
+    }
+
+    #[test]
+    fn test_logs_parsing() {
+        let parsed = parse_logs::parse_logs(PROPOSE_SAMPLE, &PROGRAM_ID.to_string());
+        assert!(!parsed.truncated);
+        assert_eq!(parsed.events.len(), 1);
+        let event = parsed.events.first().expect("No events caught");
+        assert_eq!((event.cpi_depth, event.ix_index), (2, 0));
+        let PhotonEvent::ProposeEvent(propose_event) = &event.event else {
+            panic!("Unexpected event: {:?}", event);
+        };
         assert_eq!(propose_event.dst_chain_id, 33133);
         assert_eq!(propose_event.params, vec![1, 2, 3]);
         assert_eq!(propose_event.protocol_id.as_slice(), b"onefunc_________________________");
+        // The sample predates the proposal fees
+        assert_eq!(propose_event.fee, 0);
+        assert_eq!(propose_event.fee_mint, None);
+    }
+
+    #[test]
+    fn test_legacy_executed_parsing() {
+        let program = PROGRAM_ID.to_string();
+        // Emitted before the return data was captured
+        let logs = vec![
+            format!("Program {program} invoke [1]"),
+            "Program log: Instruction: ExecuteOperation".to_owned(),
+            "Program data: XNW9yWVTb1MgAAAAq6urq6urq6urq6urq6urq6urq6urq6urq6urq6urq6sBAgMEBQYHCAkKCwwNDg8QERITFBUWFxgZGhscHR4fIA==".to_owned(),
+            format!("Program {program} success"),
+        ];
+        let events = parse(&logs).events;
+        let [ParsedEvent {
+            event: PhotonEvent::ProposalExecuted(event),
+            ..
+        }] = &events[..]
+        else {
+            panic!("Unexpected events: {:?}", describe(&events));
+        };
+        assert_eq!(event.op_hash, vec![0xab; 32]);
+        assert_eq!(event.executor.to_bytes().to_vec(), (1..=32).collect::<Vec<u8>>());
+        assert!(event.return_data.is_empty());
+    }
+
+    #[test]
+    fn test_propose_with_fee_parsing() {
+        let program = PROGRAM_ID.to_string();
+        let fee_mint = Pubkey::new_unique();
+        let propose = ProposeEvent {
+            protocol_id: b"onefunc_________________________".to_vec(),
+            nonce: 8,
+            dst_chain_id: 33133,
+            protocol_address: vec![1, 2, 3],
+            function_selector: vec![1, 2, 3],
+            params: vec![1, 2, 3],
+            fee: 1000,
+            fee_mint: Some(fee_mint),
+        };
+        let logs = vec![
+            format!("Program {program} invoke [1]"),
+            "Program log: Instruction: Propose".to_owned(),
+            program_data(propose.data()),
+            format!("Program {program} success"),
+        ];
+        let events = parse(&logs).events;
+        let [ParsedEvent {
+            event: PhotonEvent::ProposeEvent(event),
+            ..
+        }] = &events[..]
+        else {
+            panic!("Unexpected events: {:?}", describe(&events));
+        };
+        assert_eq!(event.nonce, 8);
+        assert_eq!(event.params, vec![1, 2, 3]);
+        assert_eq!(event.fee, 1000);
+        assert_eq!(event.fee_mint, Some(fee_mint));
     }
 
     #[test]
     fn test_deploy_programs() {
-        static SAMPLE: &[&str] = &[
-            "Program 11111111111111111111111111111111 invoke [1]",
-            "Program 11111111111111111111111111111111 success",
-            "Program BPFLoaderUpgradeab1e11111111111111111111111 invoke [1]",
-            "Program 11111111111111111111111111111111 invoke [2]",
-            "Program 11111111111111111111111111111111 success",
-            "Deployed program 3cAFEXstVzff2dXH8PFMgm81h8sQgpdskFGZqqoDgQkJ",
-            "Program BPFLoaderUpgradeab1e11111111111111111111111 success",
+        let parsed = parse_logs::parse_logs(DEPLOY_SAMPLE, &PROGRAM_ID.to_string());
+        assert!(parsed.events.is_empty(), "Expected no events have been met")
+    }
+
+    #[test]
+    fn test_multiple_events_parsing() {
+        let (loaded, executed, logs) = multiple_events_sample();
+        let events = parse(&logs).events;
+        assert_eq!(events.len(), 2);
+        let PhotonEvent::ProposalLoaded(event) = &events[0].event else {
+            panic!("Unexpected event: {:?}", events[0]);
+        };
+        assert_eq!(event.op_hash, loaded.op_hash);
+        assert_eq!((events[0].cpi_depth, events[0].ix_index), (1, 0));
+        let PhotonEvent::ProposalExecuted(event) = &events[1].event else {
+            panic!("Unexpected event: {:?}", events[1]);
+        };
+        assert_eq!(event.return_data, executed.return_data);
+        assert_eq!((events[1].cpi_depth, events[1].ix_index), (1, 1));
+    }
+
+    #[test]
+    fn test_malformed_payloads_skipped() {
+        let (loaded, _, mut logs) = multiple_events_sample();
+        let mut corrupted = loaded.data();
+        corrupted.truncate(12);
+        let malformed = [
+            "Program data: AQID".to_owned(),
+            "Program data: not base64 at all".to_owned(),
+            "Program log: AAAAAAAAAAAAAAAAAAAAAA==".to_owned(),
+            program_data(corrupted),
         ];
-        let events: Vec<ProposeEvent> = parse_logs::parse_logs(SAMPLE, &PROGRAM_ID.to_string())
-            .expect("Processing logs should not result in errors");
-        assert!(events.is_empty(), "Expected no events have been met")
+        for (i, log) in malformed.into_iter().enumerate() {
+            logs.insert(2 + i, log);
+        }
+        let parsed = parse(&logs);
+        assert!(!parsed.truncated);
+        assert_eq!(parsed.events.len(), 2, "Only the valid events expected to be decoded");
+    }
+
+    #[test]
+    fn test_truncated_logs() {
+        let (_, _, mut logs) = multiple_events_sample();
+        logs.truncate(7);
+        logs.push(LOG_TRUNCATED.to_owned());
+        let parsed = parse(&logs);
+        assert!(parsed.truncated);
+        assert_eq!(parsed.events.len(), 1);
+        assert!(matches!(parsed.events[0].event, PhotonEvent::ProposalLoaded(_)));
+    }
+
+    #[test]
+    fn test_inner_instructions_parsing() {
+        let (_, executed, _) = multiple_events_sample();
+        let event_cpi = |data: Vec<u8>| InnerInstruction {
+            ix_index: 1,
+            cpi_depth: 2,
+            data: [&EVENT_IX_TAG_LE[..], &data].concat(),
+        };
+        let instructions = [
+            // A regular instruction of the program is not an event
+            InnerInstruction {
+                ix_index: 0,
+                cpi_depth: 2,
+                data: executed.data(),
+            },
+            event_cpi(executed.data()),
+            event_cpi(vec![1, 2, 3]),
+        ];
+        let events = parse_logs::parse_inner_instructions(&instructions);
+        assert_eq!(events.len(), 1);
+        assert_eq!((events[0].cpi_depth, events[0].ix_index), (2, 1));
+        let PhotonEvent::ProposalExecuted(event) = &events[0].event else {
+            panic!("Unexpected event: {:?}", events[0]);
+        };
+        assert_eq!(event.op_hash, executed.op_hash);
+    }
+
+    fn recorded_samples() -> Vec<Vec<String>> {
+        let to_owned = |sample: &[&str]| sample.iter().map(|log| log.to_string()).collect();
+        vec![
+            to_owned(PROPOSE_SAMPLE),
+            to_owned(DEPLOY_SAMPLE),
+            multiple_events_sample().2,
+        ]
+    }
+
+    fn mutate(rng: &mut StdRng, logs: &mut Vec<String>) {
+        let Some(i) = (!logs.is_empty()).then(|| rng.gen_range(0..logs.len())) else {
+            logs.push(LOG_TRUNCATED.to_owned());
+            return;
+        };
+        match rng.gen_range(0..6) {
+            0 => {
+                logs.remove(i);
+            }
+            1 => {
+                let line = logs[i].clone();
+                logs.insert(rng.gen_range(0..logs.len()), line);
+            }
+            2 => {
+                let cut = rng.gen_range(0..=logs[i].len());
+                logs[i].truncate(cut);
+            }
+            3 => {
+                let payload = logs[i].strip_prefix("Program data: ").map(base64::decode);
+                if let Some(Ok(mut payload)) = payload {
+                    if let Some(byte) = payload.choose_mut(rng) {
+                        *byte ^= rng.gen_range(1..=u8::MAX);
+                    }
+                    logs[i] = program_data(payload);
+                }
+            }
+            4 => logs.insert(i, LOG_TRUNCATED.to_owned()),
+            _ => {
+                let program = if rng.gen() {
+                    PROGRAM_ID.to_string()
+                } else {
+                    "11111111111111111111111111111111".to_owned()
+                };
+                logs.insert(i, format!("Program {program} success"));
+            }
+        }
+    }
+
+    #[test]
+    fn prop_mutated_samples_never_panic() {
+        let mut rng = StdRng::seed_from_u64(0x5eed);
+        for _ in 0..2000 {
+            let mut logs = recorded_samples().choose(&mut rng).expect("Samples expected").clone();
+            for _ in 0..rng.gen_range(1..8) {
+                mutate(&mut rng, &mut logs);
+            }
+            let parsed = parse(&logs);
+            assert!(parsed.events.len() <= logs.len());
+            assert_eq!(parsed.truncated, logs.iter().any(|log| log == LOG_TRUNCATED));
+        }
+    }
+
+    #[test]
+    fn prop_truncation_keeps_leading_events() {
+        let mut rng = StdRng::seed_from_u64(0x10c5);
+        for sample in recorded_samples() {
+            let full = describe(&parse(&sample).events);
+            for _ in 0..200 {
+                let mut logs = sample.clone();
+                logs.truncate(rng.gen_range(0..=sample.len()));
+                logs.push(LOG_TRUNCATED.to_owned());
+                let parsed = parse(&logs);
+                assert!(parsed.truncated);
+                let events = describe(&parsed.events);
+                assert_eq!(events[..], full[..events.len()], "Expected the leading events");
+            }
+        }
     }
 }
@@ -0,0 +1,138 @@
+use anchor_lang::{AnchorDeserialize, Discriminator};
+use photon::{
+    AllowedDstChainsUpdated, ConsensusRateChanged, ExecutorAdded, ExecutorRemoved, FeesWithdrawn,
+    GovChangeApplied, GovChangeCancelled, GovChangeQueued, GovTimelockChanged, GuardianChanged,
+    Paused, ProposalApproved, ProposalExecuted, ProposalFailed, ProposalLoaded, ProposeEvent,
+    ProposeFeeChanged, ProposeRateLimitChanged, ProposerAdded, ProposerRemoved,
+    ProtocolAddressChanged, ProtocolRemoved, TransmitterWeightsUpdated, TransmittersUpdated,
+    Unpaused,
+};
+use std::{collections::HashMap, sync::OnceLock};
+
+/// Decodes the borsh serialized event following its discriminator
+type EventDecoder = fn(&[u8]) -> std::io::Result<PhotonEvent>;
+
+macro_rules! photon_events {
+    ($($event:ident),* $(,)?) => {
+        /// Any event emitted by the photon program, each consumer only reads the events it serves
+        #[allow(dead_code)]
+        #[derive(Debug)]
+        pub(crate) enum PhotonEvent {
+            $($event($event),)*
+        }
+
+        /// Maps the event discriminators to their decoders
+        fn registry() -> &'static HashMap<[u8; 8], EventDecoder> {
+            static REGISTRY: OnceLock<HashMap<[u8; 8], EventDecoder>> = OnceLock::new();
+            REGISTRY.get_or_init(|| {
+                HashMap::from([$((
+                    $event::discriminator(),
+                    (|mut data: &[u8]| $event::deserialize(&mut data).map(PhotonEvent::$event))
+                        as EventDecoder,
+                ),)*])
+            })
+        }
+    };
+}
+
+photon_events!(
+    ProposeEvent,
+    ProposalLoaded,
+    ProposalApproved,
+    ProposalExecuted,
+    ProposalFailed,
+    Paused,
+    Unpaused,
+    GovChangeQueued,
+    GovChangeApplied,
+    GovChangeCancelled,
+    ProtocolAddressChanged,
+    ProposerAdded,
+    ProposerRemoved,
+    ExecutorAdded,
+    ExecutorRemoved,
+    TransmittersUpdated,
+    TransmitterWeightsUpdated,
+    ConsensusRateChanged,
+    GuardianChanged,
+    GovTimelockChanged,
+    ProtocolRemoved,
+    AllowedDstChainsUpdated,
+    ProposeRateLimitChanged,
+    ProposeFeeChanged,
+    FeesWithdrawn,
+);
+
+/// Maps the discriminators of the extended events to the decoders of their layouts emitted by the
+/// earlier program versions, so the historical logs are still decoded
+fn legacy_registry() -> &'static HashMap<[u8; 8], EventDecoder> {
+    static REGISTRY: OnceLock<HashMap<[u8; 8], EventDecoder>> = OnceLock::new();
+    REGISTRY.get_or_init(|| {
+        HashMap::from([
+            (ProposeEvent::discriminator(), decode_legacy_propose as EventDecoder),
+            (ProposalExecuted::discriminator(), decode_legacy_executed as EventDecoder),
+        ])
+    })
+}
+
+impl PhotonEvent {
+    /// Decodes the event by its discriminator, `None` if it is not a photon event
+    pub(crate) fn decode(disc: &[u8; 8], data: &[u8]) -> Option<std::io::Result<PhotonEvent>> {
+        registry().get(disc).map(|decoder| {
+            decoder(data).or_else(|err| match legacy_registry().get(disc) {
+                Some(legacy_decoder) => legacy_decoder(data).map_err(|_| err),
+                None => Err(err),
+            })
+        })
+    }
+}
+
+/// Decodes the `ProposeEvent` emitted before the proposal fees were introduced
+fn decode_legacy_propose(mut data: &[u8]) -> std::io::Result<PhotonEvent> {
+    let data = &mut data;
+    let event = ProposeEvent {
+        protocol_id: AnchorDeserialize::deserialize(data)?,
+        nonce: AnchorDeserialize::deserialize(data)?,
+        dst_chain_id: AnchorDeserialize::deserialize(data)?,
+        protocol_address: AnchorDeserialize::deserialize(data)?,
+        function_selector: AnchorDeserialize::deserialize(data)?,
+        params: AnchorDeserialize::deserialize(data)?,
+        fee: 0,
+        fee_mint: None,
+    };
+    require_consumed(data)?;
+    Ok(PhotonEvent::ProposeEvent(event))
+}
+
+/// Decodes the `ProposalExecuted` emitted before the return data was captured
+fn decode_legacy_executed(mut data: &[u8]) -> std::io::Result<PhotonEvent> {
+    let data = &mut data;
+    let event = ProposalExecuted {
+        op_hash: AnchorDeserialize::deserialize(data)?,
+        executor: AnchorDeserialize::deserialize(data)?,
+        return_data: vec![],
+    };
+    require_consumed(data)?;
+    Ok(PhotonEvent::ProposalExecuted(event))
+}
+
+/// Fails if the legacy layout does not cover the whole event data
+fn require_consumed(data: &[u8]) -> std::io::Result<()> {
+    if !data.is_empty() {
+        return Err(std::io::Error::new(
+            std::io::ErrorKind::InvalidData,
+            "Not all bytes read by the legacy event layout",
+        ));
+    }
+    Ok(())
+}
+
+/// Photon event along with its place within the transaction
+#[derive(Debug)]
+pub(crate) struct ParsedEvent {
+    pub event: PhotonEvent,
+    /// The invocation depth the event was emitted at, 1 for the top level instructions
+    pub cpi_depth: usize,
+    /// The index of the top level instruction the event was emitted within
+    pub ix_index: usize,
+}
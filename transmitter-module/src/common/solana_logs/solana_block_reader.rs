@@ -0,0 +1,220 @@
+use log::{debug, info, warn};
+use solana_client::rpc_config::RpcBlockConfig;
+use solana_sdk::clock::Slot;
+use solana_transaction_status::{
+    option_serializer::OptionSerializer, EncodedTransaction, EncodedTransactionWithStatusMeta,
+    TransactionDetails, UiMessage, UiTransactionEncoding,
+};
+use solana_transactor::RpcPool;
+use tokio::sync::mpsc::UnboundedSender;
+
+use crate::common::{
+    config::SolanaClientConfig,
+    solana_logs::{
+        solana_event_listener::LogsBunch, solana_retro_reader::photon_inner_instructions,
+        EventListenerError,
+    },
+};
+
+/// The widest slot range the `getBlocks` method serves at once
+const MAX_GET_BLOCKS_RANGE: u64 = 500_000;
+
+/// Reads the photon transactions of a slot range block by block, regardless of the checkpoint
+pub(super) struct SolanaBlockReader {
+    logs_sender: UnboundedSender<LogsBunch>,
+}
+
+impl SolanaBlockReader {
+    pub(super) fn new(logs_sender: UnboundedSender<LogsBunch>) -> SolanaBlockReader {
+        SolanaBlockReader { logs_sender }
+    }
+
+    /// Sends the logs of the photon transactions within the inclusive slot range in the order of
+    /// execution, all of them to be checked as they may have been processed already
+    pub(super) async fn read_slots(
+        &self,
+        client_config: &SolanaClientConfig,
+        from_slot: Slot,
+        to_slot: Slot,
+    ) -> Result<(), EventListenerError> {
+        let rpc_pool = RpcPool::new(&client_config.read_rpcs, &client_config.write_rpcs)?;
+        let commitment = client_config.commitment;
+        let mut start_slot = from_slot;
+        while start_slot <= to_slot {
+            let end_slot = to_slot.min(start_slot.saturating_add(MAX_GET_BLOCKS_RANGE - 1));
+            let slots = rpc_pool
+                .with_read_rpc_loop(
+                    |rpc| async move {
+                        rpc.get_blocks_with_commitment(start_slot, Some(end_slot), commitment).await
+                    },
+                    commitment,
+                )
+                .await;
+            info!("Backfill slots {}..={}, blocks: {}", start_slot, end_slot, slots.len());
+            for slot in slots {
+                self.read_block(&rpc_pool, client_config, slot).await;
+            }
+            start_slot = end_slot + 1;
+        }
+        Ok(())
+    }
+
+    async fn read_block(&self, rpc_pool: &RpcPool, client_config: &SolanaClientConfig, slot: Slot) {
+        let commitment = client_config.commitment;
+        let block = rpc_pool
+            .with_read_rpc_loop(
+                |rpc| async move {
+                    rpc.get_block_with_config(
+                        slot,
+                        RpcBlockConfig {
+                            encoding: Some(UiTransactionEncoding::Json),
+                            transaction_details: Some(TransactionDetails::Full),
+                            rewards: Some(false),
+                            commitment: Some(commitment),
+                            max_supported_transaction_version: Some(0),
+                        },
+                    )
+                    .await
+                },
+                commitment,
+            )
+            .await;
+        let transactions = block.transactions.unwrap_or_default();
+        debug!("Block read, slot: {}, transactions: {}", slot, transactions.len());
+        for transaction in transactions {
+            let Some(logs_bunch) = Self::photon_logs_bunch(slot, transaction) else {
+                continue;
+            };
+            debug!("Backfill photon transaction: {}", logs_bunch.tx_signature);
+            self.logs_sender.send(logs_bunch).expect("Expected logs_bunch to be sent");
+        }
+    }
+
+    fn photon_logs_bunch(
+        slot: Slot,
+        transaction: EncodedTransactionWithStatusMeta,
+    ) -> Option<LogsBunch> {
+        let meta = transaction.meta.filter(|meta| meta.err.is_none())?;
+        let EncodedTransaction::Json(ref ui_transaction) = transaction.transaction else {
+            warn!("Unexpected transaction encoding in the block: {}", slot);
+            return None;
+        };
+        let UiMessage::Raw(ref message) = ui_transaction.message else {
+            warn!("Unexpected message encoding in the block: {}", slot);
+            return None;
+        };
+        // Photon is invoked either directly or through the CPI, so its account is among the keys
+        let photon_id = photon::ID.to_string();
+        let loaded = match meta.loaded_addresses {
+            OptionSerializer::Some(ref loaded) => {
+                loaded.writable.iter().chain(loaded.readonly.iter()).collect()
+            }
+            _ => Vec::new(),
+        };
+        if !message.account_keys.iter().chain(loaded).any(|key| *key == photon_id) {
+            return None;
+        }
+        let tx_signature = ui_transaction.signatures.first()?.clone();
+        let logs = <Option<Vec<String>>>::from(meta.log_messages.clone())?;
+        Some(LogsBunch {
+            need_check: true,
+            tx_signature,
+            logs,
+            slot,
+            inner_instructions: photon_inner_instructions(&transaction.transaction, &meta),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use solana_sdk::{bs58, pubkey::Pubkey};
+
+    const SIGNATURE: &str =
+        "5h6xBEauJ3PK6SWCZ1PGjBvj8vDdWG3KpwATGy1ARAXFSDwt8GFXM7W5Ncn16wmqokgpiKRLuS83KUxyZyv2sUYv";
+
+    fn transaction(
+        account_keys: &[String],
+        loaded: &[String],
+        err: Option<&str>,
+    ) -> EncodedTransactionWithStatusMeta {
+        let status = match err {
+            Some(err) => json!({ "Err": err }),
+            None => json!({ "Ok": null }),
+        };
+        serde_json::from_value(json!({
+            "transaction": {
+                "signatures": [SIGNATURE],
+                "message": {
+                    "header": {
+                        "numRequiredSignatures": 1,
+                        "numReadonlySignedAccounts": 0,
+                        "numReadonlyUnsignedAccounts": 1
+                    },
+                    "accountKeys": account_keys,
+                    "recentBlockhash": "11111111111111111111111111111111",
+                    "instructions": []
+                }
+            },
+            "meta": {
+                "err": err,
+                "status": status,
+                "fee": 5000,
+                "preBalances": [],
+                "postBalances": [],
+                "innerInstructions": [{
+                    "index": 0,
+                    "instructions": [{
+                        "programIdIndex": account_keys.len() + loaded.len() - 1,
+                        "accounts": [],
+                        "data": bs58::encode([1, 2, 3]).into_string(),
+                        "stackHeight": 3
+                    }]
+                }],
+                "logMessages": ["Program log: photon"],
+                "loadedAddresses": { "writable": [], "readonly": loaded }
+            },
+            "version": 0
+        }))
+        .expect("Expected transaction to be decoded")
+    }
+
+    #[test]
+    fn test_photon_logs_bunch() {
+        let keys = [Pubkey::new_unique().to_string(), photon::ID.to_string()];
+        let logs_bunch = SolanaBlockReader::photon_logs_bunch(7, transaction(&keys, &[], None))
+            .expect("Expected photon transaction to be read");
+        assert!(logs_bunch.need_check);
+        assert_eq!(logs_bunch.tx_signature, SIGNATURE);
+        assert_eq!(logs_bunch.slot, 7);
+        assert_eq!(logs_bunch.logs, vec!["Program log: photon".to_owned()]);
+        let [inner] = &logs_bunch.inner_instructions[..] else {
+            panic!("Expected the photon inner instruction");
+        };
+        assert_eq!((inner.ix_index, inner.cpi_depth, &inner.data[..]), (0, 3, &[1, 2, 3][..]));
+    }
+
+    #[test]
+    fn test_photon_logs_bunch_loaded_from_lookup_table() {
+        let keys = [Pubkey::new_unique().to_string()];
+        let loaded = [photon::ID.to_string()];
+        let logs_bunch = SolanaBlockReader::photon_logs_bunch(7, transaction(&keys, &loaded, None))
+            .expect("Expected photon transaction to be read");
+        assert_eq!(logs_bunch.inner_instructions.len(), 1);
+    }
+
+    #[test]
+    fn test_photon_logs_bunch_skipped() {
+        let keys = [
+            Pubkey::new_unique().to_string(),
+            Pubkey::new_unique().to_string(),
+        ];
+        assert!(SolanaBlockReader::photon_logs_bunch(7, transaction(&keys, &[], None)).is_none());
+
+        let keys = [Pubkey::new_unique().to_string(), photon::ID.to_string()];
+        let failed = transaction(&keys, &[], Some("AccountInUse"));
+        assert!(SolanaBlockReader::photon_logs_bunch(7, failed).is_none());
+    }
+}
@@ -2,13 +2,17 @@ use tokio::sync::mpsc::UnboundedSender;
 
 use transmitter_common::mongodb::MongodbConfig;
 
-use super::{solana_retro_reader::SolanaRetroReader, EventListenerError};
+use super::{
+    solana_block_reader::SolanaBlockReader, solana_retro_reader::SolanaRetroReader,
+    EventListenerError,
+};
 use crate::common::config::SolanaListenerConfig;
 
 pub(crate) struct SolanaEventListener {
     solana_config: SolanaListenerConfig,
     mongodb_config: MongodbConfig,
     logs_retro_reader: SolanaRetroReader,
+    logs_block_reader: SolanaBlockReader,
 }
 
 impl SolanaEventListener {
@@ -20,13 +24,23 @@ impl SolanaEventListener {
         SolanaEventListener {
             solana_config,
             mongodb_config: mongodb_config.clone(),
-            logs_retro_reader: SolanaRetroReader::new(mongodb_config, logs_sender),
+            logs_retro_reader: SolanaRetroReader::new(mongodb_config, logs_sender.clone()),
+            logs_block_reader: SolanaBlockReader::new(logs_sender),
         }
     }
 
     pub(crate) async fn listen_to_solana(&self) -> Result<(), EventListenerError> {
         self.logs_retro_reader.read_events_backward(&self.solana_config, &self.mongodb_config).await
     }
+
+    /// Reads the events of the slot range once, the checkpoint is neither used nor moved
+    pub(crate) async fn backfill(
+        &self,
+        from_slot: u64,
+        to_slot: u64,
+    ) -> Result<(), EventListenerError> {
+        self.logs_block_reader.read_slots(&self.solana_config.client, from_slot, to_slot).await
+    }
 }
 
 pub(crate) struct LogsBunch {
@@ -34,4 +48,13 @@ pub(crate) struct LogsBunch {
     pub tx_signature: String,
     pub logs: Vec<String>,
     pub slot: u64,
+    /// The photon program inner instructions, carrying the events emitted as self CPIs
+    pub inner_instructions: Vec<InnerInstruction>,
+}
+
+pub(crate) struct InnerInstruction {
+    /// The index of the top level instruction the inner one belongs to
+    pub ix_index: usize,
+    pub cpi_depth: usize,
+    pub data: Vec<u8>,
 }
@@ -8,8 +8,11 @@ use solana_client::{
     rpc_client::GetConfirmedSignaturesForAddress2Config, rpc_config::RpcTransactionConfig,
     rpc_response::RpcConfirmedTransactionStatusWithSignature,
 };
-use solana_sdk::{commitment_config::CommitmentConfig, pubkey::Pubkey, signature::Signature};
-use solana_transaction_status::UiTransactionEncoding;
+use solana_sdk::{bs58, commitment_config::CommitmentConfig, pubkey::Pubkey, signature::Signature};
+use solana_transaction_status::{
+    option_serializer::OptionSerializer, EncodedTransaction, UiInstruction, UiMessage,
+    UiTransactionEncoding, UiTransactionStatusMeta,
+};
 use solana_transactor::RpcPool;
 use std::time::Duration;
 use std::{collections::VecDeque, str::FromStr};
@@ -19,7 +22,10 @@ use transmitter_common::mongodb::{mdb_solana_chain_id, MongodbConfig, MDB_LAST_B
 
 use crate::common::{
     config::{SolanaClientConfig, SolanaListenerConfig},
-    solana_logs::{solana_event_listener::LogsBunch, EventListenerError},
+    solana_logs::{
+        solana_event_listener::{InnerInstruction, LogsBunch},
+        EventListenerError,
+    },
 };
 
 pub(super) struct SolanaRetroReader {
@@ -179,23 +185,23 @@ impl SolanaRetroReader {
             )
             .await;
 
-        let logs = transaction
-            .transaction
-            .meta
-            .filter(|meta| meta.err.is_none())
-            .map(|meta| <Option<Vec<String>>>::from(meta.log_messages))
-            .ok_or(())?
-            .ok_or(())?;
+        let meta = transaction.transaction.meta.filter(|meta| meta.err.is_none()).ok_or(())?;
+        let logs = <Option<Vec<String>>>::from(meta.log_messages.clone()).ok_or(())?;
 
         if logs.is_empty() {
             return Ok(());
         }
 
+        // The events emitted as self CPIs are kept in the inner instructions regardless of the logs
+        let inner_instructions =
+            photon_inner_instructions(&transaction.transaction.transaction, &meta);
+
         log_bunches.push_front(LogsBunch {
             need_check,
             tx_signature: signature_with_meta.signature,
             slot: transaction.slot,
             logs,
+            inner_instructions,
         });
         Ok(())
     }
@@ -224,7 +230,7 @@ impl SolanaRetroReader {
         Ok(signatures_backward)
     }
 
-    async fn get_last_processed_block(
+    pub(super) async fn get_last_processed_block(
         &self,
         mongodb_config: &MongodbConfig,
     ) -> Result<Option<String>, EventListenerError> {
@@ -269,3 +275,51 @@ impl SolanaRetroReader {
         Ok(Some(tx_signature))
     }
 }
+
+/// Collects the inner instructions invoking the photon program, the event CPIs among them
+pub(super) fn photon_inner_instructions(
+    transaction: &EncodedTransaction,
+    meta: &UiTransactionStatusMeta,
+) -> Vec<InnerInstruction> {
+    let EncodedTransaction::Json(transaction) = transaction else {
+        warn!("Unexpected transaction encoding, inner instructions skipped");
+        return Vec::new();
+    };
+    let UiMessage::Raw(ref message) = transaction.message else {
+        warn!("Unexpected message encoding, inner instructions skipped");
+        return Vec::new();
+    };
+    // The static keys are followed by the ones loaded from the lookup tables
+    let mut account_keys = message.account_keys.clone();
+    if let OptionSerializer::Some(ref loaded) = meta.loaded_addresses {
+        account_keys.extend(loaded.writable.iter().cloned());
+        account_keys.extend(loaded.readonly.iter().cloned());
+    }
+    let OptionSerializer::Some(ref inner_instructions) = meta.inner_instructions else {
+        warn!("No inner instructions in the transaction meta");
+        return Vec::new();
+    };
+    let photon_id = photon::ID.to_string();
+    let mut result = Vec::new();
+    for inner in inner_instructions {
+        for ix in &inner.instructions {
+            let UiInstruction::Compiled(ix) = ix else {
+                continue;
+            };
+            if account_keys.get(ix.program_id_index as usize) != Some(&photon_id) {
+                continue;
+            }
+            let Ok(data) = bs58::decode(&ix.data).into_vec() else {
+                warn!("Failed to decode inner instruction data: {}", ix.data);
+                continue;
+            };
+            result.push(InnerInstruction {
+                ix_index: inner.index as usize,
+                // Inner instructions are invoked at the second level at least
+                cpi_depth: ix.stack_height.unwrap_or(2) as usize,
+                data,
+            });
+        }
+    }
+    result
+}
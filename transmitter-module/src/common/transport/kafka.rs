@@ -0,0 +1,458 @@
+use async_trait::async_trait;
+use futures_util::{future::BoxFuture, stream::FuturesUnordered, StreamExt};
+use log::{debug, error, info, warn};
+use rdkafka::{
+    consumer::{CommitMode, Consumer as _, StreamConsumer},
+    message::BorrowedMessage,
+    producer::{FutureProducer, FutureRecord, Producer as _},
+    ClientConfig, Message,
+};
+use serde::Deserialize;
+use std::{collections::HashMap, future::pending, time::Duration};
+use tokio::sync::mpsc::Sender;
+use transmitter_common::{config::ReconnectConfig, data::TransmitterMsg};
+
+use super::{
+    decode_msg, default_outbox_capacity, Consumer, PublishConfirm, PublishEvent, Publisher,
+    TransportError,
+};
+use crate::common::outbox::Outbox;
+
+/// Resolves to the delivery tag of the message and whether the brokers have stored it
+type AckFuture = BoxFuture<'static, (u64, bool)>;
+
+const METADATA_TIMEOUT: Duration = Duration::from_secs(5);
+
+#[derive(Clone, Debug, Deserialize)]
+pub(crate) struct KafkaConnectConfig {
+    /// Comma separated `host:port` list of the brokers to bootstrap from
+    pub(crate) brokers: String,
+    /// SASL credentials, they are set as `sasl.username` and `sasl.password`
+    #[serde(default)]
+    pub(crate) user: Option<String>,
+    #[serde(default)]
+    pub(crate) password: Option<String>,
+    /// librdkafka client properties, e.g. `security.protocol` and the `ssl.*` and `sasl.*` ones
+    #[serde(default)]
+    pub(crate) properties: HashMap<String, String>,
+}
+
+impl KafkaConnectConfig {
+    /// The client properties set by the config take precedence over the defaults
+    fn client_config(&self, defaults: &[(&str, &str)]) -> ClientConfig {
+        let mut config = ClientConfig::new();
+        for (key, value) in defaults {
+            config.set(*key, *value);
+        }
+        config.set("bootstrap.servers", &self.brokers);
+        if let Some(user) = &self.user {
+            config.set("sasl.username", user);
+        }
+        if let Some(password) = &self.password {
+            config.set("sasl.password", password);
+        }
+        for (key, value) in &self.properties {
+            config.set(key, value);
+        }
+        config
+    }
+}
+
+#[derive(Deserialize)]
+pub(crate) struct KafkaPublishConfig {
+    #[serde(flatten)]
+    pub(crate) connect: KafkaConnectConfig,
+    /// Topic of the messages
+    pub(crate) topic: String,
+    /// Topic to publish gov registry changes to, they are not published if it is not set
+    #[serde(default)]
+    pub(crate) gov_topic: Option<String>,
+    #[serde(flatten)]
+    pub(crate) reconnect: ReconnectConfig,
+    /// File to keep the unconfirmed messages in over restarts, they are kept in memory if not set
+    #[serde(default)]
+    pub(crate) outbox_path: Option<String>,
+    /// The most messages waiting to be confirmed, the events are not taken further once reached
+    #[serde(default = "default_outbox_capacity")]
+    pub(crate) outbox_capacity: usize,
+}
+
+#[derive(Debug, Deserialize)]
+pub(crate) struct KafkaConsumeConfig {
+    #[serde(flatten)]
+    pub(crate) connect: KafkaConnectConfig,
+    pub(crate) topic: String,
+    /// Consumer group the offsets of the messages are committed to
+    pub(crate) group_id: String,
+    #[serde(flatten)]
+    pub(crate) reconnect: ReconnectConfig,
+}
+
+/// Creates the producer once the brokers respond, trying as many times as the reconnect config
+/// allows. The producer connects again on its own afterwards
+async fn connect(
+    config: &KafkaConnectConfig,
+    reconnect: &ReconnectConfig,
+) -> Result<FutureProducer, TransportError> {
+    let mut attempts = 0;
+    loop {
+        info!("Kafka connect: {}", config.brokers);
+        let producer: FutureProducer = config.client_config(&[]).create()?;
+        let client = producer.clone();
+        let metadata = tokio::task::spawn_blocking(move || {
+            client.client().fetch_metadata(None, METADATA_TIMEOUT).map(|_| ())
+        });
+        match metadata.await.expect("Expected kafka metadata fetch not to panic") {
+            Ok(()) => {
+                info!("Kafka connected");
+                return Ok(producer);
+            }
+            Err(err) => {
+                attempts += 1;
+                warn!("Failed to connect to the kafka: {}, attempt: {}", err, attempts);
+                if attempts == reconnect.attempts {
+                    return Err(TransportError::Kafka(err));
+                }
+                tokio::time::sleep(Duration::from_millis(reconnect.timeout_ms)).await;
+            }
+        }
+    }
+}
+
+pub(crate) struct KafkaPublisher {
+    config: KafkaPublishConfig,
+    producer: Option<FutureProducer>,
+    acks: FuturesUnordered<AckFuture>,
+}
+
+impl KafkaPublisher {
+    pub(crate) fn new(config: KafkaPublishConfig) -> KafkaPublisher {
+        info!(
+            "Kafka messaging arguments are: topic: {}, gov_topic: {:?}",
+            config.topic, config.gov_topic
+        );
+        KafkaPublisher {
+            config,
+            producer: None,
+            acks: FuturesUnordered::new(),
+        }
+    }
+}
+
+#[async_trait]
+impl Publisher for KafkaPublisher {
+    async fn open(&mut self) -> Result<(), TransportError> {
+        self.producer = Some(connect(&self.config.connect, &self.config.reconnect).await?);
+        self.acks = FuturesUnordered::new();
+        Ok(())
+    }
+
+    async fn publish(&mut self, outbox: &mut Outbox) {
+        let producer = self.producer.as_ref().expect("Expected kafka producer to be set");
+        while let Some(msg) = outbox.next_queued() {
+            let id = msg.id;
+            let record = FutureRecord::<(), _>::to(&msg.routing_key).payload(&msg.payload);
+            let delivery = match producer.send_result(record) {
+                Ok(delivery) => delivery,
+                Err((err, _)) => {
+                    error!("Failed to publish message: {}, error: {}", id, err);
+                    outbox.publish_failed(id);
+                    return;
+                }
+            };
+            let delivery_tag = outbox.published(id);
+            self.acks.push(Box::pin(async move {
+                let acked = match delivery.await {
+                    Ok(Ok(_)) => true,
+                    Ok(Err((err, _))) => {
+                        warn!("Publish not acked: {}, error: {}", id, err);
+                        false
+                    }
+                    Err(_) => {
+                        warn!("Publish not acked: {}, the producer is closed", id);
+                        false
+                    }
+                };
+                (delivery_tag, acked)
+            }));
+        }
+    }
+
+    /// The deliveries are reported as they come, each of them confirms a single message. The
+    /// messages the producer fails to deliver in `message.timeout.ms` are nacked
+    async fn next_event(&mut self) -> PublishEvent {
+        let Some((delivery_tag, acked)) = self.acks.next().await else {
+            return pending().await;
+        };
+        debug!("Publish confirm delivery_tag: {}, acked: {}", delivery_tag, acked);
+        let multiple = false;
+        let confirm = if acked {
+            PublishConfirm::Ack {
+                delivery_tag,
+                multiple,
+            }
+        } else {
+            PublishConfirm::Nack {
+                delivery_tag,
+                multiple,
+            }
+        };
+        PublishEvent::Confirm(confirm)
+    }
+}
+
+pub(crate) struct KafkaConsumer {
+    config: KafkaConsumeConfig,
+}
+
+impl KafkaConsumer {
+    pub(crate) fn new(config: KafkaConsumeConfig) -> KafkaConsumer {
+        KafkaConsumer { config }
+    }
+
+    fn subscribe(&self) -> Result<StreamConsumer, TransportError> {
+        let KafkaConsumeConfig {
+            connect,
+            topic,
+            group_id,
+            ..
+        } = &self.config;
+        let consumer: StreamConsumer = connect
+            .client_config(&[("auto.offset.reset", "earliest")])
+            .set("group.id", group_id)
+            .set("enable.auto.commit", "false")
+            .create()
+            .map_err(|err| {
+                error!("Failed to create consumer: {}, error: {}", group_id, err);
+                TransportError::Kafka(err)
+            })?;
+        consumer.subscribe(&[topic]).map_err(|err| {
+            error!("Failed to subscribe to topic: {}, error: {}", topic, err);
+            TransportError::Kafka(err)
+        })?;
+        info!("Consuming messages started, topic: {}, group_id: {}", topic, group_id);
+        Ok(consumer)
+    }
+
+    /// Commits the offset of the message, returns the message decoded unless it is malformed
+    fn commit(consumer: &StreamConsumer, message: &BorrowedMessage) -> Option<TransmitterMsg> {
+        if let Err(err) = consumer.commit_message(message, CommitMode::Async) {
+            error!("Failed to commit kafka message: {}", err);
+            return None;
+        }
+        debug!(
+            "Commit message, topic: {}, partition: {}, offset: {}",
+            message.topic(),
+            message.partition(),
+            message.offset()
+        );
+        message.payload().and_then(decode_msg)
+    }
+}
+
+#[async_trait]
+impl Consumer for KafkaConsumer {
+    /// The consumer connects and rejoins the group on its own, consuming stops once receiving
+    /// fails as many times in a row as the reconnect config allows
+    async fn consume(&mut self, msg_sender: Sender<TransmitterMsg>) -> Result<(), TransportError> {
+        let consumer = self.subscribe()?;
+        let reconnect = &self.config.reconnect;
+        let mut attempts = 0;
+        loop {
+            let received = consumer.recv().await.map(|message| Self::commit(&consumer, &message));
+            let msg = match received {
+                Ok(msg) => {
+                    attempts = 0;
+                    msg
+                }
+                Err(err) => {
+                    attempts += 1;
+                    warn!("Failed to receive kafka message: {}, attempt: {}", err, attempts);
+                    if attempts == reconnect.attempts {
+                        return Err(TransportError::Kafka(err));
+                    }
+                    tokio::time::sleep(Duration::from_millis(reconnect.timeout_ms)).await;
+                    continue;
+                }
+            };
+            let Some(msg) = msg else {
+                continue;
+            };
+            if msg_sender.send(msg).await.is_err() {
+                error!("Failed to send consumed message to the msg_sender");
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::common::amqp_stub::wait_until;
+    use rdkafka::{
+        consumer::BaseConsumer,
+        mocking::MockCluster,
+        producer::DefaultProducerContext,
+        types::{RDKafkaApiKey, RDKafkaRespErr},
+        Offset, TopicPartitionList,
+    };
+    use tokio::sync::mpsc;
+    use transmitter_common::data::{TransmitterMsgImpl, TxFinality};
+
+    const TOPIC: &str = "entangle.from_solana";
+    const GROUP_ID: &str = "solana";
+
+    fn cluster() -> MockCluster<'static, DefaultProducerContext> {
+        let cluster = MockCluster::new(1).unwrap();
+        cluster.create_topic(TOPIC, 1, 1).unwrap();
+        cluster
+    }
+
+    fn connect_config(cluster: &MockCluster<DefaultProducerContext>) -> KafkaConnectConfig {
+        KafkaConnectConfig {
+            brokers: cluster.bootstrap_servers(),
+            user: None,
+            password: None,
+            properties: HashMap::new(),
+        }
+    }
+
+    fn reconnect() -> ReconnectConfig {
+        ReconnectConfig {
+            attempts: 3,
+            timeout_ms: 10,
+        }
+    }
+
+    async fn open_publisher(cluster: &MockCluster<'_, DefaultProducerContext>) -> KafkaPublisher {
+        let mut publisher = KafkaPublisher::new(KafkaPublishConfig {
+            connect: connect_config(cluster),
+            topic: TOPIC.to_string(),
+            gov_topic: None,
+            reconnect: reconnect(),
+            outbox_path: None,
+            outbox_capacity: 10,
+        });
+        publisher.open().await.unwrap();
+        publisher
+    }
+
+    /// Applies the next confirms to the outbox, returns the ids of the messages acked
+    async fn confirm(
+        publisher: &mut KafkaPublisher,
+        outbox: &mut Outbox,
+        count: usize,
+    ) -> Vec<u64> {
+        let mut acked = Vec::new();
+        for _ in 0..count {
+            let next_event = tokio::time::timeout(Duration::from_secs(10), publisher.next_event());
+            let PublishEvent::Confirm(confirm) = next_event.await.unwrap() else {
+                panic!("Expected publish confirm");
+            };
+            acked.extend(outbox.confirm(confirm));
+        }
+        acked.sort();
+        acked
+    }
+
+    fn finalized_msg(slot: u64) -> String {
+        let finality = TxFinality {
+            tx_signature: "signature".to_string(),
+            slot,
+        };
+        let msg = TransmitterMsg::new(TransmitterMsgImpl::ProposalsFinalized(finality), false);
+        serde_json::to_string(&msg).unwrap()
+    }
+
+    /// The offset committed by the consumer group to the partition of the topic
+    fn committed(cluster: &MockCluster<DefaultProducerContext>) -> Offset {
+        let consumer: BaseConsumer =
+            connect_config(cluster).client_config(&[]).set("group.id", GROUP_ID).create().unwrap();
+        let mut partitions = TopicPartitionList::new();
+        partitions.add_partition(TOPIC, 0);
+        let committed = consumer.committed_offsets(partitions, Duration::from_secs(1)).unwrap();
+        committed.find_partition(TOPIC, 0).unwrap().offset()
+    }
+
+    #[tokio::test]
+    async fn test_delivered_messages_acked() {
+        let cluster = cluster();
+        let mut publisher = open_publisher(&cluster).await;
+        let mut outbox = Outbox::open(None, 10).unwrap();
+        let first = outbox.push(TOPIC, "first".to_string());
+        let second = outbox.push(TOPIC, "second".to_string());
+
+        publisher.publish(&mut outbox).await;
+        assert!(outbox.next_queued().is_none());
+        assert_eq!(confirm(&mut publisher, &mut outbox, 2).await, vec![first, second]);
+        assert!(outbox.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_failed_deliveries_nacked_and_published_again() {
+        let cluster = cluster();
+        let mut publisher = open_publisher(&cluster).await;
+        cluster.request_errors(
+            RDKafkaApiKey::Produce,
+            &[RDKafkaRespErr::RD_KAFKA_RESP_ERR_MSG_SIZE_TOO_LARGE],
+        );
+        let mut outbox = Outbox::open(None, 10).unwrap();
+        let id = outbox.push(TOPIC, "rejected".to_string());
+
+        publisher.publish(&mut outbox).await;
+        assert!(confirm(&mut publisher, &mut outbox, 1).await.is_empty());
+        assert!(outbox.next_queued().is_none());
+
+        tokio::time::sleep(outbox.retry_in().unwrap()).await;
+        publisher.publish(&mut outbox).await;
+        assert_eq!(confirm(&mut publisher, &mut outbox, 1).await, vec![id]);
+    }
+
+    #[tokio::test]
+    async fn test_unreachable_brokers_rejected() {
+        let mut publisher = KafkaPublisher::new(KafkaPublishConfig {
+            connect: KafkaConnectConfig {
+                brokers: "127.0.0.1:1".to_string(),
+                user: None,
+                password: None,
+                properties: HashMap::new(),
+            },
+            topic: TOPIC.to_string(),
+            gov_topic: None,
+            reconnect: ReconnectConfig {
+                attempts: 1,
+                timeout_ms: 10,
+            },
+            outbox_path: None,
+            outbox_capacity: 10,
+        });
+        assert!(matches!(publisher.open().await, Err(TransportError::Kafka(_))));
+    }
+
+    #[tokio::test]
+    async fn test_consumed_messages_committed_and_forwarded() {
+        let cluster = cluster();
+        let mut publisher = open_publisher(&cluster).await;
+        let mut outbox = Outbox::open(None, 10).unwrap();
+        // The malformed messages are committed and skipped
+        outbox.push(TOPIC, "malformed".to_string());
+        outbox.push(TOPIC, finalized_msg(1));
+        publisher.publish(&mut outbox).await;
+        confirm(&mut publisher, &mut outbox, 2).await;
+
+        let (msg_sender, mut msg_receiver) = mpsc::channel(1);
+        let mut consumer = KafkaConsumer::new(KafkaConsumeConfig {
+            connect: connect_config(&cluster),
+            topic: TOPIC.to_string(),
+            group_id: GROUP_ID.to_string(),
+            reconnect: reconnect(),
+        });
+        let consume = tokio::spawn(async move { consumer.consume(msg_sender).await });
+        let recv = tokio::time::timeout(Duration::from_secs(10), msg_receiver.recv());
+        let msg = recv.await.unwrap().unwrap();
+        assert_eq!(serde_json::to_string(&msg).unwrap(), finalized_msg(1));
+        wait_until(|| committed(&cluster) == Offset::Offset(2)).await;
+        assert!(!consume.is_finished());
+        consume.abort();
+    }
+}
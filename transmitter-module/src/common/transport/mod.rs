@@ -0,0 +1,181 @@
+use async_trait::async_trait;
+use log::error;
+use serde::{de::DeserializeOwned, Deserialize};
+use thiserror::Error;
+use tokio::sync::mpsc::Sender;
+use transmitter_common::data::TransmitterMsg;
+
+use crate::common::outbox::Outbox;
+
+pub(crate) mod kafka;
+pub(crate) mod rabbitmq;
+
+#[derive(Debug, Error)]
+pub(crate) enum TransportError {
+    #[error("Rabbitmq client error")]
+    Rabbitmq(#[from] amqprs::error::Error),
+    #[error("Kafka client error {0}")]
+    Kafka(#[from] rdkafka::error::KafkaError),
+}
+
+/// Message bus feedback on the messages published
+#[derive(Debug)]
+pub(crate) enum PublishConfirm {
+    Ack {
+        delivery_tag: u64,
+        multiple: bool,
+    },
+    Nack {
+        delivery_tag: u64,
+        multiple: bool,
+    },
+    /// Unroutable message returned ahead of its ack
+    Return {
+        message_id: Option<String>,
+    },
+}
+
+pub(crate) enum PublishEvent {
+    Confirm(PublishConfirm),
+    /// Publishing paused by the message bus can go on
+    Resumed,
+    /// The connection is lost, the messages published through it are not going to be confirmed
+    Closed,
+}
+
+/// Publishes the transmitter messages queued to the outbox, each of them is confirmed by the
+/// message bus on its own
+#[async_trait]
+pub(crate) trait Publisher: Send {
+    /// Connects to the message bus, trying as many times as the reconnect config allows
+    async fn open(&mut self) -> Result<(), TransportError>;
+
+    /// Publishes the queued messages of the outbox unless the message bus paused publishing
+    async fn publish(&mut self, outbox: &mut Outbox);
+
+    /// Waits for the message bus to report on the publishing
+    async fn next_event(&mut self) -> PublishEvent;
+}
+
+/// Consumes the transmitter messages, each of them is acked by the consumer once received
+#[async_trait]
+pub(crate) trait Consumer: Send {
+    /// Sends the consumed messages on, returns once consuming can not be restored
+    async fn consume(&mut self, msg_sender: Sender<TransmitterMsg>) -> Result<(), TransportError>;
+}
+
+#[derive(Clone, Copy, Debug, Default, Deserialize, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub(crate) enum TransportKind {
+    #[default]
+    Rabbitmq,
+    Kafka,
+}
+
+/// The `transport` key picks the message bus, which is configured by its own section. The other
+/// sections are not taken into account, e.g. as partially set by the envs
+#[derive(Deserialize)]
+struct TransportSections {
+    #[serde(default)]
+    transport: TransportKind,
+    rabbitmq: Option<config::Value>,
+    kafka: Option<config::Value>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(
+    try_from = "TransportSections",
+    bound = "R: DeserializeOwned, K: DeserializeOwned"
+)]
+pub(crate) enum TransportConfig<R, K> {
+    Rabbitmq(R),
+    Kafka(K),
+}
+
+impl<R: DeserializeOwned, K: DeserializeOwned> TryFrom<TransportSections>
+    for TransportConfig<R, K>
+{
+    type Error = String;
+
+    fn try_from(sections: TransportSections) -> Result<Self, Self::Error> {
+        let section = match sections.transport {
+            TransportKind::Rabbitmq => sections.rabbitmq,
+            TransportKind::Kafka => sections.kafka,
+        }
+        .ok_or_else(|| {
+            format!("Missing config section of the transport: {:?}", sections.transport)
+        })?;
+        let config = match sections.transport {
+            TransportKind::Rabbitmq => section.try_deserialize().map(TransportConfig::Rabbitmq),
+            TransportKind::Kafka => section.try_deserialize().map(TransportConfig::Kafka),
+        };
+        config
+            .map_err(|err| format!("Malformed {:?} transport config: {}", sections.transport, err))
+    }
+}
+
+pub(crate) type PublishTransportConfig =
+    TransportConfig<rabbitmq::RabbitmqPublishConfig, kafka::KafkaPublishConfig>;
+pub(crate) type ConsumeTransportConfig =
+    TransportConfig<rabbitmq::RabbitmqConsumeConfig, kafka::KafkaConsumeConfig>;
+
+/// Publishing settings the apps share regardless of the message bus
+pub(crate) struct PublishRoutes {
+    /// Routing key or topic of the messages
+    pub(crate) route: String,
+    /// Routing key or topic of gov registry changes, they are not published if it is not set
+    pub(crate) gov_route: Option<String>,
+    pub(crate) outbox_path: Option<String>,
+    pub(crate) outbox_capacity: usize,
+}
+
+impl PublishTransportConfig {
+    pub(crate) fn routes(&self) -> PublishRoutes {
+        match self {
+            TransportConfig::Rabbitmq(config) => PublishRoutes {
+                route: config.binding.routing_key.clone(),
+                gov_route: config.gov_routing_key.clone(),
+                outbox_path: config.outbox_path.clone(),
+                outbox_capacity: config.outbox_capacity,
+            },
+            TransportConfig::Kafka(config) => PublishRoutes {
+                route: config.topic.clone(),
+                gov_route: config.gov_topic.clone(),
+                outbox_path: config.outbox_path.clone(),
+                outbox_capacity: config.outbox_capacity,
+            },
+        }
+    }
+
+    pub(crate) fn into_publisher(self) -> Box<dyn Publisher> {
+        match self {
+            TransportConfig::Rabbitmq(config) => Box::new(rabbitmq::RabbitmqPublisher::new(config)),
+            TransportConfig::Kafka(config) => Box::new(kafka::KafkaPublisher::new(config)),
+        }
+    }
+}
+
+impl ConsumeTransportConfig {
+    pub(crate) fn into_consumer(self) -> Box<dyn Consumer> {
+        match self {
+            TransportConfig::Rabbitmq(config) => Box::new(rabbitmq::RabbitmqConsumer::new(config)),
+            TransportConfig::Kafka(config) => Box::new(kafka::KafkaConsumer::new(config)),
+        }
+    }
+}
+
+fn default_outbox_capacity() -> usize {
+    10000
+}
+
+/// Decodes the consumed message, the malformed ones are skipped
+fn decode_msg(data: &[u8]) -> Option<TransmitterMsg> {
+    let Ok(data) = std::str::from_utf8(data)
+        .map_err(|err| error!("Failed to convert data to string: {}", err))
+    else {
+        return None;
+    };
+    serde_json::from_str(data)
+        .map_err(|err| error!("Failed to deserialize message: {}, data: {}", err, data))
+        .ok()
+}
@@ -0,0 +1,644 @@
+use amqprs::{
+    callbacks::{ChannelCallback, ConnectionCallback},
+    channel::{
+        BasicAckArguments, BasicConsumeArguments, BasicPublishArguments, Channel,
+        ConfirmSelectArguments, ExchangeDeclareArguments, QueueBindArguments,
+        QueueDeclareArguments,
+    },
+    connection::Connection,
+    consumer::AsyncConsumer,
+    Ack, BasicProperties, Cancel, Close, CloseChannel, Deliver, Nack, Return,
+};
+use async_trait::async_trait;
+use log::{debug, error, info, warn};
+use serde::Deserialize;
+use std::{future::pending, sync::Arc, time::Duration};
+use tokio::{
+    select,
+    sync::{
+        mpsc::{unbounded_channel, Sender, UnboundedReceiver, UnboundedSender},
+        watch, Mutex, Notify,
+    },
+};
+use transmitter_common::{
+    config::ReconnectConfig,
+    data::TransmitterMsg,
+    rabbitmq_client::{RabbitmqBindingConfig, RabbitmqClient, RabbitmqConnectConfig},
+};
+
+use super::{
+    decode_msg, default_outbox_capacity, Consumer, PublishConfirm, PublishEvent, Publisher,
+    TransportError,
+};
+use crate::common::outbox::Outbox;
+
+type ConfirmedChannel = (Connection, Channel, UnboundedReceiver<PublishConfirm>);
+
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+struct FlowState {
+    channel_inactive: bool,
+    connection_blocked: bool,
+}
+
+/// Lets the messages be published unless the broker stops the channel flow or blocks the
+/// connection, the messages are kept in the outbox meanwhile
+#[derive(Clone)]
+pub(crate) struct PublishFlow {
+    state: Arc<watch::Sender<FlowState>>,
+}
+
+impl PublishFlow {
+    pub(crate) fn new() -> PublishFlow {
+        PublishFlow {
+            state: Arc::new(watch::Sender::new(FlowState::default())),
+        }
+    }
+
+    pub(crate) fn is_paused(&self) -> bool {
+        *self.state.borrow() != FlowState::default()
+    }
+
+    pub(crate) async fn resumed(&self) {
+        let mut state = self.state.subscribe();
+        let _ = state.wait_for(|state| *state == FlowState::default()).await;
+    }
+
+    /// A new connection and channel start with the flow active
+    pub(crate) fn reset(&self) {
+        self.state.send_replace(FlowState::default());
+    }
+
+    fn set_channel_active(&self, active: bool) {
+        self.state.send_modify(|state| state.channel_inactive = !active);
+    }
+
+    fn set_connection_blocked(&self, blocked: bool) {
+        self.state.send_modify(|state| state.connection_blocked = blocked);
+    }
+}
+
+#[derive(Default)]
+pub(crate) struct ConnectionControl {
+    pub(crate) notify: Arc<Notify>,
+    /// Paused while the connection is blocked if the client publishes
+    pub(crate) flow: Option<PublishFlow>,
+}
+
+impl ConnectionControl {
+    pub(crate) fn new(notify: Arc<Notify>) -> ConnectionControl {
+        ConnectionControl { notify, flow: None }
+    }
+
+    pub(crate) fn publishing(notify: Arc<Notify>, flow: PublishFlow) -> ConnectionControl {
+        ConnectionControl {
+            notify,
+            flow: Some(flow),
+        }
+    }
+}
+
+#[async_trait]
+impl ConnectionCallback for ConnectionControl {
+    async fn close(
+        &mut self,
+        connection: &Connection,
+        close: Close,
+    ) -> Result<(), amqprs::error::Error> {
+        warn!("Rabbitmq connection closed: {}, reason: {}", connection, close);
+        self.notify.notify_waiters();
+        Ok(())
+    }
+
+    async fn blocked(&mut self, connection: &Connection, reason: String) {
+        warn!("Rabbitmq connection blocked: {}, reason: {}", connection, reason);
+        if let Some(flow) = &self.flow {
+            flow.set_connection_blocked(true);
+        }
+    }
+
+    async fn unblocked(&mut self, connection: &Connection) {
+        info!("Rabbitmq connection unblocked: {}", connection);
+        if let Some(flow) = &self.flow {
+            flow.set_connection_blocked(false);
+        }
+    }
+}
+
+#[derive(Default)]
+pub(crate) struct ChannelControl {
+    pub(crate) notify: Arc<Notify>,
+    /// Receives the publish confirms if the publisher tracks them
+    pub(crate) confirm_sender: Option<UnboundedSender<PublishConfirm>>,
+    /// Paused while the channel flow is stopped if the client publishes
+    pub(crate) flow: Option<PublishFlow>,
+    /// Notified once the broker cancels consuming if the client consumes
+    pub(crate) cancel_notify: Option<Arc<Notify>>,
+}
+
+impl ChannelControl {
+    pub(crate) fn publishing(
+        notify: Arc<Notify>,
+        confirm_sender: UnboundedSender<PublishConfirm>,
+        flow: PublishFlow,
+    ) -> ChannelControl {
+        ChannelControl {
+            notify,
+            confirm_sender: Some(confirm_sender),
+            flow: Some(flow),
+            cancel_notify: None,
+        }
+    }
+
+    pub(crate) fn consuming(notify: Arc<Notify>, cancel_notify: Arc<Notify>) -> ChannelControl {
+        ChannelControl {
+            notify,
+            cancel_notify: Some(cancel_notify),
+            ..Default::default()
+        }
+    }
+
+    fn forward_confirm(&self, confirm: PublishConfirm) {
+        if let Some(confirm_sender) = &self.confirm_sender {
+            if let Err(err) = confirm_sender.send(confirm) {
+                warn!("Publish confirm dropped, the publisher is gone: {:?}", err.0);
+            }
+        }
+    }
+}
+
+#[async_trait]
+impl ChannelCallback for ChannelControl {
+    async fn close(
+        &mut self,
+        channel: &Channel,
+        close: CloseChannel,
+    ) -> Result<(), amqprs::error::Error> {
+        warn!("Rabbitmq channel closed: {}, reason: {}", channel, close);
+        self.notify.notify_waiters();
+        Ok(())
+    }
+
+    async fn cancel(
+        &mut self,
+        channel: &Channel,
+        cancel: Cancel,
+    ) -> Result<(), amqprs::error::Error> {
+        warn!(
+            "Rabbitmq cancelled consuming on channel: {}, consumer: {}",
+            channel,
+            cancel.consumer_tag()
+        );
+        match &self.cancel_notify {
+            // The permit is kept if the consumer is not waiting for it at the moment
+            Some(cancel_notify) => cancel_notify.notify_one(),
+            None => error!("Consuming cancelled on the channel not expected to consume"),
+        }
+        Ok(())
+    }
+
+    async fn flow(
+        &mut self,
+        channel: &Channel,
+        active: bool,
+    ) -> Result<bool, amqprs::error::Error> {
+        warn!("Rabbitmq requested to change the flow, channel: {}, active: {}", channel, active);
+        if let Some(flow) = &self.flow {
+            flow.set_channel_active(active);
+        }
+        Ok(active)
+    }
+
+    async fn publish_ack(&mut self, channel: &Channel, ack: Ack) {
+        debug!("Publish ack delivery_tag: {}, channel: {}", ack.delivery_tag(), channel);
+        self.forward_confirm(PublishConfirm::Ack {
+            delivery_tag: ack.delivery_tag(),
+            multiple: ack.mutiple(),
+        });
+    }
+
+    async fn publish_nack(&mut self, channel: &Channel, nack: Nack) {
+        warn!("Publish nack delivery_tag: {}, channel: {}", nack.delivery_tag(), channel);
+        self.forward_confirm(PublishConfirm::Nack {
+            delivery_tag: nack.delivery_tag(),
+            multiple: nack.multiple(),
+        });
+    }
+
+    async fn publish_return(
+        &mut self,
+        channel: &Channel,
+        ret: Return,
+        basic_properties: BasicProperties,
+        content: Vec<u8>,
+    ) {
+        info!("Publish return: {} on channel: {}, content size: {}", ret, channel, content.len());
+        self.forward_confirm(PublishConfirm::Return {
+            message_id: basic_properties.message_id().cloned(),
+        });
+    }
+}
+
+#[derive(Deserialize)]
+pub(crate) struct RabbitmqPublishConfig {
+    #[serde(flatten)]
+    pub(crate) connect: RabbitmqConnectConfig,
+    #[serde(flatten)]
+    pub(crate) binding: RabbitmqBindingConfig,
+    /// Routing key to publish gov registry changes with, they are not published if it is not set
+    #[serde(default)]
+    pub(crate) gov_routing_key: Option<String>,
+    #[serde(flatten)]
+    pub(crate) reconnect: ReconnectConfig,
+    /// File to keep the unconfirmed messages in over restarts, they are kept in memory if not set
+    #[serde(default)]
+    pub(crate) outbox_path: Option<String>,
+    /// The most messages waiting to be confirmed, the events are not taken further once reached
+    #[serde(default = "default_outbox_capacity")]
+    pub(crate) outbox_capacity: usize,
+}
+
+#[derive(Debug, Deserialize)]
+pub(crate) struct RabbitmqConsumeConfig {
+    #[serde(flatten)]
+    pub(crate) connect: RabbitmqConnectConfig,
+    #[serde(flatten)]
+    pub(crate) binding: RabbitmqBindingConfig,
+    pub(crate) consumer_tag: String,
+    pub(crate) queue: String,
+    #[serde(flatten)]
+    pub(crate) reconnect: ReconnectConfig,
+}
+
+/// Publishes the queued messages of the outbox as persistent and mandatory, so the unroutable ones
+/// are returned by the broker
+async fn publish_outbox(
+    channel: &Channel,
+    exchange: &str,
+    outbox: &mut Outbox,
+    flow: &PublishFlow,
+) {
+    while let Some(msg) = outbox.next_queued().filter(|_| !flow.is_paused()) {
+        let id = msg.id;
+        let args = BasicPublishArguments::new(exchange, &msg.routing_key).mandatory(true).finish();
+        let properties = BasicProperties::default()
+            .with_persistence(true)
+            .with_message_id(&id.to_string())
+            .finish();
+        let content = msg.payload.clone().into_bytes();
+        if let Err(err) = channel.basic_publish(properties, content, args).await {
+            error!("Failed to publish message: {}, error: {}", id, err);
+            outbox.publish_failed(id);
+            return;
+        }
+        outbox.published(id);
+    }
+}
+
+pub(crate) struct RabbitmqPublisher {
+    config: RabbitmqPublishConfig,
+    flow: PublishFlow,
+    close_notify: Arc<Notify>,
+    connection: Mutex<Option<ConfirmedChannel>>,
+}
+
+impl RabbitmqPublisher {
+    pub(crate) fn new(config: RabbitmqPublishConfig) -> RabbitmqPublisher {
+        info!(
+            "Rabbitmq messaging arguments are: exchange: {}, routing_key: {}, gov_routing_key: \
+             {:?}",
+            config.binding.exchange, config.binding.routing_key, config.gov_routing_key
+        );
+        RabbitmqPublisher {
+            config,
+            flow: PublishFlow::new(),
+            close_notify: Arc::new(Notify::new()),
+            connection: Mutex::new(None),
+        }
+    }
+
+    async fn recv_confirm(connection: &mut Option<ConfirmedChannel>) -> PublishConfirm {
+        if let Some((_, _, confirm_receiver)) = connection {
+            if let Some(confirm) = confirm_receiver.recv().await {
+                return confirm;
+            }
+        }
+        pending().await
+    }
+}
+
+#[async_trait]
+impl Publisher for RabbitmqPublisher {
+    async fn open(&mut self) -> Result<(), TransportError> {
+        self.init_connection().await
+    }
+
+    async fn publish(&mut self, outbox: &mut Outbox) {
+        let connection = self.connection.get_mut();
+        let (_, channel, _) = connection.as_ref().expect("Expected rabbitmq channel to be set");
+        publish_outbox(channel, &self.config.binding.exchange, outbox, &self.flow).await;
+    }
+
+    async fn next_event(&mut self) -> PublishEvent {
+        let flow = self.flow.clone();
+        select! {
+            _ = self.close_notify.notified() => PublishEvent::Closed,
+            _ = flow.resumed(), if flow.is_paused() => PublishEvent::Resumed,
+            confirm = Self::recv_confirm(self.connection.get_mut()) => {
+                PublishEvent::Confirm(confirm)
+            }
+        }
+    }
+}
+
+#[async_trait]
+impl RabbitmqClient for RabbitmqPublisher {
+    type Error = TransportError;
+
+    async fn reconnect(&self) -> Result<(), TransportError> {
+        self.flow.reset();
+        let conn_control =
+            ConnectionControl::publishing(self.close_notify.clone(), self.flow.clone());
+        let conn = self.connect(&self.config.connect, conn_control).await?;
+        let (confirm_sender, confirm_receiver) = unbounded_channel();
+        let chann_control = ChannelControl::publishing(
+            self.close_notify.clone(),
+            confirm_sender,
+            self.flow.clone(),
+        );
+        let channel = self.open_channel(&conn, chann_control).await?;
+        self.connection.lock().await.replace((conn, channel, confirm_receiver));
+        Ok(())
+    }
+
+    fn reconnect_config(&self) -> &ReconnectConfig {
+        &self.config.reconnect
+    }
+}
+
+pub(crate) struct RabbitmqConsumer {
+    config: RabbitmqConsumeConfig,
+    close_notify: Arc<Notify>,
+    cancel_notify: Arc<Notify>,
+    connection: Mutex<Option<(Connection, Channel)>>,
+}
+
+impl RabbitmqConsumer {
+    pub(crate) fn new(config: RabbitmqConsumeConfig) -> RabbitmqConsumer {
+        RabbitmqConsumer {
+            config,
+            close_notify: Arc::new(Notify::new()),
+            cancel_notify: Arc::new(Notify::new()),
+            connection: Mutex::new(None),
+        }
+    }
+
+    async fn subscribe(&self, msg_sender: &Sender<TransmitterMsg>) -> Result<(), TransportError> {
+        let queue_name = self.init_rabbitmq_structure().await?;
+        let guard = self.connection.lock().await;
+        let (_, channel) = guard.as_ref().expect("Expected rabbitmq channel to be set");
+        let args = BasicConsumeArguments::new(&queue_name, &self.config.consumer_tag);
+        let consumer = MessageConsumer {
+            msg_sender: msg_sender.clone(),
+        };
+        let consumer_tag = channel.basic_consume(consumer, args).await.map_err(|err| {
+            error!("Failed to start consuming: {}, error: {}", queue_name, err);
+            TransportError::from(err)
+        })?;
+        info!("Consuming messages with consumer_tag started: {}", consumer_tag);
+        Ok(())
+    }
+
+    async fn resubscribe(&self, msg_sender: &Sender<TransmitterMsg>) -> Result<(), TransportError> {
+        let mut attempts = 0;
+        let config = self.config.reconnect.clone();
+        while let Err(err) = self.subscribe(msg_sender).await {
+            attempts += 1;
+            warn!("Failed to subscribe to the rabbitmq queue, attempt: {}", attempts);
+            if attempts == config.attempts {
+                return Err(err);
+            }
+            tokio::time::sleep(Duration::from_millis(config.timeout_ms)).await;
+        }
+        Ok(())
+    }
+
+    async fn init_rabbitmq_structure(&self) -> Result<String, TransportError> {
+        let guard = self.connection.lock().await;
+        let (_, channel) = guard.as_ref().expect("Expected rabbitmq channel to be set");
+        let exchange = &self.config.binding.exchange;
+        let exch_args = ExchangeDeclareArguments::new(exchange, "direct").durable(true).finish();
+        channel.exchange_declare(exch_args).await.map_err(|err| {
+            error!("Failed to declare exchange: {}, error: {}", exchange, err);
+            TransportError::from(err)
+        })?;
+
+        let queue_args = QueueDeclareArguments::default()
+            .queue(self.config.queue.clone())
+            .durable(true)
+            .finish();
+        let (queue_name, _, _) = channel
+            .queue_declare(queue_args)
+            .await
+            .map_err(|err| {
+                error!("Failed to declare queue: {}", err);
+                TransportError::from(err)
+            })?
+            .expect("Expected declared queue to be some, no_wait = false");
+
+        let routing_key = &self.config.binding.routing_key;
+
+        channel
+            .queue_bind(QueueBindArguments::new(&queue_name, exchange, routing_key))
+            .await
+            .map_err(|err| {
+                error!("Failed to bind queue: {}", err);
+                TransportError::from(err)
+            })?;
+
+        info!(
+            "Queue created: {}, has been bound to the exchange: {}, routing key: {}",
+            queue_name, exchange, routing_key
+        );
+
+        channel.confirm_select(ConfirmSelectArguments::new(true)).await.map_err(|err| {
+            error!("Failed to confirm_select: {}", err);
+            TransportError::from(err)
+        })?;
+        Ok(queue_name)
+    }
+}
+
+#[async_trait]
+impl Consumer for RabbitmqConsumer {
+    /// The queue is declared and subscribed to again once the connection is recovered or the
+    /// broker cancels consuming, e.g. as the queue is deleted
+    async fn consume(&mut self, msg_sender: Sender<TransmitterMsg>) -> Result<(), TransportError> {
+        self.init_connection().await?;
+        self.subscribe(&msg_sender).await?;
+        loop {
+            select! {
+                _ = self.close_notify.notified() => self.init_connection().await?,
+                _ = self.cancel_notify.notified() => warn!("Rabbitmq consuming to be restored"),
+            }
+            self.resubscribe(&msg_sender).await?;
+        }
+    }
+}
+
+struct MessageConsumer {
+    msg_sender: Sender<TransmitterMsg>,
+}
+
+#[async_trait]
+impl AsyncConsumer for MessageConsumer {
+    async fn consume(
+        &mut self,
+        channel: &Channel,
+        deliver: Deliver,
+        _basic_properties: BasicProperties,
+        data: Vec<u8>,
+    ) {
+        let args = BasicAckArguments::new(deliver.delivery_tag(), false);
+        if let Err(err) = channel.basic_ack(args).await {
+            error!("Failed to do basic ack: {}", err);
+            return;
+        }
+        debug!("Ack to delivery: {}, on channel: {}", deliver.delivery_tag(), channel);
+
+        let Some(msg) = decode_msg(&data) else {
+            return;
+        };
+        debug!(
+            "New message consumed, exchange: {}, routing_key: {}, delivery_tag: {}",
+            deliver.exchange(),
+            deliver.routing_key(),
+            deliver.delivery_tag(),
+        );
+        if self.msg_sender.send(msg).await.is_err() {
+            error!("Failed to send consumed message to the msg_sender");
+        }
+    }
+}
+
+#[async_trait]
+impl RabbitmqClient for RabbitmqConsumer {
+    type Error = TransportError;
+
+    async fn reconnect(&self) -> Result<(), TransportError> {
+        let conn_control = ConnectionControl::new(self.close_notify.clone());
+        let conn = self.connect(&self.config.connect, conn_control).await?;
+        let chann_control =
+            ChannelControl::consuming(self.close_notify.clone(), self.cancel_notify.clone());
+        let channel = self.open_channel(&conn, chann_control).await?;
+        self.connection.lock().await.replace((conn, channel));
+        Ok(())
+    }
+
+    fn reconnect_config(&self) -> &ReconnectConfig {
+        &self.config.reconnect
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::common::amqp_stub::{
+        short_str, wait_until, AmqpStub, BASIC_CANCEL, BASIC_CONSUME, BASIC_PUBLISH, CHANNEL_FLOW,
+        CHANNEL_FLOW_OK, CONNECTION_BLOCKED, CONNECTION_UNBLOCKED, QUEUE_DECLARE,
+    };
+    use amqprs::connection::OpenConnectionArguments;
+    use tokio::sync::mpsc;
+
+    async fn open_publishing(stub: &AmqpStub, flow: &PublishFlow) -> (Connection, Channel) {
+        let config = stub.connect_config();
+        let args =
+            OpenConnectionArguments::new(&config.host, config.port, &config.user, &config.password);
+        let connection = Connection::open(&args).await.unwrap();
+        let notify = Arc::new(Notify::new());
+        let conn_control = ConnectionControl::publishing(notify.clone(), flow.clone());
+        connection.register_callback(conn_control).await.unwrap();
+        let channel = connection.open_channel(None).await.unwrap();
+        let (confirm_sender, _) = mpsc::unbounded_channel();
+        let chann_control = ChannelControl::publishing(notify, confirm_sender, flow.clone());
+        channel.register_callback(chann_control).await.unwrap();
+        channel.confirm_select(ConfirmSelectArguments::new(true)).await.unwrap();
+        (connection, channel)
+    }
+
+    #[tokio::test]
+    async fn test_channel_flow_pauses_publishing() {
+        let stub = AmqpStub::start().await;
+        let flow = PublishFlow::new();
+        let (_connection, channel) = open_publishing(&stub, &flow).await;
+        let mut outbox = Outbox::open(None, 10).unwrap();
+
+        stub.send(channel.channel_id(), CHANNEL_FLOW, vec![0]);
+        stub.wait_received(CHANNEL_FLOW_OK, 1).await;
+        assert!(flow.is_paused());
+        outbox.push("key", "{}".to_string());
+        publish_outbox(&channel, "exchange", &mut outbox, &flow).await;
+        assert!(outbox.next_queued().is_some());
+
+        stub.send(channel.channel_id(), CHANNEL_FLOW, vec![1]);
+        tokio::time::timeout(Duration::from_secs(5), flow.resumed()).await.unwrap();
+        publish_outbox(&channel, "exchange", &mut outbox, &flow).await;
+        assert!(outbox.next_queued().is_none());
+        stub.wait_received(BASIC_PUBLISH, 1).await;
+    }
+
+    #[tokio::test]
+    async fn test_blocked_connection_pauses_publishing() {
+        let stub = AmqpStub::start().await;
+        let flow = PublishFlow::new();
+        let (_connection, _channel) = open_publishing(&stub, &flow).await;
+
+        stub.send(0, CONNECTION_BLOCKED, short_str("low on memory"));
+        wait_until(|| flow.is_paused()).await;
+        stub.send(0, CONNECTION_UNBLOCKED, Vec::new());
+        tokio::time::timeout(Duration::from_secs(5), flow.resumed()).await.unwrap();
+        assert!(!flow.is_paused());
+    }
+
+    #[test]
+    fn test_flow_resumed_only_once_both_lifted() {
+        let flow = PublishFlow::new();
+        flow.set_channel_active(false);
+        flow.set_connection_blocked(true);
+        flow.set_channel_active(true);
+        assert!(flow.is_paused());
+        flow.set_connection_blocked(false);
+        assert!(!flow.is_paused());
+        flow.set_channel_active(false);
+        flow.reset();
+        assert!(!flow.is_paused());
+    }
+
+    #[tokio::test]
+    async fn test_consuming_restored_once_cancelled() {
+        let stub = AmqpStub::start().await;
+        let config = RabbitmqConsumeConfig {
+            connect: stub.connect_config(),
+            binding: RabbitmqBindingConfig {
+                exchange: "exchange".to_string(),
+                routing_key: "routing_key".to_string(),
+            },
+            consumer_tag: "executor".to_string(),
+            queue: "queue".to_string(),
+            reconnect: ReconnectConfig {
+                attempts: 3,
+                timeout_ms: 10,
+            },
+        };
+        let (msg_sender, _msg_receiver) = mpsc::channel(1);
+        let mut consumer = RabbitmqConsumer::new(config);
+        let consume = tokio::spawn(async move { consumer.consume(msg_sender).await });
+        stub.wait_received(BASIC_CONSUME, 1).await;
+
+        // The consumer channel is the first one of the connection
+        stub.send(1, BASIC_CANCEL, [short_str("executor"), vec![1]].concat());
+        stub.wait_received(BASIC_CONSUME, 2).await;
+        assert_eq!(stub.received(QUEUE_DECLARE), 2);
+        assert!(!consume.is_finished());
+        consume.abort();
+    }
+}
@@ -12,12 +12,13 @@ use transmitter_common::data::SignedOperation;
 
 use super::{
     config::ExecutorConfig, error::ExecutorError, last_block_updater::LastBlockUpdater,
-    operation_manager::OperationManager, rabbitmq_consumer::RabbitmqConsumer, ServiceCmd,
+    message_consumer::MessageConsumer, operation_manager::OperationManager, ServiceCmd,
     OP_DATA_SENDER_CAPACITY,
 };
+use crate::common::transport::TransportConfig;
 
 pub(crate) struct ExecutorApp {
-    rabbitmq_consumer: RabbitmqConsumer,
+    message_consumer: MessageConsumer,
     operation_mng: OperationManager,
     service_sender: UnboundedSender<ServiceCmd>,
     last_block_updater: LastBlockUpdater,
@@ -37,7 +38,7 @@ impl ExecutorApp {
 
     pub async fn execute_impl(self, config_path: &str) {
         select! {
-            _ = self.rabbitmq_consumer.execute() => {},
+            _ = self.message_consumer.execute() => {},
             _ = self.operation_mng.execute() => {},
             _ = self.last_block_updater.execute() => {},
             _ = Self::listen_to_signals(config_path, self.service_sender.clone()) => {}
@@ -66,7 +67,10 @@ impl ExecutorApp {
         info!("Executor: {}, balance: {}", executor, balance);
         Ok(ExecutorApp {
             last_block_updater: LastBlockUpdater::try_new(&config, last_block_receiver).await?,
-            rabbitmq_consumer: RabbitmqConsumer::new(config.rabbitmq, op_data_sender),
+            message_consumer: MessageConsumer::new(
+                config.transport.into_consumer(),
+                op_data_sender,
+            ),
             operation_mng: OperationManager::new(
                 op_data_receiver,
                 last_block_sender,
@@ -96,16 +100,26 @@ impl ExecutorApp {
             "mongodb. uri: {}, user: {}, db: {}, key: {}",
             config.mongodb.uri, config.mongodb.user, config.mongodb.db, config.mongodb.key
         );
-        info!(
-            "rabbitmq. host: {}, port: {}, user: {}, queue: {}, binding: {:?}, consumer_tag: {}, reconnect: {:?}",
-            config.rabbitmq.connect.host,
-            config.rabbitmq.connect.port,
-            config.rabbitmq.connect.user,
-            config.rabbitmq.queue,
-            config.rabbitmq.binding,
-            config.rabbitmq.consumer_tag,
-            config.rabbitmq.reconnect
-        );
+        match &config.transport {
+            TransportConfig::Rabbitmq(rabbitmq) => info!(
+                "rabbitmq. host: {}, port: {}, user: {}, queue: {}, binding: {:?}, consumer_tag: {}, reconnect: {:?}",
+                rabbitmq.connect.host,
+                rabbitmq.connect.port,
+                rabbitmq.connect.user,
+                rabbitmq.queue,
+                rabbitmq.binding,
+                rabbitmq.consumer_tag,
+                rabbitmq.reconnect
+            ),
+            TransportConfig::Kafka(kafka) => info!(
+                "kafka. brokers: {}, user: {:?}, topic: {}, group_id: {}, reconnect: {:?}",
+                kafka.connect.brokers,
+                kafka.connect.user,
+                kafka.topic,
+                kafka.group_id,
+                kafka.reconnect
+            ),
+        }
         info!("extensions: {}", config.extensions.join(", "));
     }
 
@@ -3,35 +3,20 @@ use log::{error, info};
 use serde::{de::Error, Deserialize, Deserializer};
 use solana_sdk::{self, bs58, signature::Keypair};
 
-use transmitter_common::{
-    config::ReconnectConfig,
-    mongodb::MongodbConfig,
-    rabbitmq_client::{RabbitmqBindingConfig, RabbitmqConnectConfig},
-};
+use transmitter_common::mongodb::MongodbConfig;
 
 use super::error::ExecutorError;
-use crate::common::config::SolanaClientConfig;
+use crate::common::{config::SolanaClientConfig, transport::ConsumeTransportConfig};
 
 #[derive(Debug, Deserialize)]
 pub(super) struct ExecutorConfig {
     pub(super) extensions: Vec<String>,
-    pub(super) rabbitmq: RabbitmqConfig,
+    #[serde(flatten)]
+    pub(super) transport: ConsumeTransportConfig,
     pub(super) solana: SolanaExecutorConfig,
     pub(super) mongodb: MongodbConfig,
 }
 
-#[derive(Debug, Deserialize)]
-pub(super) struct RabbitmqConfig {
-    #[serde(flatten)]
-    pub(super) connect: RabbitmqConnectConfig,
-    #[serde(flatten)]
-    pub(super) binding: RabbitmqBindingConfig,
-    pub(super) consumer_tag: String,
-    pub(super) queue: String,
-    #[serde(flatten)]
-    pub(super) reconnect: ReconnectConfig,
-}
-
 #[derive(Debug, Deserialize)]
 pub(super) struct SolanaExecutorConfig {
     #[serde(deserialize_with = "deserialize_keypair")]
@@ -79,3 +64,52 @@ where
     Keypair::from_bytes(&keydata)
         .map_err(|err| Error::custom(format!("Malformed keypair bytes: {}", err)))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::common::transport::TransportConfig;
+    use config::FileFormat;
+
+    fn read(overrides: &str) -> ExecutorConfig {
+        // The payer is expected to be set by the envs
+        let payer = format!("solana: {{ payer: {} }}", Keypair::new().to_base58_string());
+        Config::builder()
+            .add_source(File::with_name("doc/executor-config.yml"))
+            .add_source(File::from_str(&payer, FileFormat::Yaml))
+            .add_source(File::from_str(overrides, FileFormat::Yaml))
+            .build()
+            .unwrap()
+            .try_deserialize()
+            .unwrap()
+    }
+
+    #[test]
+    fn test_rabbitmq_transport() {
+        let config = read("rabbitmq: { user: guest, password: guest }");
+        let TransportConfig::Rabbitmq(rabbitmq) = config.transport else {
+            panic!("Expected rabbitmq transport");
+        };
+        assert_eq!(rabbitmq.queue, "signed_operations");
+        assert_eq!(rabbitmq.binding.routing_key, "to_solana");
+        assert_eq!(rabbitmq.reconnect.timeout_ms, 1000);
+    }
+
+    #[test]
+    fn test_kafka_transport() {
+        let config = read(
+            "
+transport: kafka
+kafka: { brokers: localhost:9092, topic: entangle.to_solana, group_id: solana, \
+properties: { security.protocol: ssl } }
+",
+        );
+        let TransportConfig::Kafka(kafka) = config.transport else {
+            panic!("Expected kafka transport");
+        };
+        assert_eq!(kafka.topic, "entangle.to_solana");
+        assert_eq!(kafka.group_id, "solana");
+        let properties = kafka.connect.properties;
+        assert_eq!(properties.get("security.protocol").map(String::as_str), Some("ssl"));
+    }
+}
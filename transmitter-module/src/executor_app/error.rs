@@ -1,6 +1,8 @@
 use thiserror::Error;
 use transmitter_common::error::ExtensionError;
 
+use crate::common::transport::TransportError;
+
 #[derive(Debug, Error)]
 pub(crate) enum ExecutorError {
     #[error("Config error")]
@@ -11,14 +13,20 @@ pub(crate) enum ExecutorError {
     Extension(#[from] ExtensionError),
     #[error("Malformed operation data")]
     MalformedData,
-    #[error("Rabbitmq client error")]
-    Rabbitmq(#[from] amqprs::error::Error),
+    #[error("Message transport error {0}")]
+    Transport(#[from] TransportError),
     #[error("Mongodb client error")]
     Mongodb(#[from] mongodb::error::Error),
     #[error("Solana transactor error {0}")]
     SolanaTransactorError(#[from] solana_transactor::TransactorError),
     #[error("Solana client error {0}")]
-    SolanaClientError(#[from] solana_client::client_error::ClientError),
+    SolanaClientError(Box<solana_client::client_error::ClientError>),
     #[error("Tokio channel error {0}")]
     ChannelError(#[from] tokio::sync::mpsc::error::SendError<u64>),
 }
+
+impl From<solana_client::client_error::ClientError> for ExecutorError {
+    fn from(err: solana_client::client_error::ClientError) -> Self {
+        ExecutorError::SolanaClientError(Box::new(err))
+    }
+}
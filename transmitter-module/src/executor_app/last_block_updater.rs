@@ -130,10 +130,7 @@ impl OpRegistry {
 
     fn get_block_to_ack(&mut self) -> Option<u64> {
         let mut block = None;
-        loop {
-            let Some(entry) = self.known_ops.first_entry() else {
-                break;
-            };
+        while let Some(entry) = self.known_ops.first_entry() {
             let block_info = entry.get();
             let elapsed = block_info.created_at.elapsed();
             if elapsed > self.ack_timeout && block_info.ops.is_empty() {
@@ -0,0 +1,59 @@
+use log::{debug, error, warn};
+use tokio::{
+    select,
+    sync::mpsc::{channel, Receiver, Sender},
+};
+
+use transmitter_common::data::{
+    SignedOperation, TransmitterMsg, TransmitterMsgImpl, TransmitterMsgVersioned,
+};
+
+use super::{error::ExecutorError, OP_DATA_SENDER_CAPACITY};
+use crate::common::transport::Consumer;
+
+/// Takes the signed operations out of the consumed messages
+pub(super) struct MessageConsumer {
+    consumer: Box<dyn Consumer>,
+    op_data_sender: Sender<SignedOperation>,
+}
+
+impl MessageConsumer {
+    pub(super) fn new(
+        consumer: Box<dyn Consumer>,
+        op_data_sender: Sender<SignedOperation>,
+    ) -> MessageConsumer {
+        MessageConsumer {
+            consumer,
+            op_data_sender,
+        }
+    }
+
+    pub(super) async fn execute(mut self) -> Result<(), ExecutorError> {
+        let (msg_sender, msg_receiver) = channel(OP_DATA_SENDER_CAPACITY);
+        select! {
+            result = self.consumer.consume(msg_sender) => result.map_err(ExecutorError::from),
+            _ = Self::forward_operations(msg_receiver, &self.op_data_sender) => Ok(()),
+        }
+    }
+
+    async fn forward_operations(
+        mut msg_receiver: Receiver<TransmitterMsg>,
+        op_data_sender: &Sender<SignedOperation>,
+    ) {
+        while let Some(msg) = msg_receiver.recv().await {
+            let signed_operation = match msg.msg {
+                TransmitterMsgVersioned::V1(TransmitterMsgImpl::SignedOperationData(
+                    signed_operation,
+                )) => signed_operation,
+                msg => {
+                    warn!("Received unexpected data: {:?}", msg);
+                    continue;
+                }
+            };
+            debug!("New signed operation consumed: {}", signed_operation);
+            if op_data_sender.send(signed_operation).await.is_err() {
+                error!("Failed to send signed operation to the op_data_sender");
+            }
+        }
+    }
+}
@@ -3,8 +3,8 @@ mod config;
 mod error;
 mod extension_manager;
 mod last_block_updater;
+mod message_consumer;
 mod operation_manager;
-mod rabbitmq_consumer;
 
 use std::fmt::{Display, Formatter};
 
@@ -49,6 +49,8 @@ impl Display for OpAcknowledge {
 #[derive(Copy, Clone, Debug, PartialEq)]
 enum ExecutorOpStatus {
     New,
+    /// Loaded by an earlier program version, the operation info is to be migrated first
+    Legacy,
     Loaded,
     Signed,
     Executed,
@@ -62,6 +64,7 @@ impl From<OpStatus> for ExecutorOpStatus {
             OpStatus::Init => ExecutorOpStatus::Loaded,
             OpStatus::Signed => ExecutorOpStatus::Signed,
             OpStatus::Executed => ExecutorOpStatus::Executed,
+            OpStatus::Failed => ExecutorOpStatus::Failed,
         }
     }
 }
@@ -4,9 +4,17 @@ use anchor_lang::{
 };
 use futures_util::{select, FutureExt, StreamExt};
 use log::*;
-use photon::{photon::ROOT, OpInfo};
+use photon::{
+    migrate::LegacyOpInfo,
+    photon::{FAILURE_REPORT_DELAY, ROOT},
+    protocol_data::Meta,
+    OpInfo, ProcessedNonces,
+};
+use solana_client::rpc_config::RpcSimulateTransactionConfig;
 use solana_sdk::{
-    address_lookup_table::AddressLookupTableAccount, instruction::Instruction, signer::Signer,
+    address_lookup_table::AddressLookupTableAccount, compute_budget::ComputeBudgetInstruction,
+    instruction::Instruction, signer::Signer, transaction::Transaction,
+    transaction::TransactionError,
 };
 use solana_transactor::{ix_compiler::InstructionBundle, log_with_ctx, SolanaTransactor};
 use std::ops::Deref;
@@ -14,7 +22,7 @@ use std::sync::{
     atomic::{AtomicU64, AtomicUsize, Ordering},
     Arc,
 };
-use std::time::Duration;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
 use tokio::sync::{
     mpsc::{Receiver, UnboundedReceiver, UnboundedSender},
     Mutex, Notify,
@@ -142,6 +150,9 @@ impl OperationManager {
                 (Some(value), ref mut attempts) if value == op_status => {
                     *attempts += 1;
                     if *attempts >= self.solana_config.executor_attempts {
+                        if op_status == ExecutorOpStatus::Signed {
+                            self.report_failure(op_hash, &op.operation_data, alt).await;
+                        }
                         op_status = ExecutorOpStatus::Failed;
                     }
                 }
@@ -172,6 +183,117 @@ impl OperationManager {
         Ok(())
     }
 
+    /// Acknowledges the failure to the source chain if the operation requested it. Only the
+    /// executions rejected by the program are reported, the ones failing for transient reasons
+    /// like RPC outages are left to be executed later
+    async fn report_failure(
+        &self,
+        op_hash: OpHash,
+        op_data: &OperationData,
+        alt: &[AddressLookupTableAccount],
+    ) {
+        let op_hash_str = hex::encode(op_hash);
+        let ack_requested =
+            Meta::decode(&op_data.meta).is_ok_and(|m| m.has_flag(Meta::FLAG_FAILURE_CALLBACK));
+        if !ack_requested {
+            return;
+        }
+        let payer = self.solana_config.payer.pubkey();
+        match self.simulate_execution(payer, op_hash, op_data).await {
+            Ok(Some(TransactionError::InstructionError(ix, err))) => {
+                warn!("{}. Execution rejected at instruction {}: {}", op_hash_str, ix, err);
+            }
+            Ok(err) => {
+                warn!("{}. Execution failure is not reported, simulated: {:?}", op_hash_str, err);
+                return;
+            }
+            Err(err) => {
+                warn!("{}. Failed to simulate the execution, not reported: {}", op_hash_str, err);
+                return;
+            }
+        }
+        self.await_failure_report_delay(op_hash).await;
+        let ix_bundle = [build_report_failure_tx(payer, op_hash, op_data)];
+        if let Err(err) = self
+            .transactor
+            .send_all_instructions(
+                Some(op_hash_str.deref()),
+                &ix_bundle,
+                &[&self.solana_config.payer],
+                payer,
+                1,
+                alt,
+                None,
+                false,
+            )
+            .await
+        {
+            error!("{}. Failed to report the operation failure: {}", op_hash_str, err);
+        }
+    }
+
+    /// Simulates the execution of the operation, returns its error if it fails
+    async fn simulate_execution(
+        &self,
+        payer: Pubkey,
+        op_hash: OpHash,
+        op_data: &OperationData,
+    ) -> Result<Option<TransactionError>, ExecutorError> {
+        let bundle = build_execute_tx(&self.extension_mng, payer, op_hash, op_data.clone())?;
+        let tx = Transaction::new_with_payer(
+            &[
+                ComputeBudgetInstruction::set_compute_unit_limit(bundle.compute_units),
+                bundle.instruction,
+            ],
+            Some(&payer),
+        );
+        let config = RpcSimulateTransactionConfig {
+            sig_verify: false,
+            replace_recent_blockhash: true,
+            commitment: Some(self.solana_config.client.commitment),
+            ..Default::default()
+        };
+        let result = self
+            .transactor
+            .rpc_pool()
+            .with_read_rpc(
+                |rpc| async move { rpc.simulate_transaction_with_config(&tx, config).await },
+                self.solana_config.client.commitment,
+            )
+            .await?;
+        Ok(result.value.err)
+    }
+
+    /// Waits for the operation to stay signed for the time the program requires before accepting
+    /// its failure report
+    async fn await_failure_report_delay(&self, op_hash: OpHash) {
+        let (op_info, _) = Pubkey::find_program_address(&[ROOT, b"OP", &op_hash], &photon::ID);
+        let op_info_data = self
+            .transactor
+            .rpc_pool()
+            .with_read_rpc_loop(
+                |rpc| async move {
+                    rpc.get_account_with_commitment(&op_info, self.solana_config.client.commitment)
+                        .await
+                },
+                self.solana_config.client.commitment,
+            )
+            .await
+            .value;
+        let Some(signed_at) = op_info_data
+            .and_then(|acc| OpInfo::try_deserialize(&mut &acc.data[..]).ok())
+            .map(|op_info| op_info.signed_at)
+        else {
+            return;
+        };
+        let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default().as_secs() as i64;
+        let report_at = signed_at.saturating_add(FAILURE_REPORT_DELAY);
+        if report_at >= now {
+            debug!("{}. Failure report delayed for {}s", hex::encode(op_hash), report_at - now + 1);
+            tokio::time::sleep(Duration::from_secs((report_at - now + 1) as u64)).await;
+        }
+    }
+
     fn ack_executed(&self, eob_block_number: u64, op_hash: OpHash, op_status: ExecutorOpStatus) {
         self.op_acknowledge_sender
             .send(OpAcknowledge::new(eob_block_number, op_hash, op_status))
@@ -193,6 +315,9 @@ impl OperationManager {
             .await
             .value;
         let op_status = match op_info_data {
+            Some(acc) if LegacyOpInfo::decode(&acc.data, &op_hash).is_some() => {
+                ExecutorOpStatus::Legacy
+            }
             Some(acc) => match OpInfo::try_deserialize(&mut &acc.data[..]) {
                 Ok(s) => ExecutorOpStatus::from(s.status),
                 Err(e) => {
@@ -227,9 +352,11 @@ impl OperationManager {
     async fn check_balance_and_suspend(&self, op_hash: &str) -> bool {
         let suspending_config = &self.solana_config.suspending_config;
 
-        if self.suspending_ctx.op_proc_counter.load(Ordering::Acquire)
-            % suspending_config.check_balance_period
-            == 0
+        if self
+            .suspending_ctx
+            .op_proc_counter
+            .load(Ordering::Acquire)
+            .is_multiple_of(suspending_config.check_balance_period)
         {
             let Ok(new_balance) = self.get_balance().await else {
                 return false;
@@ -301,6 +428,7 @@ impl OperationManager {
                     op.operation_data.clone(),
                 )?,
             ],
+            ExecutorOpStatus::Legacy => vec![build_migrate_ix(payer, op_hash)],
             ExecutorOpStatus::Loaded => vec![
                 build_sign_tx(payer, op_hash, op.clone())?,
                 build_execute_tx(&self.extension_mng, payer, op_hash, op.operation_data.clone())?,
@@ -318,6 +446,22 @@ impl OperationManager {
     }
 }
 
+fn build_migrate_ix(payer: Pubkey, op_hash: [u8; 32]) -> InstructionBundle {
+    let (op_info_pda, _) = Pubkey::find_program_address(&[ROOT, b"OP", &op_hash], &photon::ID);
+    let accounts = photon::accounts::MigrateOpInfo {
+        payer,
+        op_info: op_info_pda,
+        system_program: anchor_lang::system_program::ID,
+    }
+    .to_account_metas(None);
+    let migrate_data = photon::instruction::MigrateOpInfo {
+        op_hash: op_hash.to_vec(),
+    }
+    .data();
+    let ix = Instruction::new_with_bytes(photon::id(), &migrate_data, accounts);
+    InstructionBundle::new(ix, 200000)
+}
+
 fn build_load_ix(
     executor: Pubkey,
     op_hash: [u8; 32],
@@ -328,12 +472,27 @@ fn build_load_ix(
     let (protocol_info_pda, _) =
         Pubkey::find_program_address(&[ROOT, b"PROTOCOL", &op_data.protocol_id.0], &photon::ID);
     let (config_pda, _) = Pubkey::find_program_address(&[ROOT, b"CONFIG"], &photon::ID);
+    let (processed_nonces_pda, _) = Pubkey::find_program_address(
+        &[
+            ROOT,
+            b"NONCES",
+            &op_data.protocol_id.0,
+            &op_data.src_chain_id.to_be_bytes(),
+            &ProcessedNonces::window(op_data.nonce).to_be_bytes(),
+        ],
+        &photon::ID,
+    );
     let accounts: Vec<AccountMeta> = photon::accounts::LoadOperation {
         executor,
         protocol_info: protocol_info_pda,
         op_info: op_info_pda,
         config: config_pda,
+        processed_nonces: processed_nonces_pda,
         system_program: anchor_lang::system_program::ID,
+        #[cfg(feature = "event-cpi")]
+        event_authority: event_authority_pda(),
+        #[cfg(feature = "event-cpi")]
+        program: photon::ID,
     }
     .to_account_metas(None);
     let protocol_id = String::from_utf8(op_data.protocol_id.0.to_vec()).map_err(|err| {
@@ -346,11 +505,7 @@ fn build_load_ix(
     );
     let photon_op_data =
         photon::protocol_data::OperationData::try_from(op_data).map_err(|err| {
-            error!(
-                "{}. Failed to get op_data from op_data_message: {}",
-                op_hash_str,
-                hex::encode(err)
-            );
+            error!("{}. Failed to get op_data from op_data_message: {}", op_hash_str, err);
             ExecutorError::MalformedData
         })?;
     let load_op_data = photon::instruction::LoadOperation {
@@ -378,6 +533,11 @@ fn build_sign_tx(
         executor,
         op_info: op_info_pda,
         protocol_info: protocol_info_pda,
+        system_program: anchor_lang::system_program::ID,
+        #[cfg(feature = "event-cpi")]
+        event_authority: event_authority_pda(),
+        #[cfg(feature = "event-cpi")]
+        program: photon::ID,
     }
     .to_account_metas(None);
 
@@ -411,12 +571,22 @@ fn build_execute_tx(
         Pubkey::find_program_address(&[ROOT, b"PROTOCOL", &protocol_id.0], &photon::ID);
     let (call_authority_pda, _) =
         Pubkey::find_program_address(&[ROOT, b"CALL_AUTHORITY", &protocol_id.0], &photon::ID);
+    let (config_pda, _) = Pubkey::find_program_address(&[ROOT, b"CONFIG"], &photon::ID);
+    let ack_requested =
+        Meta::decode(&op_data.meta).is_ok_and(|m| m.has_flag(Meta::FLAG_FAILURE_CALLBACK));
 
     let mut accounts = photon::accounts::ExecuteOperation {
         executor,
         op_info: op_info_pda,
         protocol_info: protocol_info_pda,
         call_authority: call_authority_pda,
+        config: config_pda,
+        ack_nonce: ack_requested.then(|| ack_nonce_pda(&op_data)),
+        system_program: ack_requested.then_some(anchor_lang::system_program::ID),
+        #[cfg(feature = "event-cpi")]
+        event_authority: event_authority_pda(),
+        #[cfg(feature = "event-cpi")]
+        program: photon::ID,
     }
     .to_account_metas(None);
     let function_selector = &op_data.function_selector;
@@ -439,3 +609,54 @@ fn build_execute_tx(
         extension.get_compute_budget(&function_selector[2..], &op_data.params).unwrap_or(200000);
     Ok(InstructionBundle::new(ix, compute_units))
 }
+
+fn build_report_failure_tx(
+    executor: Pubkey,
+    op_hash: [u8; 32],
+    op_data: &OperationData,
+) -> InstructionBundle {
+    let (op_info_pda, _bump) = Pubkey::find_program_address(&[ROOT, b"OP", &op_hash], &photon::ID);
+    let (protocol_info_pda, _) =
+        Pubkey::find_program_address(&[ROOT, b"PROTOCOL", &op_data.protocol_id.0], &photon::ID);
+    let (config_pda, _) = Pubkey::find_program_address(&[ROOT, b"CONFIG"], &photon::ID);
+    let accounts = photon::accounts::ReportOperationFailure {
+        executor,
+        op_info: op_info_pda,
+        protocol_info: protocol_info_pda,
+        config: config_pda,
+        ack_nonce: ack_nonce_pda(op_data),
+        system_program: anchor_lang::system_program::ID,
+        #[cfg(feature = "event-cpi")]
+        event_authority: event_authority_pda(),
+        #[cfg(feature = "event-cpi")]
+        program: photon::ID,
+    }
+    .to_account_metas(None);
+    let report_data = photon::instruction::ReportOperationFailure {
+        op_hash: op_hash.to_vec(),
+    }
+    .data();
+    let ix = Instruction::new_with_bytes(photon::id(), &report_data, accounts);
+    InstructionBundle::new(ix, 200000)
+}
+
+/// The proposal nonce of the protocol to the source chain of the operation, sequencing its
+/// acknowledgement
+fn ack_nonce_pda(op_data: &OperationData) -> Pubkey {
+    Pubkey::find_program_address(
+        &[
+            ROOT,
+            b"NONCE",
+            &op_data.protocol_id.0,
+            &op_data.src_chain_id.to_be_bytes(),
+        ],
+        &photon::ID,
+    )
+    .0
+}
+
+/// The PDA signing the photon event self CPIs, required by the instructions emitting events
+#[cfg(feature = "event-cpi")]
+fn event_authority_pda() -> Pubkey {
+    Pubkey::find_program_address(&[photon::emit::EVENT_AUTHORITY_SEED], &photon::ID).0
+}
@@ -1,15 +1,19 @@
-use crate::common::solana_logs::solana_event_listener::SolanaEventListener;
-use log::info;
+use crate::common::{
+    outbox::Outbox, solana_logs::solana_event_listener::SolanaEventListener,
+    transport::TransportConfig,
+};
+use log::{error, info};
 use tokio::sync::mpsc::unbounded_channel;
 
 use super::{
-    config::ListenConfig, rabbitmq_publisher::RabbitmqPublisher,
-    solana_logs_processor::ProposalEventProcessor,
+    checkpoint::CheckpointTracker, config::ListenConfig, error::ListenError,
+    finality_tracker::FinalityTracker, gov_event_processor::GovEventProcessor,
+    message_publisher::MessagePublisher, solana_logs_processor::ProposalEventProcessor,
 };
 
 pub(crate) struct ListenerApp {
     solana_listener: SolanaEventListener,
-    rabbitmq_sender: RabbitmqPublisher,
+    message_publisher: MessagePublisher,
     solana_logs_proc: ProposalEventProcessor,
 }
 
@@ -20,27 +24,79 @@ impl ListenerApp {
             return;
         };
 
-        let mut app = ListenerApp::new(config);
+        let Ok(mut app) = ListenerApp::try_new(config, true).map_err(|err| {
+            error!("Failed to create listener: {}", err);
+        }) else {
+            return;
+        };
         app.execute_impl().await;
     }
 
-    fn new(config: ListenConfig) -> ListenerApp {
+    /// Republishes the events of the inclusive slot range to be checked by the consumers, the
+    /// proposals carry no latest block so the checkpoint moves neither back nor past the live one.
+    /// The outbox is kept in memory, the file of the live listener is left to it
+    pub(crate) async fn backfill(config_path: &str, from_slot: u64, to_slot: u64) {
+        info!("Backfill started {}", chrono::Local::now().format("%Y-%m-%d %H:%M:%S"));
+        if from_slot > to_slot {
+            error!("Malformed slot range: {}..={}", from_slot, to_slot);
+            return;
+        }
+        let Ok(config) = ListenConfig::try_from_path(config_path) else {
+            return;
+        };
+
+        let Ok(mut app) = ListenerApp::try_new(config, false).map_err(|err| {
+            error!("Failed to create listener: {}", err);
+        }) else {
+            return;
+        };
+        app.message_publisher.freeze_checkpoint();
+        app.solana_logs_proc.republish();
+        app.backfill_impl(from_slot, to_slot).await;
+    }
+
+    /// Creates the listener, the outbox is kept in `outbox_path` only with `persist_outbox` set
+    fn try_new(config: ListenConfig, persist_outbox: bool) -> Result<ListenerApp, ListenError> {
         Self::trace_config(&config);
+        let finality_tracker = FinalityTracker::new(&config.solana)?;
+        let checkpoint_tracker = CheckpointTracker::new(config.mongodb.clone());
+        let routes = config.transport.routes();
+        let outbox_path = routes.outbox_path.as_deref().filter(|_| persist_outbox);
+        let outbox = Outbox::open(outbox_path, routes.outbox_capacity)?;
         let (propose_sender, propose_receiver) = unbounded_channel();
         let (logs_sender, logs_receiver) = unbounded_channel();
-        ListenerApp {
+        let (gov_event_proc, gov_change_receiver) = if routes.gov_route.is_some() {
+            let (gov_change_sender, gov_change_receiver) = unbounded_channel();
+            (Some(GovEventProcessor::new(gov_change_sender)), Some(gov_change_receiver))
+        } else {
+            (None, None)
+        };
+        Ok(ListenerApp {
             solana_listener: SolanaEventListener::new(config.solana, config.mongodb, logs_sender),
-            rabbitmq_sender: RabbitmqPublisher::new(config.rabbitmq, propose_receiver),
+            message_publisher: MessagePublisher::new(
+                config.transport.into_publisher(),
+                routes,
+                propose_receiver,
+                gov_change_receiver,
+                outbox,
+                finality_tracker,
+                checkpoint_tracker,
+            ),
             solana_logs_proc: ProposalEventProcessor::new(
                 logs_receiver,
                 propose_sender,
                 config.allowed_protocols,
+                gov_event_proc,
             ),
-        }
+        })
     }
 
     fn trace_config(config: &ListenConfig) {
         info!("solana_commitment: {}", config.solana.client.commitment.commitment);
+        info!(
+            "solana_finality: {:?}, check_interval_ms: {}",
+            config.solana.finality, config.solana.finality_check_interval_ms
+        );
 
         for rpc in &config.solana.client.read_rpcs {
             info!("solana_read_rpc: {}, rate_limit: {}", rpc.url, rpc.ratelimit);
@@ -54,21 +110,53 @@ impl ListenerApp {
             "mongodb. uri: {}, user: {}, db: {}, key: {}",
             config.mongodb.uri, config.mongodb.user, config.mongodb.db, config.mongodb.key
         );
-        info!(
-            "rabbitmq. host: {}, port: {}, user: {},  binding: {:?},  reconnect: {:?}",
-            config.rabbitmq.connect.host,
-            config.rabbitmq.connect.port,
-            config.rabbitmq.connect.user,
-            config.rabbitmq.binding,
-            config.rabbitmq.reconnect
-        );
+        match &config.transport {
+            TransportConfig::Rabbitmq(rabbitmq) => info!(
+                "rabbitmq. host: {}, port: {}, user: {},  binding: {:?},  gov_routing_key: {:?},  \
+                 reconnect: {:?}",
+                rabbitmq.connect.host,
+                rabbitmq.connect.port,
+                rabbitmq.connect.user,
+                rabbitmq.binding,
+                rabbitmq.gov_routing_key,
+                rabbitmq.reconnect
+            ),
+            TransportConfig::Kafka(kafka) => info!(
+                "kafka. brokers: {}, user: {:?}, topic: {}, gov_topic: {:?}, reconnect: {:?}",
+                kafka.connect.brokers,
+                kafka.connect.user,
+                kafka.topic,
+                kafka.gov_topic,
+                kafka.reconnect
+            ),
+        }
         info!("allowed_protocols: {}", config.allowed_protocols.join(", "));
     }
 
+    async fn backfill_impl(self, from_slot: u64, to_slot: u64) {
+        let ListenerApp {
+            solana_listener,
+            mut message_publisher,
+            solana_logs_proc,
+        } = self;
+        // Every stage is over once its input is drained and closed, which closes the next one
+        let read = async move {
+            if let Err(err) = solana_listener.backfill(from_slot, to_slot).await {
+                error!("Failed to backfill slots {}..={}: {}", from_slot, to_slot, err);
+            }
+        };
+        let process = async move { solana_logs_proc.execute().await };
+        let (_, _, published) = tokio::join!(read, process, message_publisher.publish());
+        match published {
+            Ok(()) => info!("Backfill finished, slots: {}..={}", from_slot, to_slot),
+            Err(err) => error!("Failed to publish the backfilled events: {}", err),
+        }
+    }
+
     async fn execute_impl(&mut self) {
         tokio::select! {
             _ = self.solana_listener.listen_to_solana() => {}
-            _ = self.rabbitmq_sender.publish_to_rabbitmq() => {}
+            _ = self.message_publisher.publish() => {}
             _ = self.solana_logs_proc.execute() => {}
         }
     }
@@ -0,0 +1,265 @@
+use log::{debug, info, warn};
+use mongodb::{
+    bson::{doc, Document},
+    options::{ClientOptions, Credential, ServerApi, ServerApiVersion, UpdateOptions},
+    Client, Collection,
+};
+use std::collections::VecDeque;
+
+use transmitter_common::{
+    mongodb::{mdb_solana_chain_id, MongodbConfig, MDB_LAST_BLOCK_COLLECTION},
+    utils::get_time_ms,
+};
+
+use super::error::ListenError;
+
+struct Queued {
+    id: u64,
+    /// The transaction all the messages of which are queued up to this one
+    checkpoint: Option<String>,
+    confirmed: bool,
+}
+
+/// Advances the `"from"` checkpoint the retrospective reading starts with once the broker confirms
+/// every proposal queued up to a transaction. The transactions after it are read again on restart
+/// with `need_check` set, so the checkpoint never passes an unconfirmed proposal.
+pub(super) struct CheckpointTracker {
+    mongodb_config: MongodbConfig,
+    collection: Option<Collection<Document>>,
+    persist: bool,
+    queued: VecDeque<Queued>,
+    complete: Option<String>,
+    persisted: Option<String>,
+}
+
+impl CheckpointTracker {
+    pub(super) fn new(mongodb_config: MongodbConfig) -> CheckpointTracker {
+        CheckpointTracker {
+            mongodb_config,
+            collection: None,
+            persist: true,
+            queued: VecDeque::new(),
+            complete: None,
+            persisted: None,
+        }
+    }
+
+    /// Leaves the checkpoint to the live listening, used to republish the past events
+    pub(super) fn freeze(&mut self) {
+        self.persist = false;
+    }
+
+    /// Tracks the message queued to the outbox with the id, along with the transaction it
+    /// completes if any
+    pub(super) fn on_queued(&mut self, id: u64, checkpoint: Option<String>) {
+        self.queued.push_back(Queued {
+            id,
+            checkpoint,
+            confirmed: false,
+        });
+    }
+
+    /// Takes the ids of the messages acked by the broker
+    pub(super) async fn on_acked(&mut self, ids: &[u64]) {
+        self.confirm(ids);
+        self.persist_complete().await;
+    }
+
+    /// Advances the complete checkpoint over the leading confirmed messages
+    fn confirm(&mut self, ids: &[u64]) {
+        for queued in self.queued.iter_mut() {
+            if ids.contains(&queued.id) {
+                queued.confirmed = true;
+            }
+        }
+        while let Some(queued) = self.queued.pop_front() {
+            if !queued.confirmed {
+                self.queued.push_front(queued);
+                break;
+            }
+            if let Some(tx_signature) = queued.checkpoint {
+                self.complete = Some(tx_signature);
+            }
+        }
+    }
+
+    async fn persist_complete(&mut self) {
+        if !self.persist || self.complete == self.persisted {
+            return;
+        }
+        let Some(checkpoint) = self.complete.clone() else {
+            return;
+        };
+        match self.update_checkpoint(&checkpoint).await {
+            Ok(()) => {
+                debug!("Checkpoint persisted: {}", checkpoint);
+                self.persisted = Some(checkpoint);
+            }
+            Err(err) => warn!("Failed to persist checkpoint: {}, error: {}", checkpoint, err),
+        }
+    }
+
+    async fn update_checkpoint(&mut self, checkpoint: &str) -> Result<(), ListenError> {
+        let collection = match self.collection {
+            Some(ref collection) => collection,
+            None => self.collection.insert(Self::connect(&self.mongodb_config).await?),
+        };
+        collection
+            .update_one(
+                doc! { "direction": "from", "chain": mdb_solana_chain_id() },
+                doc! {
+                    "$set": {
+                        &self.mongodb_config.key: checkpoint,
+                        "updated_at": get_time_ms() as i64
+                    }
+                },
+                UpdateOptions::builder().upsert(true).build(),
+            )
+            .await?;
+        Ok(())
+    }
+
+    async fn connect(mongodb_config: &MongodbConfig) -> Result<Collection<Document>, ListenError> {
+        info!("Connect to mongodb to persist the checkpoint: {}", mongodb_config.uri);
+        let mut client_options = ClientOptions::parse_async(&mongodb_config.uri).await?;
+        let server_api = ServerApi::builder().version(ServerApiVersion::V1).build();
+        client_options.server_api = Some(server_api);
+        client_options.credential = Some(
+            Credential::builder()
+                .username(mongodb_config.user.clone())
+                .password(mongodb_config.password.clone())
+                .build(),
+        );
+        let client = Client::with_options(client_options)?;
+        let db = client.database(&mongodb_config.db);
+        Ok(db.collection::<Document>(MDB_LAST_BLOCK_COLLECTION))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::common::{outbox::Outbox, transport::PublishConfirm};
+
+    fn tracker() -> CheckpointTracker {
+        CheckpointTracker::new(MongodbConfig {
+            user: "user".to_owned(),
+            password: "password".to_owned(),
+            uri: "mongodb://localhost:27017".to_owned(),
+            db: "db".to_owned(),
+            key: "listener".to_owned(),
+        })
+    }
+
+    /// Queues the messages with the transactions they complete, returns their ids
+    fn queue(
+        tracker: &mut CheckpointTracker,
+        outbox: &mut Outbox,
+        checkpoints: &[Option<&str>],
+    ) -> Vec<u64> {
+        let mut ids = Vec::new();
+        for checkpoint in checkpoints {
+            let id = outbox.push("key", String::new());
+            tracker.on_queued(id, checkpoint.map(str::to_owned));
+            ids.push(id);
+        }
+        ids
+    }
+
+    fn publish_all(outbox: &mut Outbox) {
+        while let Some(id) = outbox.next_queued().map(|msg| msg.id) {
+            outbox.published(id);
+        }
+    }
+
+    fn ack(delivery_tag: u64) -> PublishConfirm {
+        PublishConfirm::Ack {
+            delivery_tag,
+            multiple: false,
+        }
+    }
+
+    #[test]
+    fn test_advances_over_confirmed_prefix() {
+        let (mut tracker, mut outbox) = (tracker(), Outbox::open(None, 10).unwrap());
+        queue(&mut tracker, &mut outbox, &[Some("tx1"), Some("tx2"), Some("tx3")]);
+        publish_all(&mut outbox);
+
+        // The later confirms wait for the earlier ones
+        tracker.confirm(&outbox.confirm(ack(2)));
+        assert_eq!(tracker.complete, None);
+        tracker.confirm(&outbox.confirm(ack(1)));
+        assert_eq!(tracker.complete.as_deref(), Some("tx2"));
+        tracker.confirm(&outbox.confirm(ack(3)));
+        assert_eq!(tracker.complete.as_deref(), Some("tx3"));
+        assert!(tracker.queued.is_empty());
+    }
+
+    #[test]
+    fn test_nacked_holds_checkpoint() {
+        let (mut tracker, mut outbox) = (tracker(), Outbox::open(None, 10).unwrap());
+        let ids = queue(&mut tracker, &mut outbox, &[Some("tx1"), Some("tx2")]);
+        publish_all(&mut outbox);
+
+        let nack = PublishConfirm::Nack {
+            delivery_tag: 1,
+            multiple: false,
+        };
+        tracker.confirm(&outbox.confirm(nack));
+        tracker.confirm(&outbox.confirm(ack(2)));
+        assert_eq!(tracker.complete, None);
+
+        // Published again once the retry delay is over
+        while outbox.next_queued().is_none() {
+            std::thread::sleep(outbox.retry_in().unwrap());
+        }
+        publish_all(&mut outbox);
+        assert_eq!(outbox.confirm(ack(3)), vec![ids[0]]);
+        tracker.confirm(&[ids[0]]);
+        assert_eq!(tracker.complete.as_deref(), Some("tx2"));
+    }
+
+    #[test]
+    fn test_multi_proposal_tx_complete_with_last() {
+        let (mut tracker, mut outbox) = (tracker(), Outbox::open(None, 10).unwrap());
+        // Only the last proposal of the transaction completes it
+        let ids = queue(&mut tracker, &mut outbox, &[None, None, Some("tx1"), None]);
+        publish_all(&mut outbox);
+
+        tracker.confirm(&outbox.confirm(ack(1)));
+        tracker.confirm(&outbox.confirm(ack(2)));
+        assert_eq!(tracker.complete, None);
+        tracker.confirm(&outbox.confirm(ack(3)));
+        assert_eq!(tracker.complete.as_deref(), Some("tx1"));
+        assert_eq!(tracker.queued.iter().map(|queued| queued.id).collect::<Vec<_>>(), vec![ids[3]]);
+    }
+
+    #[test]
+    fn test_channel_reset_republishes() {
+        let (mut tracker, mut outbox) = (tracker(), Outbox::open(None, 10).unwrap());
+        queue(&mut tracker, &mut outbox, &[Some("tx1"), Some("tx2")]);
+        publish_all(&mut outbox);
+
+        // The confirms of the closed channel never come, the messages keep their ids
+        outbox.reset_channel();
+        publish_all(&mut outbox);
+        tracker.confirm(&outbox.confirm(ack(1)));
+        assert_eq!(tracker.complete.as_deref(), Some("tx1"));
+        tracker.confirm(&outbox.confirm(ack(2)));
+        assert_eq!(tracker.complete.as_deref(), Some("tx2"));
+    }
+
+    #[tokio::test]
+    async fn test_frozen_not_persisted() {
+        let (mut tracker, mut outbox) = (tracker(), Outbox::open(None, 10).unwrap());
+        tracker.freeze();
+        queue(&mut tracker, &mut outbox, &[Some("tx1")]);
+        publish_all(&mut outbox);
+
+        // No database is reached while frozen
+        tracker.on_acked(&outbox.confirm(ack(1))).await;
+        assert_eq!(tracker.complete.as_deref(), Some("tx1"));
+        assert_eq!(tracker.persisted, None);
+        assert!(tracker.collection.is_none());
+    }
+}
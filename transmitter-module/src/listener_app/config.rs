@@ -5,11 +5,12 @@ use serde::Deserialize;
 use transmitter_common::mongodb::MongodbConfig;
 
 use super::error::ListenError;
-use crate::common::{config::SolanaListenerConfig, rabbitmq::RabbitmqPublishConfig};
+use crate::common::{config::SolanaListenerConfig, transport::PublishTransportConfig};
 
 #[derive(Deserialize)]
 pub(super) struct ListenConfig {
-    pub(super) rabbitmq: RabbitmqPublishConfig,
+    #[serde(flatten)]
+    pub(super) transport: PublishTransportConfig,
     pub(super) solana: SolanaListenerConfig,
     pub(super) mongodb: MongodbConfig,
     pub(super) allowed_protocols: Vec<String>,
@@ -33,3 +34,60 @@ impl ListenConfig {
         })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::common::transport::TransportConfig;
+    use config::FileFormat;
+
+    /// The rabbitmq credentials are expected to be set by the envs
+    const RABBITMQ: &str = "rabbitmq: { user: guest, password: guest }";
+    const KAFKA: &str = "
+transport: kafka
+kafka: { brokers: localhost:9092, topic: entangle.from_solana, outbox_capacity: 100 }
+";
+
+    fn read(overrides: &str) -> Result<ListenConfig, config::ConfigError> {
+        Config::builder()
+            .add_source(File::with_name("doc/listener-config.yml"))
+            .add_source(File::from_str(overrides, FileFormat::Yaml))
+            .build()?
+            .try_deserialize()
+    }
+
+    #[test]
+    fn test_rabbitmq_transport() {
+        let config = read(RABBITMQ).unwrap();
+        let TransportConfig::Rabbitmq(rabbitmq) = &config.transport else {
+            panic!("Expected rabbitmq transport");
+        };
+        assert_eq!(rabbitmq.connect.user, "guest");
+        let routes = config.transport.routes();
+        assert_eq!(routes.route, "from_solana");
+        assert_eq!(routes.gov_route.as_deref(), Some("gov_from_solana"));
+        assert_eq!(routes.outbox_capacity, 10000);
+    }
+
+    #[test]
+    fn test_kafka_transport() {
+        // The rabbitmq section lacking the credentials is not taken into account
+        let config = read(KAFKA).unwrap();
+        let TransportConfig::Kafka(kafka) = &config.transport else {
+            panic!("Expected kafka transport");
+        };
+        assert_eq!(kafka.connect.brokers, "localhost:9092");
+        assert_eq!(kafka.reconnect.attempts, 20);
+        let routes = config.transport.routes();
+        assert_eq!(routes.route, "entangle.from_solana");
+        assert_eq!(routes.gov_route, None);
+        assert_eq!(routes.outbox_path, None);
+        assert_eq!(routes.outbox_capacity, 100);
+    }
+
+    #[test]
+    fn test_missing_transport_section_rejected() {
+        assert!(read("transport: kafka").is_err());
+        assert!(read("transport: nats").is_err());
+    }
+}
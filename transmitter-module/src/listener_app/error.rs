@@ -1,11 +1,23 @@
 use thiserror::Error;
 
+use crate::common::transport::TransportError;
+
 #[derive(Debug, Error)]
 pub(crate) enum ListenError {
     #[error("Config error")]
     Config,
-    #[error("Rabbitmq client error")]
-    Rabbitmq(#[from] amqprs::error::Error),
+    #[error("Message transport error {0}")]
+    Transport(#[from] TransportError),
     #[error("Mongodb client error")]
     Mongodb(#[from] mongodb::error::Error),
+    #[error("Solana transactor error {0}")]
+    SolanaTransactor(Box<solana_transactor::TransactorError>),
+    #[error("Outbox error {0}")]
+    Outbox(#[from] std::io::Error),
+}
+
+impl From<solana_transactor::TransactorError> for ListenError {
+    fn from(err: solana_transactor::TransactorError) -> Self {
+        ListenError::SolanaTransactor(Box::new(err))
+    }
 }
@@ -0,0 +1,406 @@
+use log::{debug, error, warn};
+use solana_sdk::{
+    clock::MAX_PROCESSING_AGE, commitment_config::CommitmentConfig, signature::Signature,
+};
+use solana_transaction_status::{TransactionConfirmationStatus, TransactionStatus};
+use solana_transactor::RpcPool;
+use std::{collections::VecDeque, str::FromStr, time::Duration};
+use tokio::time::{interval, Interval, MissedTickBehavior};
+
+use transmitter_common::data::TxFinality;
+
+use super::{error::ListenError, message_publisher::ListenerMsg};
+use crate::common::config::{FinalityMode, SolanaListenerConfig};
+
+/// The most signatures the `getSignatureStatuses` method accepts at once
+const MAX_SIGNATURE_STATUSES: usize = 256;
+
+/// What is published once the transaction finality is known
+enum Held {
+    /// The message is published if the transaction is finalized, dropped otherwise
+    Msg(Box<ListenerMsg>),
+    /// The tentative proposals of the transaction are finalized or retracted
+    Proposals,
+}
+
+struct Pending {
+    signature: Signature,
+    slot: u64,
+    held: Held,
+    /// The finalized block height the transaction was first found missing at
+    missing_since: Option<u64>,
+}
+
+/// Holds the listener messages back until the finality of their transactions is known, as the
+/// finality mode requires.
+///
+/// The state is kept in memory only. The messages are released in the order they have been read,
+/// so the checkpoint never passes a pending transaction, and the pending transactions are read
+/// again on restart with `need_check` set and tracked anew.
+pub(super) struct FinalityTracker {
+    mode: FinalityMode,
+    interval: Interval,
+    rpc_pool: RpcPool,
+    pending: VecDeque<Pending>,
+    ready: VecDeque<ListenerMsg>,
+}
+
+impl FinalityTracker {
+    pub(super) fn new(config: &SolanaListenerConfig) -> Result<FinalityTracker, ListenError> {
+        let mut interval = interval(Duration::from_millis(config.finality_check_interval_ms));
+        interval.set_missed_tick_behavior(MissedTickBehavior::Delay);
+        Ok(FinalityTracker {
+            mode: config.finality,
+            interval,
+            rpc_pool: RpcPool::new(&config.client.read_rpcs, &config.client.write_rpcs)?,
+            pending: VecDeque::new(),
+            ready: VecDeque::new(),
+        })
+    }
+
+    /// Returns the message to be published right away, the others are held
+    pub(super) fn admit(&mut self, msg: ListenerMsg) -> Option<ListenerMsg> {
+        if self.mode == FinalityMode::Immediate {
+            return Some(msg);
+        }
+        let Some((signature, slot)) = Self::origin(&msg) else {
+            return Some(msg);
+        };
+        match (self.mode, msg) {
+            (FinalityMode::TwoPhase, ListenerMsg::Propose(propose)) => {
+                // A single resolution covers all the proposals of the transaction
+                let last = self.pending.back();
+                if !last.is_some_and(|last| {
+                    last.signature == signature && matches!(last.held, Held::Proposals)
+                }) {
+                    self.pending.push_back(Pending {
+                        signature,
+                        slot,
+                        held: Held::Proposals,
+                        missing_since: None,
+                    });
+                }
+                Some(ListenerMsg::TentativePropose(propose))
+            }
+            (_, msg) => {
+                self.pending.push_back(Pending {
+                    signature,
+                    slot,
+                    held: Held::Msg(Box::new(msg)),
+                    missing_since: None,
+                });
+                None
+            }
+        }
+    }
+
+    pub(super) fn pop_ready(&mut self) -> Option<ListenerMsg> {
+        self.ready.pop_front()
+    }
+
+    pub(super) fn is_idle(&self) -> bool {
+        self.pending.is_empty() && self.ready.is_empty()
+    }
+
+    /// Waits for the next check, cancel safe so the interval is kept across the select loop
+    pub(super) async fn tick(&mut self) {
+        self.interval.tick().await;
+    }
+
+    /// Resolves the finality of the pending transactions, in the order they have been read
+    pub(super) async fn check(&mut self) {
+        let finalized = CommitmentConfig::finalized();
+        let finalized_slot = self
+            .rpc_pool
+            .with_read_rpc_loop(
+                |rpc| async move { rpc.get_slot_with_commitment(finalized).await },
+                finalized,
+            )
+            .await;
+        let block_height = self
+            .rpc_pool
+            .with_read_rpc_loop(
+                |rpc| async move { rpc.get_block_height_with_commitment(finalized).await },
+                finalized,
+            )
+            .await;
+        let signatures: Vec<Signature> = self
+            .pending
+            .iter()
+            .take(MAX_SIGNATURE_STATUSES)
+            .map(|pending| pending.signature)
+            .collect();
+        let signatures = &signatures;
+        let statuses = self
+            .rpc_pool
+            .with_read_rpc_loop(
+                |rpc| async move { rpc.get_signature_statuses_with_history(signatures).await },
+                finalized,
+            )
+            .await
+            .value;
+        self.on_statuses(finalized_slot, block_height, statuses);
+    }
+
+    /// Takes the statuses of the leading pending transactions at the finalized slot and block
+    /// height, stops at the first one not resolved yet
+    fn on_statuses(
+        &mut self,
+        finalized_slot: u64,
+        block_height: u64,
+        statuses: Vec<Option<TransactionStatus>>,
+    ) {
+        for status in statuses {
+            let Some(pending) = self.pending.front_mut() else {
+                break;
+            };
+            let slot = match status {
+                Some(status)
+                    if status.err.is_none()
+                        && status.confirmation_status
+                            == Some(TransactionConfirmationStatus::Finalized) =>
+                {
+                    Some(status.slot)
+                }
+                Some(status) if status.err.is_some() => None,
+                // A transaction dropped with its fork may be included again in a later slot while
+                // its blockhash is valid, which is no longer than the processing age past the
+                // height it is found missing at
+                None if pending.slot <= finalized_slot => {
+                    let missing_since = *pending.missing_since.get_or_insert(block_height);
+                    if block_height <= missing_since + MAX_PROCESSING_AGE as u64 {
+                        break;
+                    }
+                    None
+                }
+                _ => break,
+            };
+            let pending = self.pending.pop_front().expect("Expected pending transaction");
+            self.resolve(pending, slot);
+        }
+    }
+
+    fn resolve(&mut self, pending: Pending, finalized_slot: Option<u64>) {
+        let tx_signature = pending.signature.to_string();
+        match (pending.held, finalized_slot) {
+            (Held::Msg(msg), Some(_)) => self.ready.push_back(*msg),
+            (Held::Msg(_), None) => {
+                warn!("Transaction has not been finalized, its events dropped: {}", tx_signature)
+            }
+            (Held::Proposals, Some(slot)) => {
+                debug!("Tentative proposals finalized: {}, slot: {}", tx_signature, slot);
+                self.ready
+                    .push_back(ListenerMsg::ProposalsFinalized(TxFinality { tx_signature, slot }))
+            }
+            (Held::Proposals, None) => {
+                warn!("Tentative proposals retracted: {}, slot: {}", tx_signature, pending.slot);
+                self.ready.push_back(ListenerMsg::ProposalsRetracted(TxFinality {
+                    tx_signature,
+                    slot: pending.slot,
+                }))
+            }
+        }
+    }
+
+    /// The transaction the message has been read from
+    fn origin(msg: &ListenerMsg) -> Option<(Signature, u64)> {
+        let (signature, slot) = match msg {
+            ListenerMsg::Propose(propose) | ListenerMsg::TentativePropose(propose) => {
+                let signature = Signature::try_from(propose.operation_data.src_op_tx_id.as_slice())
+                    .map_err(|err| error!("Malformed proposal tx id: {}", err))
+                    .ok()?;
+                return Some((signature, propose.operation_data.src_block_number));
+            }
+            ListenerMsg::GovChange(gov_change) => (&gov_change.tx_signature, gov_change.slot),
+            ListenerMsg::ProposalsFinalized(_) | ListenerMsg::ProposalsRetracted(_) => return None,
+        };
+        Signature::from_str(signature)
+            .map_err(|err| error!("Malformed tx signature: {}, error: {}", signature, err))
+            .ok()
+            .map(|signature| (signature, slot))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use solana_sdk::transaction::TransactionError;
+    use solana_transactor::RpcEntry;
+    use transmitter_common::data::{OperationData, Propose};
+
+    use crate::common::config::SolanaClientConfig;
+
+    fn tracker(mode: FinalityMode) -> FinalityTracker {
+        let rpcs = vec![RpcEntry {
+            url: "http://localhost:8899".to_owned(),
+            ratelimit: 10,
+        }];
+        FinalityTracker::new(&SolanaListenerConfig {
+            client: SolanaClientConfig {
+                commitment: CommitmentConfig::confirmed(),
+                read_rpcs: rpcs.clone(),
+                write_rpcs: rpcs,
+            },
+            tx_read_from: None,
+            finality: mode,
+            finality_check_interval_ms: 1000,
+        })
+        .expect("Expected tracker to be created")
+    }
+
+    fn propose(signature: Signature, slot: u64) -> ListenerMsg {
+        ListenerMsg::Propose(Propose {
+            operation_data: OperationData {
+                src_op_tx_id: signature.as_ref().to_vec(),
+                src_block_number: slot,
+                ..Default::default()
+            },
+            ..Default::default()
+        })
+    }
+
+    fn status(
+        slot: u64,
+        confirmation_status: TransactionConfirmationStatus,
+        err: Option<TransactionError>,
+    ) -> Option<TransactionStatus> {
+        Some(TransactionStatus {
+            slot,
+            confirmations: None,
+            status: err.clone().map_or(Ok(()), Err),
+            err,
+            confirmation_status: Some(confirmation_status),
+        })
+    }
+
+    fn finalized(slot: u64) -> Option<TransactionStatus> {
+        status(slot, TransactionConfirmationStatus::Finalized, None)
+    }
+
+    fn confirmed(slot: u64) -> Option<TransactionStatus> {
+        status(slot, TransactionConfirmationStatus::Confirmed, None)
+    }
+
+    /// The released messages with the transactions and slots they refer to
+    fn drain_ready(tracker: &mut FinalityTracker) -> Vec<(&'static str, String, u64)> {
+        let mut ready = Vec::new();
+        while let Some(msg) = tracker.pop_ready() {
+            ready.push(match msg {
+                ListenerMsg::Propose(propose) => (
+                    "propose",
+                    Signature::try_from(propose.operation_data.src_op_tx_id.as_slice())
+                        .unwrap()
+                        .to_string(),
+                    propose.operation_data.src_block_number,
+                ),
+                ListenerMsg::ProposalsFinalized(finality) => {
+                    ("finalized", finality.tx_signature, finality.slot)
+                }
+                ListenerMsg::ProposalsRetracted(finality) => {
+                    ("retracted", finality.tx_signature, finality.slot)
+                }
+                _ => panic!("Unexpected message released"),
+            });
+        }
+        ready
+    }
+
+    #[tokio::test]
+    async fn test_immediate() {
+        let mut tracker = tracker(FinalityMode::Immediate);
+        assert!(matches!(
+            tracker.admit(propose(Signature::new_unique(), 5)),
+            Some(ListenerMsg::Propose(_))
+        ));
+        assert!(tracker.is_idle());
+    }
+
+    #[tokio::test]
+    async fn test_two_phase_resolves_tx_once() {
+        let mut tracker = tracker(FinalityMode::TwoPhase);
+        let (first, second) = (Signature::new_unique(), Signature::new_unique());
+        for msg in [propose(first, 5), propose(first, 5), propose(second, 6)] {
+            assert!(matches!(tracker.admit(msg), Some(ListenerMsg::TentativePropose(_))));
+        }
+        assert_eq!(tracker.pending.len(), 2);
+
+        tracker.on_statuses(10, 100, vec![finalized(5), confirmed(6)]);
+        assert_eq!(drain_ready(&mut tracker), vec![("finalized", first.to_string(), 5)]);
+        assert_eq!(tracker.pending.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_delayed_released_in_order() {
+        let mut tracker = tracker(FinalityMode::Delayed);
+        let (first, second) = (Signature::new_unique(), Signature::new_unique());
+        assert!(tracker.admit(propose(first, 5)).is_none());
+        assert!(tracker.admit(propose(second, 6)).is_none());
+
+        // The later transaction waits for the earlier one not to let the checkpoint pass it
+        tracker.on_statuses(10, 100, vec![confirmed(5), finalized(6)]);
+        assert!(drain_ready(&mut tracker).is_empty());
+
+        tracker.on_statuses(10, 100, vec![finalized(5), finalized(6)]);
+        assert_eq!(
+            drain_ready(&mut tracker),
+            vec![
+                ("propose", first.to_string(), 5),
+                ("propose", second.to_string(), 6)
+            ]
+        );
+        assert!(tracker.is_idle());
+    }
+
+    #[tokio::test]
+    async fn test_missing_tx_retracted_once_blockhash_expired() {
+        let mut tracker = tracker(FinalityMode::TwoPhase);
+        let signature = Signature::new_unique();
+        tracker.admit(propose(signature, 5));
+
+        // Not finalized yet
+        tracker.on_statuses(4, 100, vec![None]);
+        assert!(drain_ready(&mut tracker).is_empty());
+        // Dropped with its fork, but may still be included again
+        tracker.on_statuses(10, 100, vec![None]);
+        tracker.on_statuses(200, 100 + MAX_PROCESSING_AGE as u64, vec![None]);
+        assert!(drain_ready(&mut tracker).is_empty());
+
+        tracker.on_statuses(201, 101 + MAX_PROCESSING_AGE as u64, vec![None]);
+        assert_eq!(drain_ready(&mut tracker), vec![("retracted", signature.to_string(), 5)]);
+    }
+
+    #[tokio::test]
+    async fn test_missing_tx_included_again() {
+        let mut tracker = tracker(FinalityMode::TwoPhase);
+        let signature = Signature::new_unique();
+        tracker.admit(propose(signature, 5));
+
+        tracker.on_statuses(10, 100, vec![None]);
+        tracker.on_statuses(20, 110, vec![finalized(12)]);
+        assert_eq!(drain_ready(&mut tracker), vec![("finalized", signature.to_string(), 12)]);
+    }
+
+    #[tokio::test]
+    async fn test_failed_tx() {
+        let failed = || {
+            status(
+                5,
+                TransactionConfirmationStatus::Finalized,
+                Some(TransactionError::AccountInUse),
+            )
+        };
+
+        let mut two_phase = tracker(FinalityMode::TwoPhase);
+        let signature = Signature::new_unique();
+        two_phase.admit(propose(signature, 5));
+        two_phase.on_statuses(10, 100, vec![failed()]);
+        assert_eq!(drain_ready(&mut two_phase), vec![("retracted", signature.to_string(), 5)]);
+
+        // The held messages are dropped
+        let mut delayed = tracker(FinalityMode::Delayed);
+        delayed.admit(propose(signature, 5));
+        delayed.on_statuses(10, 100, vec![failed()]);
+        assert!(drain_ready(&mut delayed).is_empty());
+        assert!(delayed.is_idle());
+    }
+}
@@ -0,0 +1,113 @@
+use log::{debug, error};
+use tokio::sync::mpsc::UnboundedSender;
+
+use crate::common::solana_logs::photon_events::PhotonEvent;
+use transmitter_common::data::{GovChange, GovChangeImpl};
+
+/// Intercepts the registry changes made by gov to be forwarded to off-chain indexers
+pub(super) struct GovEventProcessor {
+    gov_change_sender: UnboundedSender<GovChange>,
+}
+
+impl GovEventProcessor {
+    pub(super) fn new(gov_change_sender: UnboundedSender<GovChange>) -> GovEventProcessor {
+        GovEventProcessor { gov_change_sender }
+    }
+
+    /// Forwards the event if it is a registry change, the other events are ignored
+    pub(super) fn on_event(
+        &self,
+        event: PhotonEvent,
+        signature: &str,
+        slot: u64,
+        need_check: bool,
+    ) {
+        let change = match event {
+            PhotonEvent::ProtocolAddressChanged(e) => GovChangeImpl::ProtocolAddressChanged {
+                protocol_id: e.protocol_id,
+                old_address: e.old_address,
+                new_address: e.new_address,
+            },
+            PhotonEvent::ProposerAdded(e) => GovChangeImpl::ProposerAdded {
+                protocol_id: e.protocol_id,
+                proposer: e.proposer,
+            },
+            PhotonEvent::ProposerRemoved(e) => GovChangeImpl::ProposerRemoved {
+                protocol_id: e.protocol_id,
+                proposer: e.proposer,
+            },
+            PhotonEvent::ExecutorAdded(e) => GovChangeImpl::ExecutorAdded {
+                protocol_id: e.protocol_id,
+                executor: e.executor,
+            },
+            PhotonEvent::ExecutorRemoved(e) => GovChangeImpl::ExecutorRemoved {
+                protocol_id: e.protocol_id,
+                executor: e.executor,
+            },
+            PhotonEvent::TransmittersUpdated(e) => GovChangeImpl::TransmittersUpdated {
+                protocol_id: e.protocol_id,
+                old_transmitters: e.old_transmitters,
+                new_transmitters: e.new_transmitters,
+            },
+            PhotonEvent::TransmitterWeightsUpdated(e) => GovChangeImpl::TransmitterWeightsUpdated {
+                protocol_id: e.protocol_id,
+                transmitters: e.transmitters,
+                old_weights: e.old_weights,
+                new_weights: e.new_weights,
+            },
+            PhotonEvent::ConsensusRateChanged(e) => GovChangeImpl::ConsensusRateChanged {
+                protocol_id: e.protocol_id,
+                old_rate: e.old_rate,
+                new_rate: e.new_rate,
+            },
+            PhotonEvent::GuardianChanged(e) => GovChangeImpl::GuardianChanged {
+                old_guardian: e.old_guardian,
+                new_guardian: e.new_guardian,
+            },
+            PhotonEvent::GovTimelockChanged(e) => GovChangeImpl::GovTimelockChanged {
+                old_timelock: e.old_timelock,
+                new_timelock: e.new_timelock,
+            },
+            PhotonEvent::ProtocolRemoved(e) => GovChangeImpl::ProtocolRemoved {
+                protocol_id: e.protocol_id,
+                rent_recipient: e.rent_recipient,
+            },
+            PhotonEvent::AllowedDstChainsUpdated(e) => GovChangeImpl::AllowedDstChainsUpdated {
+                protocol_id: e.protocol_id,
+                old_dst_chains: e.old_dst_chains,
+                new_dst_chains: e.new_dst_chains,
+            },
+            PhotonEvent::ProposeRateLimitChanged(e) => GovChangeImpl::ProposeRateLimitChanged {
+                protocol_id: e.protocol_id,
+                old_max_proposals: e.old_max_proposals,
+                old_window: e.old_window,
+                new_max_proposals: e.new_max_proposals,
+                new_window: e.new_window,
+            },
+            PhotonEvent::ProposeFeeChanged(e) => GovChangeImpl::ProposeFeeChanged {
+                protocol_id: e.protocol_id,
+                old_fee: e.old_fee,
+                old_mint: e.old_mint,
+                new_fee: e.new_fee,
+                new_mint: e.new_mint,
+            },
+            PhotonEvent::Paused(e) => GovChangeImpl::Paused {
+                protocol_id: e.protocol_id,
+            },
+            PhotonEvent::Unpaused(e) => GovChangeImpl::Unpaused {
+                protocol_id: e.protocol_id,
+            },
+            _ => return,
+        };
+
+        debug!("Gov change intercepted: {:?}", change);
+        if let Err(err) = self.gov_change_sender.send(GovChange {
+            need_check,
+            tx_signature: signature.to_string(),
+            slot,
+            change,
+        }) {
+            error!("Failed to send gov change through the channel: {}", err);
+        }
+    }
+}
@@ -0,0 +1,193 @@
+use log::{debug, error, info};
+use tokio::{select, sync::mpsc::UnboundedReceiver, time::sleep};
+
+use transmitter_common::data::{
+    GovChange, Propose, TransmitterMsg, TransmitterMsgImpl, TxFinality,
+};
+
+use super::{checkpoint::CheckpointTracker, error::ListenError, finality_tracker::FinalityTracker};
+use crate::common::{
+    outbox::Outbox,
+    transport::{PublishEvent, PublishRoutes, Publisher},
+};
+
+pub(super) enum ListenerMsg {
+    Propose(Propose),
+    TentativePropose(Propose),
+    ProposalsFinalized(TxFinality),
+    ProposalsRetracted(TxFinality),
+    GovChange(GovChange),
+}
+
+pub(super) struct MessagePublisher {
+    publisher: Box<dyn Publisher>,
+    route: String,
+    gov_route: Option<String>,
+    propose_receiver: UnboundedReceiver<Propose>,
+    gov_change_receiver: Option<UnboundedReceiver<GovChange>>,
+    inputs_closed: bool,
+    outbox: Outbox,
+    finality_tracker: FinalityTracker,
+    checkpoint_tracker: CheckpointTracker,
+}
+
+impl MessagePublisher {
+    pub(super) fn new(
+        publisher: Box<dyn Publisher>,
+        routes: PublishRoutes,
+        propose_receiver: UnboundedReceiver<Propose>,
+        gov_change_receiver: Option<UnboundedReceiver<GovChange>>,
+        outbox: Outbox,
+        finality_tracker: FinalityTracker,
+        checkpoint_tracker: CheckpointTracker,
+    ) -> MessagePublisher {
+        MessagePublisher {
+            publisher,
+            route: routes.route,
+            gov_route: routes.gov_route,
+            propose_receiver,
+            gov_change_receiver,
+            inputs_closed: false,
+            outbox,
+            finality_tracker,
+            checkpoint_tracker,
+        }
+    }
+
+    pub(super) async fn publish(&mut self) -> Result<(), ListenError> {
+        self.publisher.open().await?;
+        loop {
+            self.publisher.publish(&mut self.outbox).await;
+            if self.inputs_closed && self.finality_tracker.is_idle() && self.outbox.is_empty() {
+                return Ok(());
+            }
+            self.progress().await?;
+        }
+    }
+
+    fn enqueue_msg(&mut self, msg: ListenerMsg) {
+        let (transmitter_msg, route) = match &msg {
+            ListenerMsg::Propose(propose) => (
+                TransmitterMsg::new(
+                    TransmitterMsgImpl::Propose(propose.clone()),
+                    propose.need_check,
+                ),
+                &self.route,
+            ),
+            ListenerMsg::TentativePropose(propose) => (
+                TransmitterMsg::new(
+                    TransmitterMsgImpl::TentativePropose(propose.clone()),
+                    propose.need_check,
+                ),
+                &self.route,
+            ),
+            ListenerMsg::ProposalsFinalized(finality) => (
+                TransmitterMsg::new(
+                    TransmitterMsgImpl::ProposalsFinalized(finality.clone()),
+                    false,
+                ),
+                &self.route,
+            ),
+            ListenerMsg::ProposalsRetracted(finality) => (
+                TransmitterMsg::new(
+                    TransmitterMsgImpl::ProposalsRetracted(finality.clone()),
+                    false,
+                ),
+                &self.route,
+            ),
+            ListenerMsg::GovChange(gov_change) => (
+                TransmitterMsg::new(
+                    TransmitterMsgImpl::GovChange(gov_change.clone()),
+                    gov_change.need_check,
+                ),
+                self.gov_route
+                    .as_ref()
+                    .expect("Expected gov changes to be received only with gov_route set"),
+            ),
+        };
+        debug!("message to be sent: {:?}", transmitter_msg);
+        let Ok(json_data) = serde_json::to_string(&transmitter_msg).map_err(|err| {
+            error!(
+                "Failed to encode operation_data message: {:?}, error: {}",
+                transmitter_msg, err
+            );
+        }) else {
+            return;
+        };
+        let id = self.outbox.push(route, json_data);
+        self.checkpoint_tracker.on_queued(id, Self::checkpoint(&msg));
+    }
+
+    /// Keeps the checkpoint where it is while the past events are republished
+    pub(super) fn freeze_checkpoint(&mut self) {
+        self.checkpoint_tracker.freeze();
+    }
+
+    /// The transaction the message completes, all its messages are queued up to this one
+    fn checkpoint(msg: &ListenerMsg) -> Option<String> {
+        match msg {
+            // The earlier proposals of the transaction do not let the checkpoint pass it
+            ListenerMsg::Propose(propose) if propose.last_in_tx => propose.latest_block_id.clone(),
+            ListenerMsg::Propose(_) => None,
+            ListenerMsg::ProposalsFinalized(finality) => Some(finality.tx_signature.clone()),
+            // Tentative proposals are followed by their finalization, which passes the checkpoint
+            ListenerMsg::TentativePropose(_)
+            | ListenerMsg::ProposalsRetracted(_)
+            | ListenerMsg::GovChange(_) => None,
+        }
+    }
+
+    /// Waits for a message to be queued, a message bus event or a message to be retried. The
+    /// messages held by the finality tracker are queued once resolved, and no more messages are
+    /// taken while the outbox is full
+    async fn progress(&mut self) -> Result<(), ListenError> {
+        let retry_in = self.outbox.retry_in();
+        let recv = Self::recv_msg(&mut self.propose_receiver, self.gov_change_receiver.as_mut());
+        select! {
+            msg = recv, if !self.inputs_closed && !self.outbox.is_full() => {
+                match msg {
+                    Some(msg) => {
+                        if let Some(msg) = self.finality_tracker.admit(msg) {
+                            self.enqueue_msg(msg);
+                        }
+                    }
+                    None => self.inputs_closed = true,
+                }
+            },
+            _ = self.finality_tracker.tick(), if !self.finality_tracker.is_idle() => {
+                self.finality_tracker.check().await;
+            }
+            event = self.publisher.next_event() => match event {
+                PublishEvent::Confirm(confirm) => {
+                    let acked = self.outbox.confirm(confirm);
+                    self.checkpoint_tracker.on_acked(&acked).await;
+                }
+                PublishEvent::Resumed => info!("Publishing resumed"),
+                PublishEvent::Closed => {
+                    self.publisher.open().await?;
+                    self.outbox.reset_channel();
+                }
+            },
+            _ = sleep(retry_in.unwrap_or_default()), if retry_in.is_some() => {}
+        }
+        while let Some(msg) = self.finality_tracker.pop_ready() {
+            self.enqueue_msg(msg);
+        }
+        Ok(())
+    }
+
+    async fn recv_msg(
+        propose_receiver: &mut UnboundedReceiver<Propose>,
+        gov_change_receiver: Option<&mut UnboundedReceiver<GovChange>>,
+    ) -> Option<ListenerMsg> {
+        let Some(gov_change_receiver) = gov_change_receiver else {
+            return propose_receiver.recv().await.map(ListenerMsg::Propose);
+        };
+        // Both channels are drained before the publishing is over
+        select! {
+            Some(propose) = propose_receiver.recv() => Some(ListenerMsg::Propose(propose)),
+            Some(change) = gov_change_receiver.recv() => Some(ListenerMsg::GovChange(change)),
+            else => None,
+        }
+    }
+}
@@ -1,7 +1,10 @@
 mod app;
+mod checkpoint;
 mod config;
 mod error;
-mod rabbitmq_publisher;
+mod finality_tracker;
+mod gov_event_processor;
+mod message_publisher;
 mod solana_logs_processor;
 
 pub(super) use app::ListenerApp;
@@ -1,14 +1,17 @@
 use log::{debug, error, info};
 use photon::ProposeEvent;
 use solana_sdk::signature::Signature;
-use std::str::FromStr;
+use std::{str::FromStr, sync::Mutex as SyncMutex};
 use tokio::sync::{
     mpsc::{UnboundedReceiver, UnboundedSender},
     Mutex,
 };
 
+use super::gov_event_processor::GovEventProcessor;
 use crate::common::solana_logs::{
-    event_processor::EventProcessor, solana_event_listener::LogsBunch,
+    event_processor::EventProcessor,
+    photon_events::{ParsedEvent, PhotonEvent},
+    solana_event_listener::LogsBunch,
 };
 use transmitter_common::{
     data::{default_meta, OperationData, Propose, ProtocolId},
@@ -19,6 +22,11 @@ pub(super) struct ProposalEventProcessor {
     logs_receiver: Mutex<UnboundedReceiver<LogsBunch>>,
     propose_sender: UnboundedSender<Propose>,
     allowed_protocols: Vec<Vec<u8>>,
+    gov_event_proc: Option<GovEventProcessor>,
+    /// The republished proposals carry no latest block, so the consumers keep their checkpoint
+    republish: bool,
+    /// The last proposal of the transaction is held until its end to be marked as such
+    held_propose: SyncMutex<Option<Propose>>,
 }
 
 impl ProposalEventProcessor {
@@ -26,6 +34,7 @@ impl ProposalEventProcessor {
         logs_receiver: UnboundedReceiver<LogsBunch>,
         propose_sender: UnboundedSender<Propose>,
         allowed_protocols: Vec<String>,
+        gov_event_proc: Option<GovEventProcessor>,
     ) -> ProposalEventProcessor {
         info!("Allowed protocols to listen events: {}", allowed_protocols.join(", "));
 
@@ -45,20 +54,24 @@ impl ProposalEventProcessor {
             logs_receiver: Mutex::new(logs_receiver),
             propose_sender,
             allowed_protocols,
+            gov_event_proc,
+            republish: false,
+            held_propose: SyncMutex::new(None),
         }
     }
 
+    /// Keeps the consumers from moving the checkpoint while the past events are republished
+    pub(super) fn republish(&mut self) {
+        self.republish = true;
+    }
+
     pub(super) async fn execute(&self) {
         while let Some(logs_bunch) = self.logs_receiver.lock().await.recv().await {
             self.on_logs(logs_bunch);
         }
     }
-}
-
-impl EventProcessor for ProposalEventProcessor {
-    type Event = ProposeEvent;
 
-    fn on_event(&self, event: Self::Event, signature: &str, slot: u64, need_check: bool) {
+    fn on_propose(&self, event: ProposeEvent, signature: &str, slot: u64, need_check: bool) {
         if !self.allowed_protocols.contains(&event.protocol_id) {
             return;
         }
@@ -74,9 +87,10 @@ impl EventProcessor for ProposalEventProcessor {
             error!("Failed to parse tx_signature from: {}", signature);
             return;
         };
-        if let Err(err) = self.propose_sender.send(Propose {
+        let propose = Propose {
             need_check,
-            latest_block_id: signature.to_string(),
+            last_in_tx: false,
+            latest_block_id: (!self.republish).then(|| signature.to_string()),
             operation_data: OperationData {
                 src_chain_id: SOLANA_CHAIN_ID,
                 meta: default_meta(),
@@ -90,8 +104,110 @@ impl EventProcessor for ProposalEventProcessor {
                 params: event.params,
                 reserved: <Vec<u8>>::default(),
             },
-        }) {
+            fee: event.fee,
+            fee_mint: event.fee_mint,
+        };
+        let held = self.held_propose.lock().expect("Expected held proposal lock").replace(propose);
+        if let Some(held) = held {
+            self.send_propose(held);
+        }
+    }
+
+    fn send_propose(&self, propose: Propose) {
+        if let Err(err) = self.propose_sender.send(propose) {
             error!("Failed to send proposal through the channel: {}", err);
         }
     }
 }
+
+impl EventProcessor for ProposalEventProcessor {
+    fn on_event(&self, event: ParsedEvent, signature: &str, slot: u64, need_check: bool) {
+        match event.event {
+            PhotonEvent::ProposeEvent(event) => self.on_propose(event, signature, slot, need_check),
+            event => {
+                if let Some(gov_event_proc) = &self.gov_event_proc {
+                    gov_event_proc.on_event(event, signature, slot, need_check);
+                }
+            }
+        }
+    }
+
+    fn on_tx_end(&self, _signature: &str) {
+        let held = self.held_propose.lock().expect("Expected held proposal lock").take();
+        if let Some(mut last) = held {
+            last.last_in_tx = true;
+            self.send_propose(last);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::sync::mpsc::unbounded_channel;
+
+    const SIGNATURE: &str =
+        "5h6xBEauJ3PK6SWCZ1PGjBvj8vDdWG3KpwATGy1ARAXFSDwt8GFXM7W5Ncn16wmqokgpiKRLuS83KUxyZyv2sUYv";
+
+    fn propose_event() -> ProposeEvent {
+        // The allowed protocols are zero padded
+        let mut protocol_id = b"onefunc".to_vec();
+        protocol_id.resize(32, 0);
+        ProposeEvent {
+            protocol_id,
+            nonce: 1,
+            dst_chain_id: 33133,
+            protocol_address: vec![1; 20],
+            function_selector: vec![1, 4, 1, 2, 3, 4],
+            params: vec![],
+            fee: 0,
+            fee_mint: None,
+        }
+    }
+
+    fn processor() -> (ProposalEventProcessor, UnboundedReceiver<Propose>) {
+        let (_, logs_receiver) = unbounded_channel();
+        let (propose_sender, propose_receiver) = unbounded_channel();
+        let processor = ProposalEventProcessor::new(
+            logs_receiver,
+            propose_sender,
+            vec!["onefunc".to_owned()],
+            None,
+        );
+        (processor, propose_receiver)
+    }
+
+    fn proposal(republish: bool) -> Propose {
+        let (mut processor, mut propose_receiver) = processor();
+        if republish {
+            processor.republish();
+        }
+        processor.on_propose(propose_event(), SIGNATURE, 7, true);
+        processor.on_tx_end(SIGNATURE);
+        propose_receiver.try_recv().expect("Expected proposal to be sent")
+    }
+
+    #[test]
+    fn test_latest_block_id() {
+        assert_eq!(proposal(false).latest_block_id.as_deref(), Some(SIGNATURE));
+        // The republished proposals never move the consumers checkpoint
+        assert_eq!(proposal(true).latest_block_id, None);
+    }
+
+    #[test]
+    fn test_last_proposal_of_tx_marked() {
+        let (processor, mut propose_receiver) = processor();
+        processor.on_propose(propose_event(), SIGNATURE, 7, true);
+        processor.on_propose(propose_event(), SIGNATURE, 7, true);
+        let first = propose_receiver.try_recv().expect("Expected first proposal to be sent");
+        assert!(!first.last_in_tx);
+        // The last one waits for the end of the transaction
+        assert!(propose_receiver.try_recv().is_err());
+
+        processor.on_tx_end(SIGNATURE);
+        let last = propose_receiver.try_recv().expect("Expected last proposal to be sent");
+        assert!(last.last_in_tx);
+        processor.on_tx_end(SIGNATURE);
+        assert!(propose_receiver.try_recv().is_err());
+    }
+}
@@ -1,17 +1,17 @@
 use crate::{
-    common::solana_logs::solana_event_listener::SolanaEventListener,
+    common::{outbox::Outbox, solana_logs::solana_event_listener::SolanaEventListener},
     watcher_app::{
-        config::WatcherConfig, rabbitmq_publisher::RabbitmqPublisher,
-        solana_logs_processor::OperationExecutedEventProcessor,
+        config::WatcherConfig, error::WatcherError, message_publisher::MessagePublisher,
+        solana_logs_processor::OperationEventProcessor,
     },
 };
-use log::info;
+use log::{error, info};
 use tokio::sync::mpsc::unbounded_channel;
 
 pub(crate) struct WatcherApp {
     solana_listener: SolanaEventListener,
-    rabbitmq_sender: RabbitmqPublisher,
-    solana_logs_proc: OperationExecutedEventProcessor,
+    message_publisher: MessagePublisher,
+    solana_logs_proc: OperationEventProcessor,
 }
 
 impl WatcherApp {
@@ -21,25 +21,36 @@ impl WatcherApp {
             return;
         };
 
-        let mut app = WatcherApp::new(config);
+        let Ok(mut app) = WatcherApp::try_new(config).map_err(|err| {
+            error!("Failed to create watcher: {}", err);
+        }) else {
+            return;
+        };
         app.execute_impl().await;
     }
 
-    fn new(config: WatcherConfig) -> WatcherApp {
+    fn try_new(config: WatcherConfig) -> Result<WatcherApp, WatcherError> {
+        let routes = config.transport.routes();
+        let outbox = Outbox::open(routes.outbox_path.as_deref(), routes.outbox_capacity)?;
         let (op_stat_sender, op_stat_receiver) = unbounded_channel();
         let (logs_sender, logs_receiver) = unbounded_channel();
 
-        WatcherApp {
+        Ok(WatcherApp {
             solana_listener: SolanaEventListener::new(config.solana, config.mongodb, logs_sender),
-            solana_logs_proc: OperationExecutedEventProcessor::new(logs_receiver, op_stat_sender),
-            rabbitmq_sender: RabbitmqPublisher::new(config.rabbitmq, op_stat_receiver),
-        }
+            solana_logs_proc: OperationEventProcessor::new(logs_receiver, op_stat_sender),
+            message_publisher: MessagePublisher::new(
+                config.transport.into_publisher(),
+                routes,
+                op_stat_receiver,
+                outbox,
+            ),
+        })
     }
 
     async fn execute_impl(&mut self) {
         tokio::select! {
             _ = self.solana_listener.listen_to_solana() => {}
-            _ = self.rabbitmq_sender.publish_to_rabbitmq() => {}
+            _ = self.message_publisher.publish() => {}
             _ = self.solana_logs_proc.execute() => {}
         }
     }
@@ -4,13 +4,14 @@ use serde::Deserialize;
 use transmitter_common::mongodb::MongodbConfig;
 
 use crate::{
-    common::{config::SolanaListenerConfig, rabbitmq::RabbitmqPublishConfig},
+    common::{config::SolanaListenerConfig, transport::PublishTransportConfig},
     watcher_app::error::WatcherError,
 };
 
 #[derive(Deserialize)]
 pub(super) struct WatcherConfig {
-    pub(super) rabbitmq: RabbitmqPublishConfig,
+    #[serde(flatten)]
+    pub(super) transport: PublishTransportConfig,
     pub(super) solana: SolanaListenerConfig,
     pub(super) mongodb: MongodbConfig,
 }
@@ -33,3 +34,44 @@ impl WatcherConfig {
         })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::common::transport::TransportConfig;
+    use config::FileFormat;
+
+    fn read(overrides: &str) -> WatcherConfig {
+        Config::builder()
+            .add_source(File::with_name("doc/watcher-config.yml"))
+            .add_source(File::from_str(overrides, FileFormat::Yaml))
+            .build()
+            .unwrap()
+            .try_deserialize()
+            .unwrap()
+    }
+
+    #[test]
+    fn test_rabbitmq_transport() {
+        let config = read("rabbitmq: { user: guest, password: guest }");
+        assert!(matches!(config.transport, TransportConfig::Rabbitmq(_)));
+        let routes = config.transport.routes();
+        assert_eq!(routes.route, "from_solana");
+        assert_eq!(routes.outbox_path.as_deref(), Some("/var/lib/photon/watcher-outbox.jsonl"));
+    }
+
+    #[test]
+    fn test_kafka_transport() {
+        let config = read(
+            "
+transport: kafka
+kafka: { brokers: localhost:9092, user: kafka, password: kafka, topic: entangle.watcher }
+",
+        );
+        let TransportConfig::Kafka(kafka) = &config.transport else {
+            panic!("Expected kafka transport");
+        };
+        assert_eq!(kafka.connect.user.as_deref(), Some("kafka"));
+        assert_eq!(config.transport.routes().route, "entangle.watcher");
+    }
+}
@@ -1,9 +1,13 @@
 use thiserror::Error;
 
+use crate::common::transport::TransportError;
+
 #[derive(Debug, Error)]
 pub(crate) enum WatcherError {
     #[error("Config error")]
     Config,
-    #[error("Rabbitmq client error")]
-    Rabbitmq(#[from] amqprs::error::Error),
+    #[error("Message transport error {0}")]
+    Transport(#[from] TransportError),
+    #[error("Outbox error {0}")]
+    Outbox(#[from] std::io::Error),
 }
@@ -0,0 +1,87 @@
+use log::{debug, error, info};
+use tokio::{select, sync::mpsc::UnboundedReceiver, time::sleep};
+
+use transmitter_common::data::TransmitterMsg;
+
+use super::error::WatcherError;
+use crate::common::{
+    outbox::Outbox,
+    transport::{PublishEvent, PublishRoutes, Publisher},
+};
+
+pub(super) struct MessagePublisher {
+    publisher: Box<dyn Publisher>,
+    route: String,
+    op_status_receiver: UnboundedReceiver<TransmitterMsg>,
+    op_status_closed: bool,
+    outbox: Outbox,
+}
+
+impl MessagePublisher {
+    pub(super) fn new(
+        publisher: Box<dyn Publisher>,
+        routes: PublishRoutes,
+        op_status_receiver: UnboundedReceiver<TransmitterMsg>,
+        outbox: Outbox,
+    ) -> MessagePublisher {
+        MessagePublisher {
+            publisher,
+            route: routes.route,
+            op_status_receiver,
+            op_status_closed: false,
+            outbox,
+        }
+    }
+
+    pub(super) async fn publish(&mut self) -> Result<(), WatcherError> {
+        self.publisher.open().await?;
+        loop {
+            self.publisher.publish(&mut self.outbox).await;
+            if self.op_status_closed && self.outbox.is_empty() {
+                return Ok(());
+            }
+            self.progress().await?;
+        }
+    }
+
+    fn enqueue_op_status(&mut self, transmitter_msg: TransmitterMsg) {
+        debug!("operation_status to be sent: {:?}", transmitter_msg);
+        let Ok(json_data) = serde_json::to_string(&transmitter_msg).map_err(|err| {
+            error!(
+                "Failed to encode operation_data message: {:?}, error: {}",
+                transmitter_msg, err
+            );
+        }) else {
+            return;
+        };
+        self.outbox.push(&self.route, json_data);
+    }
+
+    /// Waits for an operation status to be queued, a message bus event or a message to be retried,
+    /// no more operation statuses are taken while the outbox is full
+    async fn progress(&mut self) -> Result<(), WatcherError> {
+        let retry_in = self.outbox.retry_in();
+        select! {
+            op_status = self.op_status_receiver.recv(),
+                if !self.op_status_closed && !self.outbox.is_full() =>
+            {
+                match op_status {
+                    Some(op_status) => self.enqueue_op_status(op_status),
+                    None => self.op_status_closed = true,
+                }
+            },
+            event = self.publisher.next_event() => match event {
+                PublishEvent::Confirm(confirm) => {
+                    self.outbox.confirm(confirm);
+                }
+                PublishEvent::Resumed => info!("Publishing resumed"),
+                PublishEvent::Closed => {
+                    self.publisher.open().await?;
+                    self.outbox.reset_channel();
+                }
+            },
+            _ = sleep(retry_in.unwrap_or_default()), if retry_in.is_some() => {}
+        }
+        Ok(())
+    }
+}
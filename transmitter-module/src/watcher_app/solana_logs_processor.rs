@@ -1,3 +1,4 @@
+use anchor_lang::prelude::Pubkey;
 use log::{debug, error};
 use tokio::sync::{
     mpsc::{UnboundedReceiver, UnboundedSender},
@@ -5,22 +6,25 @@ use tokio::sync::{
 };
 
 use crate::common::solana_logs::{
-    event_processor::EventProcessor, solana_event_listener::LogsBunch,
+    event_processor::EventProcessor,
+    photon_events::{ParsedEvent, PhotonEvent},
+    solana_event_listener::LogsBunch,
 };
 
-use transmitter_common::data::{OpHash, ProposalExecuted};
+use transmitter_common::data::{self, OpHash, OperationStage, TransmitterMsg, TransmitterMsgImpl};
 
-pub(super) struct OperationExecutedEventProcessor {
+/// Intercepts the lifecycle events of the operations to be published
+pub(super) struct OperationEventProcessor {
     logs_receiver: Mutex<UnboundedReceiver<LogsBunch>>,
-    op_status_sender: UnboundedSender<ProposalExecuted>,
+    op_status_sender: UnboundedSender<TransmitterMsg>,
 }
 
-impl OperationExecutedEventProcessor {
+impl OperationEventProcessor {
     pub(super) fn new(
         logs_receiver: UnboundedReceiver<LogsBunch>,
-        op_status_sender: UnboundedSender<ProposalExecuted>,
-    ) -> OperationExecutedEventProcessor {
-        OperationExecutedEventProcessor {
+        op_status_sender: UnboundedSender<TransmitterMsg>,
+    ) -> OperationEventProcessor {
+        OperationEventProcessor {
             logs_receiver: Mutex::new(logs_receiver),
             op_status_sender,
         }
@@ -33,19 +37,76 @@ impl OperationExecutedEventProcessor {
     }
 }
 
-impl EventProcessor for OperationExecutedEventProcessor {
-    type Event = photon::ProposalExecuted;
-
-    fn on_event(&self, event: Self::Event, signature: &str, _slot: u64, need_check: bool) {
-        debug!("OperationExecuted status event intercepted: {:?}", event);
-        if let Err(err) = self.op_status_sender.send(ProposalExecuted {
-            need_check,
-            last_watched_block: signature.to_string(),
-            op_hash: OpHash::try_from(event.op_hash)
-                .expect("op_hash expected to be gotten from proposal_executed event"),
-            executor: event.executor,
-        }) {
-            error!("Failed to send proposal through the channel: {}", err);
+impl EventProcessor for OperationEventProcessor {
+    fn on_event(&self, event: ParsedEvent, signature: &str, slot: u64, need_check: bool) {
+        let stage = |op_hash: Vec<u8>, executor: Pubkey| {
+            Some(OperationStage {
+                tx_signature: signature.to_string(),
+                slot,
+                op_hash: to_op_hash(op_hash)?,
+                executor,
+            })
+        };
+        let msg = match event.event {
+            PhotonEvent::ProposalLoaded(e) => {
+                stage(e.op_hash, e.executor).map(TransmitterMsgImpl::ProposalLoaded)
+            }
+            PhotonEvent::ProposalApproved(e) => {
+                stage(e.op_hash, e.executor).map(TransmitterMsgImpl::ProposalApproved)
+            }
+            PhotonEvent::ProposalExecuted(e) => to_op_hash(e.op_hash).map(|op_hash| {
+                TransmitterMsgImpl::ProposalExecuted(data::ProposalExecuted {
+                    need_check,
+                    last_watched_block: signature.to_string(),
+                    op_hash,
+                    executor: e.executor,
+                    return_data: e.return_data,
+                    slot,
+                })
+            }),
+            PhotonEvent::ProposalFailed(e) => {
+                stage(e.op_hash, e.executor).map(TransmitterMsgImpl::ProposalFailed)
+            }
+            PhotonEvent::GovChangeQueued(e) => to_op_hash(e.op_hash).map(|op_hash| {
+                TransmitterMsgImpl::GovChangeQueued(data::GovChangeQueued {
+                    tx_signature: signature.to_string(),
+                    slot,
+                    change_id: e.change_id,
+                    op_hash,
+                    code: e.code,
+                    activation_time: e.activation_time,
+                })
+            }),
+            PhotonEvent::GovChangeApplied(e) => to_op_hash(e.op_hash).map(|op_hash| {
+                TransmitterMsgImpl::GovChangeApplied(data::GovChangeApplied {
+                    tx_signature: signature.to_string(),
+                    slot,
+                    change_id: e.change_id,
+                    op_hash,
+                })
+            }),
+            PhotonEvent::GovChangeCancelled(e) => to_op_hash(e.op_hash).map(|op_hash| {
+                TransmitterMsgImpl::GovChangeCancelled(data::GovChangeCancelled {
+                    tx_signature: signature.to_string(),
+                    slot,
+                    change_id: e.change_id,
+                    op_hash,
+                })
+            }),
+            _ => return,
+        };
+        let Some(msg) = msg else {
+            return;
+        };
+        debug!("Operation event intercepted: {:?}", msg);
+        if let Err(err) = self.op_status_sender.send(TransmitterMsg::new(msg, need_check)) {
+            error!("Failed to send operation event through the channel: {}", err);
         }
     }
 }
+
+fn to_op_hash(op_hash: Vec<u8>) -> Option<OpHash> {
+    OpHash::try_from(op_hash)
+        .map_err(|op_hash| error!("Unexpected op_hash length: {}", hex::encode(op_hash)))
+        .ok()
+}
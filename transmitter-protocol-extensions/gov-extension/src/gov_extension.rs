@@ -1,6 +1,6 @@
 use log::{debug, error};
 use photon::{
-    gov::{abi_decode_scheme, decode_abi_params, GovOperation},
+    gov::{abi_decode_scheme, decode_abi_params, pending_change_id_by_code, GovOperation},
     photon::ROOT,
     protocol_data::GOV_PROTOCOL_ID,
 };
@@ -48,6 +48,13 @@ impl ProtocolExtension for GovExtension {
             ExtensionError::Extension
         })?;
 
+        let change_id = pending_change_id_by_code(code, params).map_err(|err| {
+            error!("Failed to get pending change id: {}", err);
+            ExtensionError::Extension
+        })?;
+        let (pending_change_pda, _) =
+            Pubkey::find_program_address(&[ROOT, b"PENDING_GOV", &change_id], &photon::ID);
+
         let params =
             decode_abi_params(params, abi_decode_scheme(gov_operation)).map_err(|err| {
                 error!("Failed to decode abi params: {}", err);
@@ -82,13 +89,27 @@ impl ProtocolExtension for GovExtension {
             target_protocol_info_pda
         );
 
-        Ok(vec![
+        let mut accounts = vec![
             AccountMeta::new_readonly(photon::ID, false),
             AccountMeta::new(config_pda, false),
             AccountMeta::new(gov_protocol_pda, false),
             AccountMeta::new(target_protocol_info_pda, false),
             AccountMeta::new(system_program::id(), false),
-        ])
+            AccountMeta::new(pending_change_pda, false),
+        ];
+        if gov_operation == GovOperation::RemoveAllowedProtocol {
+            let rent_recipient = params
+                .get(1)
+                .and_then(|x| x.clone().into_bytes())
+                .and_then(|x| <[u8; 32]>::try_from(x).ok())
+                .ok_or_else(|| {
+                    error!("Failed to get rent recipient from decoded abi params");
+                    ExtensionError::Extension
+                })?;
+            accounts.push(AccountMeta::new(Pubkey::new_from_array(rent_recipient), false));
+        }
+
+        Ok(accounts)
     }
 
     fn sign_transaction(
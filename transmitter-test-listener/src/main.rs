@@ -88,19 +88,22 @@ impl AsyncConsumer for Consumer {
         };
         let chain_id = mdb_solana_chain_id();
 
-        self.collection
-            .update_one(
-                doc! { "direction": "from", "chain": chain_id },
-                doc! { "$set": { &self.config.key: &proposal.latest_block_id, "updated_at": get_time_ms() as i64 } },
-                UpdateOptions::builder().upsert(true).build(),
-            )
-            .await
-            .expect("Expected last_processed_block be updated");
+        // The republished proposals do not move the checkpoint
+        if let Some(latest_block_id) = &proposal.latest_block_id {
+            self.collection
+                .update_one(
+                    doc! { "direction": "from", "chain": chain_id },
+                    doc! { "$set": { &self.config.key: latest_block_id, "updated_at": get_time_ms() as i64 } },
+                    UpdateOptions::builder().upsert(true).build(),
+                )
+                .await
+                .expect("Expected last_processed_block be updated");
+        }
         let args = BasicAckArguments::new(deliver.delivery_tag(), false);
         if let Err(err) = channel.basic_ack(args).await {
             error!("Failed to do basic ack: {}", err);
         } else {
-            debug!("Propose message consumed, latest_block_id: {}", proposal.latest_block_id);
+            debug!("Propose message consumed, latest_block_id: {:?}", proposal.latest_block_id);
         }
     }
 }
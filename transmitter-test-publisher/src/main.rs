@@ -8,13 +8,13 @@ extern crate photon;
 use config::{Config, File};
 use ethabi::{ethereum_types, Address, Token, Uint};
 use libsecp256k1::sign;
-use log::{error, info};
+use log::info;
 use rand::{distributions::Alphanumeric, random, Rng, RngCore};
 use serde::Deserialize;
 use std::{env, time::Duration};
 use thiserror::Error;
 
-use transmitter_common::data::{Meta, OperationData, ProtocolId};
+use transmitter_common::data::{default_meta, OperationData, ProtocolId};
 
 use cli::Operation;
 use photon::protocol_data::GOV_PROTOCOL_ID;
@@ -55,8 +55,7 @@ pub(crate) async fn publish(config: &str, operation: &Operation, times: u64) {
 
     let dst_chain_id = photon::photon::SOLANA_CHAIN_ID;
     let protocol_address: Vec<u8> = onefunc::ID.to_bytes().to_vec();
-    let meta: &Meta =
-        b"\x01\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00";
+    let meta = default_meta();
     for nonce in 0..times {
         let mut tx_id = [0u8; 64];
         rand::thread_rng().fill_bytes(&mut tx_id);
@@ -68,7 +67,7 @@ pub(crate) async fn publish(config: &str, operation: &Operation, times: u64) {
                     ethabi::encode(&[Token::Tuple(vec![Token::Uint(Uint::from(*component))])]);
                 OperationData {
                     protocol_id,
-                    meta: *meta,
+                    meta,
                     src_block_number: 1,
                     src_chain_id: dst_chain_id,
                     dest_chain_id: dst_chain_id,
@@ -84,7 +83,7 @@ pub(crate) async fn publish(config: &str, operation: &Operation, times: u64) {
                 let function_selector: Vec<u8> = b"\x01\x0Cto_be_failed".to_vec();
                 OperationData {
                     protocol_id,
-                    meta: *meta,
+                    meta,
                     src_block_number: 1,
                     src_chain_id: dst_chain_id,
                     dest_chain_id: dst_chain_id,
@@ -100,7 +99,7 @@ pub(crate) async fn publish(config: &str, operation: &Operation, times: u64) {
                 let function_selector: Vec<u8> = b"\x01\x12init_owned_counter".to_vec();
                 OperationData {
                     protocol_id,
-                    meta: *meta,
+                    meta,
                     src_block_number: 1,
                     src_chain_id: dst_chain_id,
                     dest_chain_id: dst_chain_id,
@@ -118,7 +117,7 @@ pub(crate) async fn publish(config: &str, operation: &Operation, times: u64) {
                     ethabi::encode(&[Token::Tuple(vec![Token::Uint(Uint::from(*component))])]);
                 OperationData {
                     protocol_id,
-                    meta: *meta,
+                    meta,
                     src_block_number: 1,
                     src_chain_id: dst_chain_id,
                     dest_chain_id: dst_chain_id,
@@ -135,7 +134,7 @@ pub(crate) async fn publish(config: &str, operation: &Operation, times: u64) {
                 code_function_selector.extend(code.iter());
                 OperationData {
                     protocol_id,
-                    meta: *meta,
+                    meta,
                     src_block_number: 1,
                     src_chain_id: dst_chain_id,
                     dest_chain_id: dst_chain_id,
@@ -162,7 +161,7 @@ pub(crate) async fn publish(config: &str, operation: &Operation, times: u64) {
                 ])]);
                 OperationData {
                     protocol_id: gov_protocol_id,
-                    meta: *meta,
+                    meta,
                     src_block_number: 1,
                     src_chain_id: 33133,
                     dest_chain_id: dst_chain_id,
@@ -206,7 +205,7 @@ pub(crate) async fn publish(config: &str, operation: &Operation, times: u64) {
 
 #[cfg(test)]
 mod test {
-    use super::OperationData;
+    use super::{default_meta, OperationData};
     use crate::util::{predefined_signers, TransmitterSignature};
     use libsecp256k1::{sign, PublicKey};
     use rand::RngCore;
@@ -297,4 +296,65 @@ mod test {
         let op_hash_contract = op_data.op_hash_with_message();
         assert_eq!(op_hash_contract, op_hash_module);
     }
+
+    #[test]
+    fn test_malformed_op_data_rejected() {
+        use photon::error::CustomError;
+
+        let op_data = |meta: [u8; 32], function_selector: &[u8]| OperationData {
+            protocol_id: ProtocolId(*onefunc::onefunc::PROTOCOL_ID),
+            meta,
+            src_block_number: 1,
+            src_chain_id: photon::photon::SOLANA_CHAIN_ID,
+            dest_chain_id: photon::photon::SOLANA_CHAIN_ID,
+            nonce: 1,
+            src_op_tx_id: vec![1; 64],
+            protocol_addr: onefunc::ID.to_bytes().to_vec(),
+            function_selector: function_selector.to_vec(),
+            params: <Vec<u8>>::default(),
+            reserved: <Vec<u8>>::default(),
+        };
+        let try_from = photon::protocol_data::OperationData::try_from;
+        assert!(matches!(
+            try_from(op_data([1; 32], b"\x02\x00")),
+            Err(CustomError::InvalidSelectorType)
+        ));
+        assert!(matches!(
+            try_from(op_data([1; 32], b"\x00\x05\x01\x02\x03\x04")),
+            Err(CustomError::InvalidSelectorLength)
+        ));
+        assert!(matches!(
+            try_from(op_data([1; 32], b"\x00")),
+            Err(CustomError::InvalidSelectorLength)
+        ));
+        assert!(matches!(
+            try_from(op_data([1; 32], b"\x01\x02\xff\xfe")),
+            Err(CustomError::InvalidSelectorName)
+        ));
+        assert!(matches!(
+            try_from(op_data([2; 32], b"\x00\x04\x01\x02\x03\x04")),
+            Err(CustomError::UnsupportedMetaVersion)
+        ));
+        assert!(try_from(op_data([1; 32], b"\x00\x04\x01\x02\x03\x04")).is_ok());
+        // The meta of the proposals relayed by the transmitters is loadable as is
+        assert!(try_from(op_data(default_meta(), b"\x00\x04\x01\x02\x03\x04")).is_ok());
+    }
+
+    #[test]
+    fn test_meta_encoding() {
+        use photon::protocol_data::{Meta, META_VERSION};
+
+        let meta = Meta::new(Meta::FLAG_FAILURE_CALLBACK);
+        let bytes = meta.encode();
+        assert_eq!(bytes[30..], [Meta::FLAG_FAILURE_CALLBACK, META_VERSION]);
+        let decoded = Meta::decode(&bytes).expect("Expected meta of the supported version");
+        assert_eq!(decoded, meta);
+        assert!(decoded.has_flag(Meta::FLAG_FAILURE_CALLBACK));
+        assert!(!Meta::default().has_flag(Meta::FLAG_FAILURE_CALLBACK));
+        assert!(Meta::decode(&[0; 32]).is_err());
+        // The layout is the one the listener relayed before the typed meta
+        let mut plain = [0; 32];
+        plain[31] = 1;
+        assert_eq!(Meta::default().encode(), plain);
+    }
 }